@@ -4,7 +4,7 @@ extern crate lastfm_parse_rs;
 
 use url::Url;
 
-use lastfm_parse_rs::from_json_slice;
+use lastfm_parse_rs::{from_json_slice, Credentials};
 use lastfm_parse_rs::structs::artist::GetInfo;
 
 use async_http_client::prelude::*;
@@ -12,16 +12,13 @@ use async_http_client::HttpRequest;
 
 
 fn main() {
-    let base_url = "http://ws.audioscrobbler.com/2.0/";
+    let base_url = "https://ws.audioscrobbler.com/2.0/";
     let api_key = "INSERT_YOUR_API_KEY_HERE";
+    let credentials = Credentials::new(base_url, api_key);
     let get_info = GetInfo::request(
-        base_url,
-        api_key,
-        None,
-        None,
+        &credentials,
         "iamthemorning",
-        None,
-        Some(1),
+        Some(true),
         None,
         None,
     );
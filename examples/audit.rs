@@ -0,0 +1,63 @@
+//! Compares saved API responses against data structures and lists the gaps.
+//!
+//! Responses are expected to be laid out as `<dir>/<method>/<any name>.json`,
+//! e.g. `tests/fixtures/album.getinfo/lighthouse.json`.
+//!
+//! Run with `cargo run --example audit --features audit -- [dir]` (`tests/fixtures` by default).
+
+extern crate lastfm_parse_rs;
+
+use std::env;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use lastfm_parse_rs::audit::{audit_method, Report};
+
+
+fn audit_dir(dir: &Path, method: &str) -> io::Result<Option<Report>> {
+    let mut merged: Option<Report> = None;
+
+    let mut paths: Vec<_> = fs::read_dir(dir)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<io::Result<_>>()?;
+    paths.sort();
+
+    for path in paths.iter().filter(|p| p.extension().is_some_and(|e| e == "json")) {
+        let json = fs::read_to_string(path)?;
+        match audit_method(method, &json) {
+            Some(Ok(report)) => match merged {
+                Some(ref mut merged) => merged.merge(report),
+                None => merged = Some(report),
+            },
+            Some(Err(err)) => println!("  {}: {}", path.display(), err),
+            None => {
+                println!("  unknown method");
+                return Ok(None);
+            }
+        }
+    }
+    Ok(merged)
+}
+
+fn main() {
+    let root = env::args().nth(1).unwrap_or_else(|| "tests/fixtures".to_owned());
+
+    let mut methods: Vec<_> = fs::read_dir(&root)
+        .expect("fixtures directory can't be read")
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.is_dir())
+        .collect();
+    methods.sort();
+
+    for dir in methods {
+        let method = dir.file_name().unwrap().to_string_lossy().into_owned();
+        println!("{}", method);
+        match audit_dir(&dir, &method) {
+            Ok(Some(ref report)) if report.is_empty() => println!("  ok"),
+            Ok(Some(report)) => print!("{}", report),
+            Ok(None) => {}
+            Err(err) => println!("  {}", err),
+        }
+    }
+}
@@ -0,0 +1,201 @@
+use std::collections::BTreeSet;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use serde::ser::Serialize;
+use serde_json::{self, Value};
+use serde_ignored::{self, Path};
+
+use lastfm_type::{LastfmType, to_json_vec};
+use error::{Error, DeserializeError, Result};
+use structs::api_error::ApiError;
+use structs::{album, artist, auth, chart, geo, library, tag, track, user};
+
+// ----------------------------------------------------------------
+
+/// Gaps between a json response and the data structure it was parsed into.
+/// Paths look like `album.tracks.track[].artist`, array indices are omitted.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Report {
+    /// Json fields that are not mapped to any data structure field
+    pub unmapped: BTreeSet<String>,
+    /// Data structure fields that were not filled from json (left `None` or defaulted)
+    pub unfilled: BTreeSet<String>,
+}
+
+impl Report {
+    /// Combines reports for several responses of the same method:
+    /// field is unmapped if it was unmapped in any of responses,
+    /// and unfilled only if none of responses has filled it.
+    pub fn merge(&mut self, other: Report) {
+        self.unmapped.extend(other.unmapped);
+        self.unfilled = self.unfilled.intersection(&other.unfilled).cloned().collect();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.unmapped.is_empty() && self.unfilled.is_empty()
+    }
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        for path in &self.unmapped {
+            writeln!(f, "  unmapped json field: {}", path)?;
+        }
+        for path in &self.unfilled {
+            writeln!(f, "  unfilled struct field: {}", path)?;
+        }
+        Ok(())
+    }
+}
+
+// ----------------------------------------------------------------
+
+/// Parses json response into given data type and reports the gaps between them.
+/// Fields buried in untagged enums (like `VecOrStruct`) are not checked for being mapped.
+pub fn audit_json<'de, Lt>(json: &'de str) -> Result<Report>
+where
+    Lt: LastfmType<'de> + Serialize,
+{
+    let mut report = Report::default();
+
+    let mut deserializer = serde_json::Deserializer::from_str(json);
+    let outer: Result<Lt::Outer> = serde_ignored::deserialize(&mut deserializer, |path| {
+        report.unmapped.insert(format_path(&path));
+    }).and_then(|outer| deserializer.end().map(|_| outer))
+        .map_err(|e| Error::Deserialize(DeserializeError::from(e).with_input(json.as_bytes())));
+
+    let data: Lt = match outer {
+        Ok(outer) => outer.into(),
+        Err(err) => {
+            return match serde_json::from_str::<ApiError>(json) {
+                Ok(api_err) => Err(Error::Api(api_err)),
+                Err(_) => Err(err),
+            }
+        }
+    };
+
+    let input: Value = serde_json::from_str(json).map_err(|e| Error::Deserialize(e.into()))?;
+    let output: Value = serde_json::from_slice(&to_json_vec(&data)?).map_err(Error::Serialize)?;
+    collect_unfilled(&input, &output, "", &mut report.unfilled);
+
+    Ok(report)
+}
+
+/// Same as `audit_json()`, but picks data type by API method name (as in `RequestParams::method()`).
+/// Returns `None` for unknown methods.
+pub fn audit_method(method: &str, json: &str) -> Option<Result<Report>> {
+    macro_rules! audit_methods {
+        ($($name:expr => $data_t:ty),* $(,)*) => {
+            match method {
+                $($name => Some(audit_json::<$data_t>(json)),)*
+                _ => None,
+            }
+        }
+    }
+
+    audit_methods! {
+        "album.addtags" => album::AddTags,
+        "album.getinfo" => album::GetInfo,
+        "album.gettags" => album::GetTags,
+        "album.gettoptags" => album::GetTopTags,
+        "album.removetag" => album::RemoveTag,
+        "album.search" => album::Search,
+        "artist.addtags" => artist::AddTags,
+        "artist.getcorrection" => artist::GetCorrections,
+        "artist.getinfo" => artist::GetInfo,
+        "artist.getsimilar" => artist::GetSimilar,
+        "artist.gettags" => artist::GetTags,
+        "artist.gettopalbums" => artist::GetTopAlbums,
+        "artist.gettoptags" => artist::GetTopTags,
+        "artist.gettoptracks" => artist::GetTopTracks,
+        "artist.removetag" => artist::RemoveTag,
+        "artist.search" => artist::Search,
+        "auth.getmobilesession" => auth::GetMobileSession,
+        "auth.getsession" => auth::GetSession,
+        "auth.gettoken" => auth::GetToken,
+        "chart.gettopartists" => chart::GetTopArtists,
+        "chart.gettoptags" => chart::GetTopTags,
+        "chart.gettoptracks" => chart::GetTopTracks,
+        "geo.gettopartists" => geo::GetTopArtists,
+        "geo.gettoptracks" => geo::GetTopTracks,
+        "library.getartists" => library::GetArtists,
+        "tag.getinfo" => tag::GetInfo,
+        "tag.getsimilar" => tag::GetSimilar,
+        "tag.gettopalbums" => tag::GetTopAlbums,
+        "tag.gettopartists" => tag::GetTopArtists,
+        "tag.gettoptags" => tag::GetTopTags,
+        "tag.gettoptracks" => tag::GetTopTracks,
+        "tag.getweeklychartlist" => tag::GetWeeklyChartList,
+        "track.addtags" => track::AddTags,
+        "track.getcorrection" => track::GetCorrections,
+        "track.getinfo" => track::GetInfo,
+        "track.getsimilar" => track::GetSimilar,
+        "track.gettags" => track::GetTags,
+        "track.gettoptags" => track::GetTopTags,
+        "track.love" => track::Love,
+        "track.removetags" => track::RemoveTag,
+        "track.scrobble" => track::Scrobble,
+        "track.search" => track::Search,
+        "track.unlove" => track::Unlove,
+        "track.updatenowplaying" => track::UpdateNowPlaying,
+        "user.getfriends" => user::GetFriends,
+        "user.getinfo" => user::GetInfo,
+        "user.getlovedtracks" => user::GetLovedTracks,
+        "user.getpersonaltags" => user::GetTaggings,
+        "user.getrecenttracks" => user::GetRecentTracks,
+        "user.gettopalbums" => user::GetTopAlbums,
+        "user.gettopartists" => user::GetTopArtists,
+        "user.gettoptags" => user::GetTopTags,
+        "user.gettoptracks" => user::GetTopTracks,
+        "user.getweeklyalbumchart" => user::GetWeeklyAlbumChart,
+        "user.getweeklyartistchart" => user::GetWeeklyArtistChart,
+        "user.getweeklychartlist" => user::GetWeeklyChartList,
+        "user.getweeklytrackchart" => user::GetWeeklyTrackChart,
+    }
+}
+
+// ----------------------------------------------------------------
+
+/// Walks serialized data structure and collects paths that are missing from original json
+fn collect_unfilled(input: &Value, output: &Value, path: &str, res: &mut BTreeSet<String>) {
+    match (input, output) {
+        (Value::Object(input), Value::Object(output)) => {
+            for (key, value) in output {
+                let path = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+                match input.get(key) {
+                    Some(input) => collect_unfilled(input, value, &path, res),
+                    None => {
+                        res.insert(path);
+                    }
+                }
+            }
+        }
+        (Value::Array(input), Value::Array(output)) => {
+            let path = format!("{}[]", path);
+            for (input, output) in input.iter().zip(output) {
+                collect_unfilled(input, output, &path, res);
+            }
+        }
+        // Single object parsed as one-element list
+        (input @ Value::Object(_), Value::Array(output)) => {
+            if let Some(output) = output.first() {
+                collect_unfilled(input, output, &format!("{}[]", path), res);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn format_path(path: &Path) -> String {
+    match *path {
+        Path::Root => String::new(),
+        Path::Seq { parent, .. } => format!("{}[]", format_path(parent)),
+        Path::Map { parent, ref key } => {
+            let parent = format_path(parent);
+            if parent.is_empty() { key.clone() } else { format!("{}.{}", parent, key) }
+        }
+        Path::Some { parent } | Path::NewtypeStruct { parent } | Path::NewtypeVariant { parent } => {
+            format_path(parent)
+        }
+    }
+}
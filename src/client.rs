@@ -0,0 +1,225 @@
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use lastfm_type::{LastfmType, from_json_slice, from_json_owned, parse_api_error};
+use request::{Request, RequestBuilder, RequestParams, Format};
+use credentials::{Credentials, SessionState, NoSession, Permits};
+use transport::{Transport, HttpRequest, HttpResponse};
+use rate_limit::RateLimiter;
+use retry::RetryPolicy;
+use error::{Error, Result};
+use structs::api_error::ApiError;
+#[cfg(feature = "xml")]
+use xml::{self, from_xml_slice, from_xml_owned};
+
+// ----------------------------------------------------------------
+
+/// Default Lastfm API endpoint
+pub static LASTFM_BASE_URL: &str = "https://ws.audioscrobbler.com/2.0/";
+
+/// Ready-made API client.
+/// Holds base url and credentials, forms requests, sends them via given transport
+/// and parses responses.
+/// Optionally paces requests with a rate limiter, which can be shared with other clients.
+/// Transient failures are retried according to retry policy (no retries by default).
+#[derive(Debug)]
+pub struct Client<T, S: SessionState = NoSession> {
+    transport: T,
+    credentials: Credentials<S>,
+    limiter: Option<Arc<RateLimiter>>,
+    retry: RetryPolicy,
+    format: Format,
+}
+
+impl<T, S: SessionState> Client<T, S> {
+    /// Constructs new client with given transport and credentials
+    pub fn new(transport: T, credentials: Credentials<S>) -> Client<T, S> {
+        Client {
+            transport,
+            credentials,
+            limiter: None,
+            retry: RetryPolicy::never(),
+            format: Format::Json,
+        }
+    }
+
+    /// Makes client go through given rate limiter before each request
+    pub fn with_rate_limiter(mut self, limiter: Arc<RateLimiter>) -> Client<T, S> {
+        self.limiter = Some(limiter);
+        self
+    }
+
+    /// Sets policy for retrying requests that failed with a transient error
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Client<T, S> {
+        self.retry = retry;
+        self
+    }
+
+    /// Sets format responses are requested and parsed in
+    pub fn with_format(mut self, format: Format) -> Client<T, S> {
+        self.format = format;
+        self
+    }
+
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    pub fn credentials(&self) -> &Credentials<S> {
+        &self.credentials
+    }
+
+    pub fn rate_limiter(&self) -> Option<&RateLimiter> {
+        self.limiter.as_deref()
+    }
+
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry
+    }
+
+    pub fn format(&self) -> Format {
+        self.format
+    }
+
+    /// Constructs request object for built method parameters
+    /// using client's base url, credentials and format.
+    /// Methods that need a session key are only accepted by authenticated clients.
+    pub fn request<P, L>(&self, builder: RequestBuilder<P, L>) -> Request<'_, P>
+    where
+        P: RequestParams + Debug,
+        S: Permits<L>,
+    {
+        builder.request(&self.credentials).with_format(self.format)
+    }
+}
+
+impl<T: Transport, S: SessionState> Client<T, S> {
+    /// Sends request for given method parameters and parses the response.
+    /// Since data types are zero-copy, response body is stored to the `buffer`
+    /// and returned object borrows from it.
+    ///
+    /// Methods that need a session key can't be called by clients without one:
+    ///
+    /// ```compile_fail
+    /// # extern crate lastfm_parse_rs as lastfm;
+    /// # use lastfm::{Client, Credentials};
+    /// # use lastfm::structs::track::{Love, Params};
+    /// # use lastfm::transport::InMemoryTransport;
+    /// # fn main() {
+    /// let client = Client::new(InMemoryTransport::new(), Credentials::new("http://localhost/2.0/", "key"));
+    /// let mut buffer = Vec::new();
+    /// let _: lastfm::Result<Love> = client.call(Params::Love { artist: "cure", track: "creep" }, &mut buffer);
+    /// # }
+    /// ```
+    pub fn call<'b, L, P>(&self, params: P, buffer: &'b mut Vec<u8>) -> Result<L>
+    where
+        L: LastfmType<'b>,
+        P: RequestParams + Debug,
+        S: Permits<L>,
+    {
+        let request = self.request(RequestBuilder::<_, L>::new(&params)).to_http()?;
+        let response = self.execute(&params, &request)?;
+
+        *buffer = response.body;
+        let res = parse_response(self.format, params.method(), response.status, buffer);
+        self.observe(&res);
+        res
+    }
+
+    /// Same as `call()`, but returns owned `'static` object, so no buffer is needed.
+    /// Response body is dropped right after parsing.
+    pub fn call_owned<L, P>(&self, params: P) -> Result<L>
+    where
+        L: LastfmType<'static>,
+        P: RequestParams + Debug,
+        S: Permits<L>,
+    {
+        let request = self.request(RequestBuilder::<_, L>::new(&params)).to_http()?;
+        let response = self.execute(&params, &request)?;
+
+        let res = match self.format {
+            Format::Json => from_json_owned(&response.body),
+            #[cfg(feature = "xml")]
+            Format::Xml => from_xml_owned(&response.body),
+        }.map_err(|e| e.with_method(params.method()));
+        self.observe(&res);
+        res
+    }
+
+    /// Waits for rate limiter (if any) and sends prepared request
+    pub(crate) fn send(&self, request: &HttpRequest) -> Result<HttpResponse> {
+        if let Some(ref limiter) = self.limiter {
+            limiter.acquire(self.credentials.api_key());
+        }
+        self.transport.send(request)
+    }
+
+    /// Sends request and checks response status,
+    /// retrying transient failures according to retry policy
+    pub(crate) fn execute<P: RequestParams + ?Sized>(&self, params: &P, request: &HttpRequest) -> Result<HttpResponse> {
+        let mut attempt = 1;
+        loop {
+            let res = self.send(request).and_then(|response| check_status(self.format, response));
+            if res.is_err() {
+                self.observe(&res);
+            }
+
+            match res {
+                Err(ref err) if self.retry.should_retry(params, attempt, err) => {
+                    self.retry.wait(attempt);
+                    attempt += 1;
+                }
+                res => return res,
+            }
+        }
+    }
+}
+
+impl<T, S: SessionState> Client<T, S> {
+    /// Reports outcome of a call to rate limiter (if any)
+    pub(crate) fn observe<L>(&self, res: &Result<L>) {
+        if let Some(ref limiter) = self.limiter {
+            limiter.observe(self.credentials.api_key(), res);
+        }
+    }
+}
+
+/// Turns failed response into an error: API error if body describes one
+/// (service often reports those with 200 status), status code error for other non-2xx responses.
+/// Called on each attempt, so that retryable API errors are retried regardless of status.
+pub(crate) fn check_status(format: Format, response: HttpResponse) -> Result<HttpResponse> {
+    match api_error(format, &response.body) {
+        Some(err) => Err(Error::Api(err)),
+        None if response.is_success() => Ok(response),
+        None => Err(Error::Status(response.status)),
+    }
+}
+
+fn api_error(format: Format, body: &[u8]) -> Option<ApiError> {
+    match format {
+        Format::Json => parse_api_error(body),
+        #[cfg(feature = "xml")]
+        Format::Xml => xml::parse_api_error(body),
+    }
+}
+
+/// Parses response body, falling back to status code error
+/// if body of non-2xx response is not recognizable.
+/// Deserialization errors are tagged with given API method name.
+pub(crate) fn parse_response<'b, L>(format: Format, method: &str, status: u16, body: &'b [u8]) -> Result<L>
+where
+    L: LastfmType<'b>,
+{
+    let success = (200..300).contains(&status);
+    let res = match format {
+        Format::Json => from_json_slice(body),
+        #[cfg(feature = "xml")]
+        Format::Xml => from_xml_slice(body),
+    };
+    match res {
+        Err(Error::Deserialize(_)) if !success => Err(Error::Status(status)),
+        #[cfg(feature = "xml")]
+        Err(Error::Xml(_)) if !success => Err(Error::Status(status)),
+        res => res.map_err(|e| e.with_method(method)),
+    }
+}
@@ -0,0 +1,125 @@
+use std::fmt::Debug;
+
+use request::{Request, RequestParams};
+
+// ----------------------------------------------------------------
+
+/// Session state of `Credentials`: no session key
+#[derive(Debug, Clone)]
+pub struct NoSession;
+
+/// Session state of `Credentials`: holds session key obtained via `auth.*` methods
+#[derive(Debug, Clone)]
+pub struct Session(String);
+
+/// Describes session state of `Credentials`
+pub trait SessionState {
+    fn key(&self) -> Option<&str>;
+}
+
+impl SessionState for NoSession {
+    fn key(&self) -> Option<&str> {
+        None
+    }
+}
+
+impl SessionState for Session {
+    fn key(&self) -> Option<&str> {
+        Some(&self.0)
+    }
+}
+
+// ----------------------------------------------------------------
+
+/// Implemented for data types whose API methods don't need a session key.
+/// Implementations are generated by lastfm_t! family of macros.
+pub trait SessionFree {}
+
+/// Indicates that credentials with given session state are sufficient
+/// to call API method that returns `T`
+pub trait Permits<T> {}
+
+impl<T> Permits<T> for Session {}
+
+impl<T: SessionFree> Permits<T> for NoSession {}
+
+// ----------------------------------------------------------------
+
+/// API endpoint and credentials shared across requests.
+/// Comes in two flavors:
+/// * `Unauthenticated`: API key and optional shared secret (needed for `auth.*` methods);
+/// * `Authenticated`: API key, shared secret and session key (needed for `write` methods).
+///
+/// Requests for methods that need a session key can't be built from unauthenticated credentials.
+#[derive(Debug, Clone)]
+pub struct Credentials<S: SessionState = NoSession> {
+    base_url: String,
+    api_key: String,
+    secret: Option<String>,
+    session: S,
+}
+
+pub type Unauthenticated = Credentials<NoSession>;
+pub type Authenticated = Credentials<Session>;
+
+impl Credentials<NoSession> {
+    /// Constructs unauthenticated credentials from base url and API key
+    pub fn new(base_url: &str, api_key: &str) -> Credentials<NoSession> {
+        Credentials {
+            base_url: base_url.to_owned(),
+            api_key: api_key.to_owned(),
+            secret: None,
+            session: NoSession,
+        }
+    }
+
+    /// Sets shared secret, required for signed methods
+    pub fn with_secret(mut self, secret: &str) -> Credentials<NoSession> {
+        self.secret = Some(secret.to_owned());
+        self
+    }
+
+    /// Turns credentials into authenticated ones.
+    /// Methods that need a session key are signed, so shared secret is required as well.
+    pub fn authenticate(self, secret: &str, session: &str) -> Credentials<Session> {
+        Credentials {
+            base_url: self.base_url,
+            api_key: self.api_key,
+            secret: Some(secret.to_owned()),
+            session: Session(session.to_owned()),
+        }
+    }
+}
+
+impl<S: SessionState> Credentials<S> {
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    pub fn api_key(&self) -> &str {
+        &self.api_key
+    }
+
+    pub fn secret(&self) -> Option<&str> {
+        self.secret.as_deref()
+    }
+
+    pub fn session(&self) -> Option<&str> {
+        self.session.key()
+    }
+
+    /// Constructs request object for arbitrary method parameters.
+    /// Session key presence is checked by callers at compile time (see `Permits`).
+    pub(crate) fn request<P>(&self, params: P) -> Request<'_, P>
+    where
+        P: RequestParams + Debug,
+    {
+        Request::new(
+            &self.base_url,
+            &self.api_key,
+            self.secret(),
+            self.session(),
+            params,
+        )
+    }
+}
@@ -3,28 +3,78 @@ use std::error::Error as StdError;
 use std::result::Result as StdResult;
 
 use serde_json::error::Error as SerdeError;
+use serde_path_to_error::Error as PathError;
+use url::ParseError as UrlError;
+#[cfg(feature = "xml")]
+use roxmltree::Error as XmlError;
 
 pub use structs::api_error::ApiError;
 
 /// Error type for Last.fm API requests
-/// As of now, it can be either serde or API fail.
+/// It can be either serde, API, request construction or transport fail.
 /// Serde fails usually mean that the problem is in the library.
 #[derive(Debug)]
 pub enum Error {
-    Deserialize(SerdeError),
+    Deserialize(DeserializeError),
+    Serialize(SerdeError),
+    /// Xml response is malformed
+    #[cfg(feature = "xml")]
+    Xml(XmlError),
     Api(ApiError),
+    /// Signed method was requested without shared secret
+    MissingSecret,
+    /// Method that needs a session key was requested without one
+    MissingSession,
+    /// Base url could not be parsed
+    InvalidBaseUrl(UrlError),
+    /// Method argument or response value is not acceptable
+    InvalidParameter { name: &'static str, reason: String },
+    /// Transport failed to deliver request or receive response
+    Transport(Box<dyn StdError + Send + Sync>),
+    /// Service returned non-2xx status along with unrecognizable body
+    Status(u16),
 }
 
 impl Display for Error {
     fn fmt(&self, f: &mut Formatter) -> FmtResult {
         match *self {
-            Error::Deserialize(ref se) => {
-                write!(f, "Deserialization failed, reason: {}", se.to_string())
-            }
+            Error::Deserialize(ref de) => write!(f, "Deserialization failed, reason: {}", de),
+            Error::Serialize(ref se) => write!(f, "Serialization failed, reason: {}", se),
+            #[cfg(feature = "xml")]
+            Error::Xml(ref xe) => write!(f, "Xml parsing failed, reason: {}", xe),
             Error::Api(ref ae) => {
-                write!(f, "Lastfm API error\n")?;
+                writeln!(f, "Lastfm API error")?;
                 ae.fmt(f)
             }
+            Error::MissingSecret => write!(f, "Signed methods require shared secret"),
+            Error::MissingSession => write!(f, "Method requires session key"),
+            Error::InvalidBaseUrl(ref ue) => write!(f, "Invalid base url, reason: {}", ue),
+            Error::InvalidParameter { name, ref reason } => {
+                write!(f, "Invalid parameter '{}': {}", name, reason)
+            }
+            Error::Transport(ref te) => write!(f, "Transport failed, reason: {}", te),
+            Error::Status(status) => write!(f, "Unexpected HTTP status: {}", status),
+        }
+    }
+}
+
+impl Error {
+    /// Attaches API method name to deserialization error, other errors are returned as is
+    pub(crate) fn with_method(self, method: &str) -> Error {
+        match self {
+            Error::Deserialize(de) => Error::Deserialize(de.with_method(method)),
+            err => err,
+        }
+    }
+
+    /// Indicates whether failed request may succeed if sent again:
+    /// transient API errors, transport failures and server-side HTTP statuses
+    pub fn is_retryable(&self) -> bool {
+        match *self {
+            Error::Api(ref ae) => ae.error.is_retryable(),
+            Error::Transport(_) => true,
+            Error::Status(status) => status == 429 || status >= 500,
+            _ => false,
         }
     }
 }
@@ -32,10 +82,171 @@ impl Display for Error {
 impl StdError for Error {
     fn source(&self) -> Option<&(dyn StdError + 'static)> {
         match *self {
-            Error::Deserialize(ref se) => Some(se),
+            Error::Deserialize(ref de) => Some(de.inner()),
+            Error::Serialize(ref se) => Some(se),
+            Error::InvalidBaseUrl(ref ue) => Some(ue),
+            #[cfg(feature = "xml")]
+            Error::Xml(ref xe) => Some(xe),
+            Error::Transport(ref te) => Some(&**te),
             _ => None,
         }
     }
 }
 
 pub type Result<T> = StdResult<T, Error>;
+
+// ----------------------------------------------------------------
+
+/// Number of input bytes kept on each side of the failure position
+const SNIPPET_RADIUS: usize = 40;
+
+/// Details of a response that failed to deserialize
+#[derive(Debug)]
+pub struct DeserializeError {
+    inner: SerdeError,
+    reason: String,
+    path: Option<String>,
+    snippet: Option<String>,
+    method: Option<String>,
+    api_error: bool,
+}
+
+impl DeserializeError {
+    /// Underlying serde error
+    pub fn inner(&self) -> &SerdeError {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> SerdeError {
+        self.inner
+    }
+
+    /// Path to the failed value, e.g. `recenttracks.track[3].date.uts`
+    pub fn path(&self) -> Option<&str> {
+        self.path.as_deref()
+    }
+
+    /// Type or values the parser expected to see, e.g. `u32` or `one of ...`
+    pub fn expected(&self) -> Option<&str> {
+        let reason = self.reason();
+        let start = reason.find("expected ")? + "expected ".len();
+        Some(&reason[start..])
+    }
+
+    /// Part of the input around the failure position
+    pub fn snippet(&self) -> Option<&str> {
+        self.snippet.as_deref()
+    }
+
+    /// API method the response was requested for, when known
+    pub fn method(&self) -> Option<&str> {
+        self.method.as_deref()
+    }
+
+    /// Indicates that response was recognized as an API error, but has unexpected shape
+    pub fn is_api_error(&self) -> bool {
+        self.api_error
+    }
+
+    pub(crate) fn with_method(mut self, method: &str) -> DeserializeError {
+        self.method = Some(method.to_owned());
+        self
+    }
+
+    /// Prepends path to the value that was parsed separately, e.g. `recenttracks.track[3]`
+    pub(crate) fn with_path_prefix(mut self, prefix: &str) -> DeserializeError {
+        self.path = Some(match self.path.take() {
+            Some(ref path) if path.starts_with('[') => format!("{}{}", prefix, path),
+            Some(path) => format!("{}.{}", prefix, path),
+            None => prefix.to_owned(),
+        });
+        self
+    }
+
+    pub(crate) fn with_api_error(mut self) -> DeserializeError {
+        self.api_error = true;
+        self
+    }
+
+    /// Keeps part of the input around the position serde reported (if any)
+    pub(crate) fn with_input(mut self, input: &[u8]) -> DeserializeError {
+        if self.inner.line() == 0 {
+            return self;
+        }
+        let line_start = input
+            .split(|b| *b == b'\n')
+            .take(self.inner.line() - 1)
+            .map(|line| line.len() + 1)
+            .sum::<usize>();
+        let pos = (line_start + self.inner.column().saturating_sub(1)).min(input.len());
+
+        let mut start = pos.saturating_sub(SNIPPET_RADIUS);
+        let mut end = (pos + SNIPPET_RADIUS).min(input.len());
+        while start > 0 && input[start] & 0xC0 == 0x80 {
+            start -= 1;
+        }
+        while end < input.len() && input[end] & 0xC0 == 0x80 {
+            end += 1;
+        }
+        self.snippet = Some(String::from_utf8_lossy(&input[start..end]).into_owned());
+        self
+    }
+
+    /// Serde's message without position
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+}
+
+impl From<SerdeError> for DeserializeError {
+    fn from(inner: SerdeError) -> DeserializeError {
+        // serde_json doesn't expose the message alone, position is cut off the description instead
+        let mut reason = inner.to_string();
+        if inner.line() != 0 {
+            let suffix = format!(" at line {} column {}", inner.line(), inner.column());
+            if reason.ends_with(&suffix) {
+                let len = reason.len() - suffix.len();
+                reason.truncate(len);
+            }
+        }
+        DeserializeError {
+            inner,
+            reason,
+            path: None,
+            snippet: None,
+            method: None,
+            api_error: false,
+        }
+    }
+}
+
+impl From<PathError<SerdeError>> for DeserializeError {
+    fn from(err: PathError<SerdeError>) -> DeserializeError {
+        let path = err.path().to_string();
+        let mut res = DeserializeError::from(err.into_inner());
+        if path != "." {
+            res.path = Some(path);
+        }
+        res
+    }
+}
+
+impl Display for DeserializeError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        if let Some(ref method) = self.method {
+            write!(f, "{}: ", method)?;
+        }
+        if self.api_error {
+            write!(f, "malformed API error: ")?;
+        }
+        if let Some(ref path) = self.path {
+            write!(f, "{}: ", path)?;
+        }
+        write!(f, "{}", self.inner)?;
+        if let Some(ref snippet) = self.snippet {
+            write!(f, ", near `{}`", snippet)?;
+        }
+        Ok(())
+    }
+}
+
@@ -1,14 +1,21 @@
-use std::marker::Sized;
+use std::cell::Cell;
+use std::marker::{PhantomData, Sized};
 use std::convert::Into;
-use std::fmt::Debug;
+use std::fmt::{Debug, Formatter, Result as FmtResult};
+use std::result::Result as StdResult;
 
-use serde::de::Deserialize;
-use serde_json;
+use serde::de::{Deserialize, DeserializeSeed, Deserializer, Visitor, MapAccess, IgnoredAny, Error as DeError};
+use serde::de::value::{BorrowedStrDeserializer, MapAccessDeserializer, StringDeserializer};
+use serde::ser::{Serialize, Serializer, SerializeMap};
+use serde_json::{self, de::Read};
+use serde_path_to_error as path_to_error;
 
-use error::{Error, Result};
-use structs::api_error::ApiError;
+use error::{Error, DeserializeError, Result};
+use structs::api_error::{ApiError, ApiErrorKind};
+use structs::common::with_clean_shape;
 
-pub use request::{Request, RequestParams};
+pub use request::{Request, RequestParams, RequestBuilder, IntoArg};
+pub use credentials::{Credentials, SessionState, SessionFree, Permits};
 
 // ----------------------------------------------------------------
 
@@ -25,50 +32,245 @@ where
     Self: Sized + Deserialize<'de>,
 {
     type Outer: Deserialize<'de> + Debug + Into<Self>;
+
+    /// Name of the wrapping object's only field, if there's one
+    const WRAPPER: Option<&'static str> = None;
 }
 
 // ----------------------------------------------------------------
 
-macro_rules! from_json_impl {
-    ($defn:path, $src:expr, $dst:ty) => {{
-        let res: Result<$dst> = $defn($src).map_err(|e| Error::Deserialize(e));
-        if res.is_err() {
-            let err_res: Result<ApiError> =
-                $defn($src).map_err(|e| Error::Deserialize(e));
-
-            return match err_res {
-                Ok(api_err) => Err(Error::Api(api_err)),
-                Err(_) => Err(res.unwrap_err()),
-            };
+/// Parses either API error or given data type, depending on whether json has top-level `error` key.
+/// Both are picked up in a single pass, see `ApiOr`.
+fn from_json_impl<'de, R, Lt>(deserializer: serde_json::Deserializer<R>, input: &[u8]) -> Result<Lt>
+where
+    R: Read<'de>,
+    Lt: LastfmType<'de>,
+{
+    let api = Cell::new(false);
+    match deserialize(deserializer, input, ApiOr::<Lt::Outer>::new(&api)) {
+        Ok(Ok(outer)) => Ok(outer.into()),
+        Ok(Err(err)) => Err(Error::Api(err)),
+        Err(e) if api.get() => Err(Error::Deserialize(e.with_api_error())),
+        Err(e) => Err(Error::Deserialize(e)),
+    }
+}
+
+/// Extracts API error from json response, if it is one.
+/// The rest of the response is skipped over without being parsed.
+pub(crate) fn parse_api_error(json: &[u8]) -> Option<ApiError> {
+    let api = Cell::new(false);
+    match deserialize(serde_json::Deserializer::from_slice(json), json, ApiOr::<IgnoredAny>::new(&api)) {
+        Ok(Err(err)) => Some(err),
+        _ => None,
+    }
+}
+
+/// Deserializes value, keeping track of the path and input around the failure
+fn deserialize<'de, R, T>(mut deserializer: serde_json::Deserializer<R>, input: &[u8], seed: T) -> StdResult<T::Value, DeserializeError>
+where
+    R: Read<'de>,
+    T: DeserializeSeed<'de>,
+{
+    let mut track = path_to_error::Track::new();
+    let value = seed
+        .deserialize(path_to_error::Deserializer::new(&mut deserializer, &mut track))
+        .map_err(|e| DeserializeError::from(path_to_error::Error::new(track.path(), e)).with_input(input))?;
+    deserializer.end().map_err(|e| DeserializeError::from(e).with_input(input))?;
+    Ok(value)
+}
+
+/// Outer json object of a response: either API error or data type `T`.
+/// Top-level `error` and `message` keys are taken out of the object as it is read,
+/// the rest is handed over to `T`. `api` flag is raised if API error itself is malformed.
+struct ApiOr<'a, T> {
+    api: &'a Cell<bool>,
+    phantom: PhantomData<T>,
+}
+
+impl<'a, T> ApiOr<'a, T> {
+    fn new(api: &'a Cell<bool>) -> ApiOr<'a, T> {
+        ApiOr { api, phantom: PhantomData }
+    }
+}
+
+impl<'de, 'a, T: Deserialize<'de>> DeserializeSeed<'de> for ApiOr<'a, T> {
+    type Value = StdResult<T, ApiError>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> StdResult<Self::Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de, 'a, T: Deserialize<'de>> Visitor<'de> for ApiOr<'a, T> {
+    type Value = StdResult<T, ApiError>;
+
+    fn expecting(&self, f: &mut Formatter) -> FmtResult {
+        f.write_str("json object")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> StdResult<Self::Value, A::Error> {
+        let mut map = WithoutError { map, api: self.api, error: None, message: None };
+        let data = T::deserialize(MapAccessDeserializer::new(&mut map));
+
+        match (map.error, map.message) {
+            (Some(error), Some(message)) => Ok(Err(ApiError { error, message })),
+            (Some(_), None) => {
+                self.api.set(true);
+                Err(A::Error::missing_field("message"))
+            }
+            (None, _) => data.map(Ok),
         }
-        Ok(res.unwrap().into())
-    }}
+    }
+}
+
+/// Map access that skips over (and keeps) API error fields
+struct WithoutError<'a, A> {
+    map: A,
+    api: &'a Cell<bool>,
+    error: Option<ApiErrorKind>,
+    message: Option<String>,
+}
+
+impl<'a, A> WithoutError<'a, A> {
+    fn flag<E>(&self, err: E) -> E {
+        self.api.set(true);
+        err
+    }
+}
+
+impl<'de, 'a, A: MapAccess<'de>> MapAccess<'de> for WithoutError<'a, A> {
+    type Error = A::Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> StdResult<Option<K::Value>, A::Error> {
+        loop {
+            match self.map.next_key()? {
+                Some(Key::Error) => self.error = Some(self.map.next_value().map_err(|e| self.flag(e))?),
+                Some(Key::Message) => self.message = Some(self.map.next_value().map_err(|e| self.flag(e))?),
+                Some(Key::Borrowed(key)) => return seed.deserialize(BorrowedStrDeserializer::new(key)).map(Some),
+                Some(Key::Owned(key)) => return seed.deserialize(StringDeserializer::new(key)).map(Some),
+                None => return Ok(None),
+            }
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> StdResult<V::Value, A::Error> {
+        self.map.next_value_seed(seed)
+    }
+}
+
+/// Top-level key of a response object
+enum Key<'de> {
+    Error,
+    Message,
+    Borrowed(&'de str),
+    Owned(String),
+}
+
+impl<'de> Deserialize<'de> for Key<'de> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> StdResult<Key<'de>, D::Error> {
+        struct KeyVisitor;
+
+        impl<'de> Visitor<'de> for KeyVisitor {
+            type Value = Key<'de>;
+
+            fn expecting(&self, f: &mut Formatter) -> FmtResult {
+                f.write_str("object key")
+            }
+
+            fn visit_borrowed_str<E: DeError>(self, key: &'de str) -> StdResult<Key<'de>, E> {
+                Ok(match key {
+                    "error" => Key::Error,
+                    "message" => Key::Message,
+                    key => Key::Borrowed(key),
+                })
+            }
+
+            fn visit_str<E: DeError>(self, key: &str) -> StdResult<Key<'de>, E> {
+                Ok(match key {
+                    "error" => Key::Error,
+                    "message" => Key::Message,
+                    key => Key::Owned(key.to_owned()),
+                })
+            }
+        }
+
+        deserializer.deserialize_str(KeyVisitor)
+    }
 }
 
 /// Parses given data type from json string slice (zero-copy)
-/// Beware that it will fail in case source contains escape sequences,
-/// as serde is currently unable to decode them inplace.
-/// Current solution is to unescape input manually beforehand.
-/// See https://github.com/serde-rs/json/issues/318 and tests/common/mod.rs for more details.
+/// Strings are borrowed from the source, except for the ones containing escape sequences:
+/// those are unescaped into owned copies (see `Cow` fields of data types).
 pub fn from_json_str<'de, Lt: LastfmType<'de>>(json: &'de str) -> Result<Lt> {
-    from_json_impl!(serde_json::from_str, &json, Lt::Outer)
+    from_json_impl(serde_json::Deserializer::from_str(json), json.as_bytes())
 }
 
 /// Parses given data type from json byte slice (zero-copy)
-/// Beware that it will fail in case source contains escape sequences,
-/// as serde is currently unable to decode them inplace.
-/// Current solution is to unescape input manually beforehand.
-/// See https://github.com/serde-rs/json/issues/318 and tests/common/mod.rs for more details.
+/// Strings are borrowed from the source, except for the ones containing escape sequences:
+/// those are unescaped into owned copies (see `Cow` fields of data types).
 pub fn from_json_slice<'de, Lt: LastfmType<'de>>(json: &'de [u8]) -> Result<Lt> {
-    from_json_impl!(serde_json::from_slice, &json, Lt::Outer)
+    from_json_impl(serde_json::Deserializer::from_slice(json), json)
+}
+
+/// Parses given data type from json string or byte slice into an owned `'static` object,
+/// so that the source can be dropped right away (all strings are copied).
+pub fn from_json_owned<J, Lt>(json: J) -> Result<Lt>
+where
+    J: AsRef<[u8]>,
+    Lt: LastfmType<'static>,
+{
+    // Reader-based deserializer never borrows from the source
+    let json = json.as_ref();
+    from_json_impl(serde_json::Deserializer::from_reader(json), json)
+}
+
+/// Borrowing counterpart of a wrapper object, used for serialization
+struct Wrapped<'a, T: 'a>(Option<&'static str>, &'a T);
+
+impl<'a, T: Serialize> Serialize for Wrapped<'a, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> ::std::result::Result<S::Ok, S::Error> {
+        match self.0 {
+            Some(name) => {
+                let mut map = serializer.serialize_map(Some(1))?;
+                map.serialize_entry(name, self.1)?;
+                map.end()
+            }
+            None => self.1.serialize(serializer),
+        }
+    }
+}
+
+/// Serializes given data type into json string of the same shape Lastfm API returns,
+/// including the wrapper object, so that it can be parsed back with `from_json_str()`
+pub fn to_json_string<'de, Lt: LastfmType<'de> + Serialize>(data: &Lt) -> Result<String> {
+    serde_json::to_string(&Wrapped(Lt::WRAPPER, data)).map_err(Error::Serialize)
+}
+
+/// Same as `to_json_string()`, but produces a byte vector
+pub fn to_json_vec<'de, Lt: LastfmType<'de> + Serialize>(data: &Lt) -> Result<Vec<u8>> {
+    serde_json::to_vec(&Wrapped(Lt::WRAPPER, data)).map_err(Error::Serialize)
+}
+
+/// Serializes any data type (or its part) into "clean" json: without wrapper object
+/// and with numeric values written as numbers, not strings.
+/// Clean json is meant to be consumed by third parties and can't be parsed back.
+pub fn to_clean_json_string<T: Serialize>(data: &T) -> Result<String> {
+    with_clean_shape(|| serde_json::to_string(data)).map_err(Error::Serialize)
+}
+
+/// Same as `to_clean_json_string()`, but produces a `serde_json::Value`
+pub fn to_clean_json_value<T: Serialize>(data: &T) -> Result<serde_json::Value> {
+    with_clean_shape(|| serde_json::to_value(data)).map_err(Error::Serialize)
 }
 
 // ----------------------------------------------------------------
 
 /// This macro is used to define top-level requestable Lastfm data structure.
 /// For given Deserialize + Debug struct specifies
-/// a wrapper (see LastfmType trait), conversions
-/// and request() function with API method agruments.
+/// a wrapper (see LastfmType trait), conversions,
+/// request() function with API method agruments and builder() function (see request_t! macro).
+/// Method arguments are split into two lists: required and optional ones.
+/// Optional arguments are specified without `Option<>`.
 /// Following should be included in order to use this macro:
 /// ```
 /// use std::convert::Into;
@@ -78,41 +280,34 @@ macro_rules! lastfm_t {
     (
         $name:ident, $data_t:ident, $wrapper_name:ident,
         $params_t:ident, $params_variant:ident,
-        [$($param_key:ident: $param_t:ty),*]
+        [$($req_key:ident: $req_t:ty),*],
+        [$($opt_key:ident: $opt_t:ty),*]
+        $(, $access:ident)*
     ) => {
-        #[derive(Deserialize, Debug)]
+        #[derive(Deserialize, Serialize, Debug)]
         pub struct $wrapper_name<'dt> {
             #[serde(borrow)]
             $name: $data_t<'dt>,
         }
 
-        impl<'dt> Into<$data_t<'dt>> for $wrapper_name<'dt> {
-            fn into(self) -> $data_t<'dt> {
-                self.$name
+        impl<'dt> From<$wrapper_name<'dt>> for $data_t<'dt> {
+            fn from(wrapper: $wrapper_name<'dt>) -> $data_t<'dt> {
+                wrapper.$name
             }
         }
 
         impl<'dt> LastfmType<'dt> for $data_t<'dt> {
             type Outer = $wrapper_name<'dt>;
-        }
 
-        impl<'dt> $data_t<'dt> {
-            pub fn request<'rq>(
-                base_url: &'rq str,
-                api_key: &'rq str,
-                secret: Option<&'rq str>,
-                session: Option<&'rq str>,
-                $($param_key: $param_t,)*
-            ) -> Request<'rq, $params_t<'rq>> {
-                Request {
-                    base_url: base_url,
-                    api_key: api_key,
-                    secret: secret,
-                    session: session,
-                    params: $params_t::$params_variant { $($param_key: $param_key,)* },
-                }
-            }
+            const WRAPPER: Option<&'static str> = Some(stringify!($name));
         }
+
+        request_t!(
+            $data_t, $params_t, $params_variant,
+            [$($req_key: $req_t),*],
+            [$($opt_key: $opt_t),*]
+            $(, $access)*
+        );
     }
 }
 
@@ -120,29 +315,20 @@ macro_rules! lastfm_t {
 macro_rules! unwrapped_lastfm_t {
     (
         $data_t:ident, $params_t:ident, $params_variant:ident,
-        [$($param_key:ident: $param_t:ty),*]
+        [$($req_key:ident: $req_t:ty),*],
+        [$($opt_key:ident: $opt_t:ty),*]
+        $(, $access:ident)*
     ) => {
         impl<'dt> LastfmType<'dt> for $data_t<'dt> {
             type Outer = Self;
         }
 
-        impl<'dt> $data_t<'dt> {
-            pub fn request<'rq>(
-                base_url: &'rq str,
-                api_key: &'rq str,
-                secret: Option<&'rq str>,
-                session: Option<&'rq str>,
-                $($param_key: $param_t,)*
-            ) -> Request<'rq, $params_t<'rq>> {
-                Request {
-                    base_url: base_url,
-                    api_key: api_key,
-                    secret: secret,
-                    session: session,
-                    params: $params_t::$params_variant { $($param_key: $param_key,)* },
-                }
-            }
-        }
+        request_t!(
+            $data_t, $params_t, $params_variant,
+            [$($req_key: $req_t),*],
+            [$($opt_key: $opt_t),*]
+            $(, $access)*
+        );
     }
 }
 
@@ -150,21 +336,107 @@ macro_rules! unwrapped_lastfm_t {
 macro_rules! empty_lastfm_t {
     (
         $data_t:ident, $params_t:ident, $params_variant:ident,
-        [$($param_key:ident: $param_t:ty),*]
+        [$($req_key:ident: $req_t:ty),*],
+        [$($opt_key:ident: $opt_t:ty),*]
+        $(, $access:ident)*
     ) => {
-        #[derive(Deserialize, Debug)]
+        #[derive(Deserialize, Serialize, Debug)]
         pub struct $data_t<'dt> {
             #[serde(skip)]
             phantom: PhantomData<&'dt ()>,
         }
 
+        owned_t!($data_t { phantom });
+
         unwrapped_lastfm_t!(
             $data_t, $params_t, $params_variant,
-            [$($param_key: $param_t),*]
+            [$($req_key: $req_t),*],
+            [$($opt_key: $opt_t),*]
+            $(, $access)*
         );
     }
 }
 
+/// Generates request construction functions for given data type:
+/// * request() takes credentials and all method arguments positionally,
+///   optional ones wrapped in `Option`, required ones are converted with `Into`
+///   (so they can be declared as `impl Into<ArtistIdent<'rq>>` and alike);
+/// * builder() takes required arguments only and returns `RequestBuilder`
+///   with a setter for each optional argument (see `IntoArg` for accepted values).
+///
+/// Methods that need a session key are marked with trailing `session` flag:
+/// requests for them can only be built from authenticated credentials.
+/// All the others implement `SessionFree` marker trait.
+#[macro_export]
+macro_rules! request_t {
+    (
+        $data_t:ident, $params_t:ident, $params_variant:ident,
+        [$($req_key:ident: $req_t:ty),*],
+        [$($opt_key:ident: $opt_t:ty),*]
+    ) => {
+        impl<'dt> SessionFree for $data_t<'dt> {}
+
+        request_t!(
+            @impl $data_t, $params_t, $params_variant,
+            [$($req_key: $req_t),*],
+            [$($opt_key: $opt_t),*]
+        );
+    };
+    (
+        $data_t:ident, $params_t:ident, $params_variant:ident,
+        [$($req_key:ident: $req_t:ty),*],
+        [$($opt_key:ident: $opt_t:ty),*],
+        session
+    ) => {
+        request_t!(
+            @impl $data_t, $params_t, $params_variant,
+            [$($req_key: $req_t),*],
+            [$($opt_key: $opt_t),*]
+        );
+    };
+    (
+        @impl $data_t:ident, $params_t:ident, $params_variant:ident,
+        [$($req_key:ident: $req_t:ty),*],
+        [$($opt_key:ident: $opt_t:ty),*]
+    ) => {
+        impl<'dt> $data_t<'dt> {
+            #[allow(clippy::useless_conversion, clippy::too_many_arguments)]
+            pub fn request<'rq, S>(
+                credentials: &'rq Credentials<S>,
+                $($req_key: $req_t,)*
+                $($opt_key: Option<$opt_t>,)*
+            ) -> Request<'rq, $params_t<'rq>>
+            where
+                S: SessionState + Permits<$data_t<'static>>,
+            {
+                credentials.request($params_t::$params_variant { $($req_key: $req_key.into(),)* $($opt_key,)* })
+            }
+
+            #[allow(clippy::useless_conversion)]
+            pub fn builder<'rq>(
+                $($req_key: $req_t),*
+            ) -> RequestBuilder<$params_t<'rq>, $data_t<'static>> {
+                RequestBuilder::new($params_t::$params_variant {
+                    $($req_key: $req_key.into(),)*
+                    $($opt_key: None,)*
+                })
+            }
+        }
+
+        impl<'rq> RequestBuilder<$params_t<'rq>, $data_t<'static>> {
+            $(
+                #[allow(irrefutable_let_patterns)]
+                pub fn $opt_key<V: IntoArg<$opt_t>>(mut self, $opt_key: V) -> Self {
+                    if let $params_t::$params_variant { $opt_key: ref mut value, .. } = self.params {
+                        *value = Some($opt_key.into_arg());
+                    }
+                    self
+                }
+            )*
+        }
+    };
+}
+
 // ----------------------------------------------------------------
 
 /// Generates lastfm_t wrapper over an opensearch object
@@ -174,33 +446,42 @@ macro_rules! opensearch_t {
         $name:ident, $data_name:ident, $wrapper_name:ident,
         $result:ident, $result_t:ident,
         $params_t:ident, $params_variant:ident,
-        [$($param_key:ident: $param_t:ty),*]
+        [$($req_key:ident: $req_t:ty),*],
+        [$($opt_key:ident: $opt_t:ty),*]
+        $(, $access:ident)*
     ) => {
-        #[derive(Deserialize, Debug)]
+        #[derive(Deserialize, Serialize, Debug)]
         pub struct $data_name<'dt> {
             #[serde(rename="opensearch:Query")]
             #[serde(borrow)]
             pub query: SearchQuery<'dt>,
             #[serde(rename="opensearch:totalResults")]
             #[serde(deserialize_with="str_to_option")]
+            #[serde(serialize_with="option_to_str")]
             pub total_results: Option<u32>,
             #[serde(rename="opensearch:startIndex")]
             #[serde(deserialize_with="str_to_option")]
+            #[serde(serialize_with="option_to_str")]
             pub start_index: Option<u32>,
             #[serde(rename="opensearch:itemsPerPage")]
             #[serde(deserialize_with="str_to_option")]
+            #[serde(serialize_with="option_to_str")]
             pub iterms_per_page: Option<u32>,
             #[serde(borrow)]
             pub $result: Option<$result_t<'dt>>,
         }
 
+        owned_t!($data_name { query, total_results, start_index, iterms_per_page, $result });
+
         lastfm_t!(
             $name,
             $data_name,
             $wrapper_name,
             $params_t,
             $params_variant,
-            [ $($param_key: $param_t),* ]
+            [$($req_key: $req_t),*],
+            [$($opt_key: $opt_t),*]
+            $(, $access)*
         );
     }
 }
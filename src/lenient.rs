@@ -0,0 +1,396 @@
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::mem;
+
+use serde::de::{Error as SerdeError, Expected, Unexpected};
+use serde_json::{self, Value, Map};
+use serde_path_to_error::{self as path_to_error, Segment};
+
+use lastfm_type::{LastfmType, from_json_str, from_json_slice};
+use error::{Error, Result};
+
+// ----------------------------------------------------------------
+
+/// Upper bound of fix attempts made for a single response,
+/// so that completely unrelated json doesn't take forever to give up on.
+/// Fixes are shared between items of the same array (see `propagate()`),
+/// so the bound doesn't depend on the number of items.
+const MAX_FIXES: usize = 64;
+
+/// Data parsed in lenient mode along with the list of schema mismatches that were worked around
+#[derive(Debug)]
+pub struct Lenient<T> {
+    pub data: T,
+    pub warnings: Vec<Warning>,
+}
+
+/// Describes a value that didn't match data structure and how it was dealt with
+#[derive(Debug, Clone, PartialEq)]
+pub struct Warning {
+    /// Path to the value in the original json, e.g. `recenttracks.track[0].date.uts`
+    pub path: String,
+    /// Parser's complaint about the original value
+    pub reason: String,
+    pub fix: Fix,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Fix {
+    /// Single object was wrapped into an array
+    WrappedInArray,
+    /// Value was replaced with given one (converted to expected type, `null` or default)
+    Replaced(Value),
+    /// Missing field was added with given value
+    Inserted(Value),
+}
+
+impl Display for Warning {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self.fix {
+            Fix::WrappedInArray => write!(f, "{}: {}, wrapped into array", self.path, self.reason),
+            Fix::Replaced(ref value) => write!(f, "{}: {}, replaced with {}", self.path, self.reason, value),
+            Fix::Inserted(ref value) => write!(f, "{}: {}, inserted {}", self.path, self.reason, value),
+        }
+    }
+}
+
+// ----------------------------------------------------------------
+
+/// Parses given data type from json string slice, working around schema mismatches:
+/// * single objects in place of arrays are wrapped into arrays;
+/// * numbers and numeric strings are converted to each other when needed;
+/// * other unparseable or missing values are replaced with `null` (so that optional fields
+///   become `None`) or, failing that, with defaults like `"0"` and `""`.
+///
+/// Fix found for an item of an array is applied to the same field of the other items right away,
+/// so that a mismatch repeated in every item of a page is dealt with in one go.
+///
+/// Each fix is reported as a warning. Well-formed responses are parsed zero-copy,
+/// fixed ones are parsed from an intermediate `serde_json::Value`, so all strings are owned.
+pub fn from_json_str_lenient<'de, Lt: LastfmType<'de>>(json: &'de str) -> Result<Lenient<Lt>> {
+    match from_json_str(json) {
+        Err(Error::Deserialize(_)) => from_value_lenient(serde_json::from_str(json).map_err(|e| Error::Deserialize(e.into()))?),
+        res => res.map(|data| Lenient { data, warnings: Vec::new() }),
+    }
+}
+
+/// Parses given data type from json byte slice in lenient mode (see `from_json_str_lenient()`)
+pub fn from_json_slice_lenient<'de, Lt: LastfmType<'de>>(json: &'de [u8]) -> Result<Lenient<Lt>> {
+    match from_json_slice(json) {
+        Err(Error::Deserialize(_)) => from_value_lenient(serde_json::from_slice(json).map_err(|e| Error::Deserialize(e.into()))?),
+        res => res.map(|data| Lenient { data, warnings: Vec::new() }),
+    }
+}
+
+/// Fix attempts made for a single value
+struct Attempt {
+    path: String,
+    original: Option<Value>,
+    next: usize,
+}
+
+fn from_value_lenient<'de, Lt: LastfmType<'de>>(mut value: Value) -> Result<Lenient<Lt>> {
+    let mut warnings: Vec<Warning> = Vec::new();
+    let mut attempts: Vec<Attempt> = Vec::new();
+    let mut fixes = 0;
+
+    loop {
+        let err = match path_to_error::deserialize::<_, Lt::Outer>(value.clone()) {
+            Ok(outer) => return Ok(Lenient { data: outer.into(), warnings }),
+            Err(err) => err,
+        };
+        if fixes == MAX_FIXES {
+            return Err(Error::Deserialize(err.into()));
+        }
+        fixes += 1;
+
+        let reason = err.inner().to_string();
+        let mut segments: Vec<Segment> = err.path().iter().cloned().collect();
+        if let Some(field) = missing_field(&reason) {
+            segments.push(Segment::Map { key: field.to_owned() });
+        }
+        let path = format_path(&segments);
+
+        let idx = match attempts.iter().position(|a| a.path == path) {
+            Some(idx) => idx,
+            None => {
+                let original = match lookup(&mut value, &segments) {
+                    Some(Lookup::Found(target)) => Some(target.clone()),
+                    Some(Lookup::Missing(..)) => None,
+                    None => return Err(Error::Deserialize(err.into())),
+                };
+                attempts.push(Attempt { path: path.clone(), original, next: 0 });
+                attempts.len() - 1
+            }
+        };
+        let attempt = &mut attempts[idx];
+
+        let fix = match lookup(&mut value, &segments) {
+            Some(Lookup::Found(target)) if target.is_object() && reason.contains("expected a sequence") => {
+                let object = target.take();
+                *target = Value::Array(vec![object]);
+                Fix::WrappedInArray
+            }
+            Some(Lookup::Found(target)) => match replacement(attempt) {
+                Some(replacement) => {
+                    *target = replacement.clone();
+                    Fix::Replaced(replacement)
+                }
+                None => return Err(Error::Deserialize(err.into())),
+            },
+            Some(Lookup::Missing(parent, key)) => match replacement(attempt) {
+                Some(replacement) => {
+                    parent.insert(key, replacement.clone());
+                    Fix::Replaced(replacement)
+                }
+                None => return Err(Error::Deserialize(err.into())),
+            },
+            None => return Err(Error::Deserialize(err.into())),
+        };
+
+        // Value that was missing originally is reported as inserted, whatever it took to fix it
+        let fix = match (attempt.original.is_none(), fix) {
+            (true, Fix::Replaced(value)) => Fix::Inserted(value),
+            (_, fix) => fix,
+        };
+        let original = attempt.original.clone();
+        warn(&mut warnings, path, reason.clone(), fix.clone());
+        propagate(&mut value, &segments, original.as_ref(), &reason, &fix, &mut attempts, &mut warnings);
+    }
+}
+
+fn warn(warnings: &mut Vec<Warning>, path: String, reason: String, fix: Fix) {
+    match warnings.iter_mut().find(|w| w.path == path) {
+        Some(warning) => warning.fix = fix,
+        None => warnings.push(Warning { path, reason, fix }),
+    }
+}
+
+/// Applies fix made at given path to the same path in all other items of enclosing arrays,
+/// as long as their original values are broken the same way: missing, an object in place of array,
+/// equal to the fixed value or, for type mismatches, of the same type (converted individually then).
+/// Items it doesn't suit are left alone and get fixed on their own, if parser trips over them.
+fn propagate(
+    value: &mut Value,
+    segments: &[Segment],
+    original: Option<&Value>,
+    reason: &str,
+    fix: &Fix,
+    attempts: &mut Vec<Attempt>,
+    warnings: &mut Vec<Warning>,
+) {
+    let mut siblings = Vec::new();
+    if segments.iter().any(|s| matches!(s, Segment::Seq { .. })) {
+        collect_siblings(value, segments, &mut Vec::new(), &mut siblings);
+    }
+
+    let own = format_path(segments);
+    for sibling in siblings {
+        let path = format_path(&sibling);
+        if path == own {
+            continue;
+        }
+        let idx = match attempts.iter().position(|a| a.path == path) {
+            Some(idx) => idx,
+            None => {
+                let original = match lookup(value, &sibling) {
+                    Some(Lookup::Found(target)) => Some(target.clone()),
+                    _ => None,
+                };
+                attempts.push(Attempt { path: path.clone(), original, next: 0 });
+                attempts.len() - 1
+            }
+        };
+        let sibling_original = attempts[idx].original.as_ref();
+
+        let (reason, fix) = match (fix, original, sibling_original) {
+            (Fix::Inserted(replacement), None, None) => (reason.to_owned(), Fix::Inserted(replacement.clone())),
+            (Fix::WrappedInArray, _, Some(&Value::Object(_))) => (reason.to_owned(), Fix::WrappedInArray),
+            (Fix::Replaced(replacement), Some(original), Some(sibling_original)) => {
+                if original == sibling_original {
+                    (reason.to_owned(), Fix::Replaced(replacement.clone()))
+                } else {
+                    match retyped(reason, original, sibling_original, replacement) {
+                        Some(retyped) => retyped,
+                        None => continue,
+                    }
+                }
+            }
+            _ => continue,
+        };
+
+        let applied = match (lookup(value, &sibling), &fix) {
+            (Some(Lookup::Found(target)), Fix::WrappedInArray) if target.is_object() => {
+                let object = target.take();
+                *target = Value::Array(vec![object]);
+                true
+            }
+            (Some(Lookup::Found(target)), Fix::Replaced(replacement))
+            | (Some(Lookup::Found(target)), Fix::Inserted(replacement)) => {
+                *target = replacement.clone();
+                true
+            }
+            (Some(Lookup::Missing(parent, key)), Fix::Inserted(replacement)) => {
+                parent.insert(key, replacement.clone());
+                true
+            }
+            _ => false,
+        };
+        if applied {
+            warn(warnings, path, reason, fix);
+        }
+    }
+}
+
+/// Adapts type mismatch fix to a sibling value of the same type, but different content:
+/// conversion is redone for sibling's own value, parser's complaint is reworded accordingly
+fn retyped(reason: &str, original: &Value, sibling: &Value, replacement: &Value) -> Option<(String, Fix)> {
+    if mem::discriminant(original) != mem::discriminant(sibling) {
+        return None;
+    }
+    let expected = reason.strip_prefix("invalid type: ")?.split(", expected ").nth(1)?;
+
+    let replacement = match conversion(original) {
+        Some(ref converted) if converted == replacement => conversion(sibling)?,
+        _ => replacement.clone(),
+    };
+    let reason = serde_json::Error::invalid_type(unexpected(sibling), &ExpectedText(expected)).to_string();
+    Some((reason, Fix::Replaced(replacement)))
+}
+
+struct ExpectedText<'a>(&'a str);
+
+impl<'a> Expected for ExpectedText<'a> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.write_str(self.0)
+    }
+}
+
+fn unexpected(value: &Value) -> Unexpected<'_> {
+    match *value {
+        Value::Null => Unexpected::Unit,
+        Value::Bool(b) => Unexpected::Bool(b),
+        Value::Number(ref n) => match (n.as_u64(), n.as_i64()) {
+            (Some(n), _) => Unexpected::Unsigned(n),
+            (_, Some(n)) => Unexpected::Signed(n),
+            _ => Unexpected::Float(n.as_f64().unwrap_or_default()),
+        },
+        Value::String(ref s) => Unexpected::Str(s),
+        Value::Array(_) => Unexpected::Seq,
+        Value::Object(_) => Unexpected::Map,
+    }
+}
+
+/// Expands array indices of the path into paths to the same field of every item
+fn collect_siblings(value: &Value, path: &[Segment], prefix: &mut Vec<Segment>, out: &mut Vec<Vec<Segment>>) {
+    let (segment, rest) = match path.split_first() {
+        Some(split) => split,
+        None => return out.push(prefix.clone()),
+    };
+
+    match (value, segment) {
+        (Value::Array(items), Segment::Seq { .. }) => {
+            for (index, item) in items.iter().enumerate() {
+                prefix.push(Segment::Seq { index });
+                collect_siblings(item, rest, prefix, out);
+                prefix.pop();
+            }
+        }
+        (Value::Object(map), Segment::Map { key }) => {
+            prefix.push(segment.clone());
+            match map.get(key) {
+                Some(child) => collect_siblings(child, rest, prefix, out),
+                // Missing fields can only be fixed at the end of the path
+                None if rest.is_empty() => out.push(prefix.clone()),
+                None => {}
+            }
+            prefix.pop();
+        }
+        _ => {}
+    }
+}
+
+/// Picks the next value to try in place of the original one:
+/// original converted between number and string, then `null`, then defaults of various types
+fn replacement(attempt: &mut Attempt) -> Option<Value> {
+    let mut candidates: Vec<Value> = attempt.original.as_ref().and_then(conversion).into_iter().collect();
+    candidates.extend(vec![
+        Value::Null,
+        Value::from("0"),
+        Value::from(0),
+        Value::from(""),
+        Value::Bool(false),
+        Value::Array(Vec::new()),
+        Value::Object(Map::new()),
+    ]);
+
+    let original = attempt.original.as_ref();
+    let candidate = candidates.into_iter().filter(|c| Some(c) != original).nth(attempt.next);
+    attempt.next += 1;
+    candidate
+}
+
+/// Number converted to string or numeric string converted to number
+fn conversion(original: &Value) -> Option<Value> {
+    match *original {
+        Value::Number(ref n) => Some(Value::String(n.to_string())),
+        Value::String(ref s) => s.trim().parse().ok().map(Value::Number),
+        _ => None,
+    }
+}
+
+fn missing_field(reason: &str) -> Option<&str> {
+    reason.strip_prefix("missing field `")?.split('`').next()
+}
+
+enum Lookup<'v> {
+    Found(&'v mut Value),
+    Missing(&'v mut Map<String, Value>, String),
+}
+
+fn lookup<'v>(value: &'v mut Value, path: &[Segment]) -> Option<Lookup<'v>> {
+    let (last, parents) = match path.split_last() {
+        Some(split) => split,
+        None => return Some(Lookup::Found(value)),
+    };
+
+    let mut parent = value;
+    for segment in parents {
+        parent = child(parent, segment)?;
+    }
+
+    match (parent, last) {
+        (&mut Value::Object(ref mut map), Segment::Map { key }) => {
+            if map.contains_key(key) {
+                map.get_mut(key).map(Lookup::Found)
+            } else {
+                Some(Lookup::Missing(map, key.clone()))
+            }
+        }
+        (parent, segment) => child(parent, segment).map(Lookup::Found),
+    }
+}
+
+fn child<'v>(value: &'v mut Value, segment: &Segment) -> Option<&'v mut Value> {
+    match (value, segment) {
+        (&mut Value::Object(ref mut map), Segment::Map { key }) => map.get_mut(key),
+        (&mut Value::Array(ref mut items), Segment::Seq { index }) => items.get_mut(*index),
+        _ => None,
+    }
+}
+
+fn format_path(path: &[Segment]) -> String {
+    let mut res = String::new();
+    for segment in path {
+        match *segment {
+            Segment::Seq { index } => res.push_str(&format!("[{}]", index)),
+            Segment::Map { key: ref name } | Segment::Enum { variant: ref name } => {
+                if !res.is_empty() {
+                    res.push('.');
+                }
+                res.push_str(name);
+            }
+            Segment::Unknown => res.push_str(".?"),
+        }
+    }
+    res
+}
@@ -7,11 +7,14 @@
 //! ## Example
 //!
 //! ```no-run
-//! use lastfm_parse_rs::from_json_str;
+//! use lastfm_parse_rs::{from_json_str, Credentials};
 //! use lastfm_parse_rs::structs::artist::GetInfo;
 //!
-//! let get_info = GetInfo::request(base_url, api_key, "iamthemorning", None, Some(1), None, None);
-//! let url: Url = Into::into(get_info);
+//! let credentials = Credentials::new(base_url, api_key);
+//! let get_info = GetInfo::builder("iamthemorning")
+//!     .autocorrect(true)
+//!     .request(&credentials);
+//! let url: Url = get_info.get_url().unwrap();
 //! // Make an http request
 //! let data: GetInfo = from_json_str(&raw_json_response).unwrap();
 //! ```
@@ -22,7 +25,7 @@
 //!
 //! To run deserialization tests:
 //!
-//! 1. Set your lastfm API key to \tests\common\mod.rs `LASTFM_API_KEY`.
+//! 1. Set your lastfm API key to \tests\net\mod.rs `LASTFM_API_KEY`.
 //!
 //! 2. Run `cargo test`. Use  `-- --nocapture` to dump raw json and deserialized objects to stdout.
 //!
@@ -35,8 +38,19 @@
 
 extern crate serde;
 extern crate serde_json;
+extern crate serde_path_to_error;
 extern crate url;
 extern crate md5;
+#[cfg(feature = "blocking")]
+extern crate ureq;
+#[cfg(feature = "async")]
+extern crate futures_core;
+#[cfg(feature = "xml")]
+extern crate roxmltree;
+#[cfg(feature = "audit")]
+extern crate serde_ignored;
+#[cfg(feature = "chrono")]
+extern crate chrono;
 
 #[macro_use]
 extern crate serde_derive;
@@ -49,17 +63,67 @@ pub mod lastfm_type;
 /// Tools for constructing API requests
 #[macro_use]
 pub mod request;
+/// API credentials shared across requests
+pub mod credentials;
+/// Client-side validation of API method arguments
+pub mod validate;
+/// Automatic iteration over paginated API methods
+#[macro_use]
+pub mod paginate;
+/// Item-by-item parsing of large list responses
+#[macro_use]
+pub mod stream;
+/// Conversion of zero-copy data types into owned ones
+#[macro_use]
+pub mod owned;
+
+/// Lenient parsing that works around mismatches between responses and data structures
+pub mod lenient;
 
 /// Serde-based API data structures
 pub mod structs;
+/// Parsing of xml responses into the same data structures
+#[cfg(feature = "xml")]
+pub mod xml;
+/// Comparison of saved responses against data structures
+#[cfg(feature = "audit")]
+pub mod audit;
+/// Conversion of Lastfm timestamps and textual dates into chrono types
+#[cfg(feature = "chrono")]
+pub mod time;
 /// Common error type for serde/API fails
 pub mod error;
+/// HTTP transport abstraction and its implementations
+pub mod transport;
+/// Client-side rate limiting
+pub mod rate_limit;
+/// Retry policy for transient failures
+pub mod retry;
+/// Ready-made API client built on top of a transport
+pub mod client;
 
 // ----------------------------------------------------------------
 
-pub use lastfm_type::{LastfmType, from_json_str, from_json_slice};
-pub use request::{Request, RequestParams};
+pub use lastfm_type::{LastfmType, from_json_str, from_json_slice, from_json_owned};
+pub use lastfm_type::{to_json_string, to_json_vec, to_clean_json_string, to_clean_json_value};
+#[cfg(feature = "xml")]
+pub use xml::{from_xml_str, from_xml_slice, from_xml_owned};
+#[cfg(feature = "chrono")]
+pub use time::{Timestamped, TimeRange};
+pub use lenient::{from_json_str_lenient, from_json_slice_lenient};
+pub use stream::{stream_items, ItemStream, Listed};
+pub use owned::IntoOwned;
+pub use structs::entity::{HasName, HasMbid, HasImages};
+pub use structs::entity::{ArtistIdent, AlbumIdent, TrackIdent};
+pub use structs::common::{Mbid, Timestamp};
+pub use request::{Request, RequestParams, RequestBuilder, IntoArg, Format};
+pub use credentials::Credentials;
 pub use error::Result;
+pub use transport::Transport;
+pub use rate_limit::RateLimiter;
+pub use retry::RetryPolicy;
+pub use client::Client;
+pub use paginate::Paginated;
 
 // ----------------------------------------------------------------
 
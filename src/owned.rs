@@ -0,0 +1,104 @@
+use std::borrow::Cow;
+use std::marker::PhantomData;
+use std::time::Duration;
+
+// ----------------------------------------------------------------
+
+/// Implements `IntoOwned` for given data type:
+/// * `owned_t!(Type)` for types that don't borrow anything (converted as is);
+/// * `owned_t!(Type { field, ... })` for structs with `'dt` lifetime,
+///   all fields have to be listed and implement `IntoOwned` themselves.
+///
+/// Latter form also adds inherent `into_owned()`, so there's no need to import the trait.
+/// Following should be included in order to use this macro:
+/// ```ignore
+/// use owned::IntoOwned;
+/// ```
+#[macro_export]
+macro_rules! owned_t {
+    ($data_t:ident { $($field:ident),* $(,)* }) => {
+        impl<'dt> IntoOwned for $data_t<'dt> {
+            type Owned = $data_t<'static>;
+
+            fn into_owned(self) -> $data_t<'static> {
+                $data_t {
+                    $($field: IntoOwned::into_owned(self.$field),)*
+                }
+            }
+        }
+
+        impl<'dt> $data_t<'dt> {
+            /// Converts object into `'static` one, copying borrowed strings
+            pub fn into_owned(self) -> $data_t<'static> {
+                IntoOwned::into_owned(self)
+            }
+        }
+    };
+    ($data_t:ty) => {
+        impl IntoOwned for $data_t {
+            type Owned = $data_t;
+
+            fn into_owned(self) -> $data_t {
+                self
+            }
+        }
+    };
+}
+
+// ----------------------------------------------------------------
+
+/// Conversion of zero-copy data types into their `'static` versions.
+/// Borrowed strings are copied, so converted object no longer depends on response buffer
+/// and can be stored, returned from functions or sent to other threads.
+pub trait IntoOwned {
+    type Owned: 'static;
+
+    fn into_owned(self) -> Self::Owned;
+}
+
+impl IntoOwned for Cow<'_, str> {
+    type Owned = Cow<'static, str>;
+
+    fn into_owned(self) -> Cow<'static, str> {
+        match self {
+            Cow::Borrowed(s) => Cow::Owned(s.to_owned()),
+            Cow::Owned(s) => Cow::Owned(s),
+        }
+    }
+}
+
+impl<T: IntoOwned> IntoOwned for Option<T> {
+    type Owned = Option<T::Owned>;
+
+    fn into_owned(self) -> Option<T::Owned> {
+        self.map(IntoOwned::into_owned)
+    }
+}
+
+impl<T: IntoOwned> IntoOwned for Vec<T> {
+    type Owned = Vec<T::Owned>;
+
+    fn into_owned(self) -> Vec<T::Owned> {
+        self.into_iter().map(IntoOwned::into_owned).collect()
+    }
+}
+
+impl<T: ?Sized + 'static> IntoOwned for PhantomData<&T> {
+    type Owned = PhantomData<&'static T>;
+
+    fn into_owned(self) -> PhantomData<&'static T> {
+        PhantomData
+    }
+}
+
+owned_t!(u8);
+owned_t!(u16);
+owned_t!(u32);
+owned_t!(u64);
+owned_t!(i32);
+owned_t!(i64);
+owned_t!(f32);
+owned_t!(f64);
+owned_t!(bool);
+owned_t!(String);
+owned_t!(Duration);
@@ -0,0 +1,386 @@
+use std::collections::VecDeque;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+#[cfg(feature = "async")]
+use std::pin::Pin;
+#[cfg(feature = "async")]
+use std::task::{Context, Poll};
+#[cfg(feature = "async")]
+use std::time::Duration;
+
+#[cfg(feature = "async")]
+use futures_core::Stream;
+
+use lastfm_type::LastfmType;
+use request::{RequestParams, RequestBuilder, Format};
+use credentials::{SessionState, Permits};
+use transport::{Transport, HttpRequest, HttpResponse};
+#[cfg(feature = "async")]
+use transport::{AsyncTransport, ResponseFuture, DelayFuture};
+use client::{Client, parse_response};
+#[cfg(feature = "async")]
+use client::check_status;
+use error::{Error, Result};
+
+// ----------------------------------------------------------------
+
+/// Implemented for method parameters that contain API methods taking `page` argument
+pub trait Pageable: RequestParams {
+    /// Returns requested page number, `None` if it's not set or method is not paginated
+    fn page(&self) -> Option<u32>;
+
+    /// Sets requested page number. Fails if method is not paginated.
+    fn set_page(&mut self, page: u32) -> Result<()>;
+}
+
+/// Error for `Pageable::set_page()` called on parameters of a method without pages
+pub fn not_paginated(method: &str) -> Error {
+    Error::InvalidParameter {
+        name: "page",
+        reason: format!("method {} is not paginated", method),
+    }
+}
+
+/// Implemented for data types that hold a single page of a paginated list.
+/// Like `RequestBuilder`, it is implemented for `'static` flavor of a data type,
+/// which stands for the same type borrowing from a response body (`Page<'b>`).
+/// See paged_t! macro.
+pub trait Paged {
+    type Page<'b>: LastfmType<'b>;
+    type Item<'b>;
+
+    /// Returns total number of pages as reported by the service
+    fn total_pages(page: &Self::Page<'_>) -> Option<u32>;
+
+    /// Takes list items out of the page
+    fn into_items<'b>(page: Self::Page<'b>) -> Vec<Self::Item<'b>>;
+}
+
+/// Implements `Paged` (and `Listed`, see list_t!) for given data type.
+/// List field is expected to be `Option<Vec<Item>>`:
+/// * `paged_t!(Data, field: Item)` is used for data with `page_info` (`@attr`) field;
+/// * `paged_t!(Data, matches.field: Item)` is used for opensearch data types.
+#[macro_export]
+macro_rules! paged_t {
+    ($data_t:ident, $items:ident: $item_t:ident) => {
+        impl Paged for $data_t<'static> {
+            type Page<'b> = $data_t<'b>;
+            type Item<'b> = $item_t<'b>;
+
+            fn total_pages(page: &Self::Page<'_>) -> Option<u32> {
+                page.page_info.as_ref().map(|info| info.total_pages)
+            }
+
+            fn into_items<'b>(page: Self::Page<'b>) -> Vec<Self::Item<'b>> {
+                page.$items.unwrap_or_default()
+            }
+        }
+
+        list_t!($data_t, $items: $item_t);
+    };
+    ($data_t:ident, $matches:ident.$items:ident: $item_t:ident) => {
+        impl Paged for $data_t<'static> {
+            type Page<'b> = $data_t<'b>;
+            type Item<'b> = $item_t<'b>;
+
+            fn total_pages(page: &Self::Page<'_>) -> Option<u32> {
+                match (page.total_results, page.iterms_per_page) {
+                    (Some(total), Some(per_page)) if per_page > 0 => {
+                        Some(total.div_ceil(per_page))
+                    }
+                    _ => None,
+                }
+            }
+
+            fn into_items<'b>(page: Self::Page<'b>) -> Vec<Self::Item<'b>> {
+                page.$matches.and_then(|m| m.$items).unwrap_or_default()
+            }
+        }
+
+        list_t!($data_t, $matches.$items: $item_t);
+    };
+}
+
+// ----------------------------------------------------------------
+
+/// Iteration state shared by `Paginated` and `PaginatedStream`
+#[derive(Debug)]
+struct Cursor<P, L, F, O> {
+    params: P,
+    next_page: u32,
+    done: bool,
+    items: VecDeque<O>,
+    map: F,
+    phantom: PhantomData<fn() -> L>,
+}
+
+impl<P, L, F, O> Cursor<P, L, F, O>
+where
+    P: Pageable + Debug,
+    L: Paged,
+    F: for<'b> FnMut(L::Item<'b>) -> O,
+{
+    fn new(params: P, map: F) -> Cursor<P, L, F, O> {
+        Cursor {
+            next_page: params.page().unwrap_or(1),
+            params,
+            done: false,
+            items: VecDeque::new(),
+            map,
+            phantom: PhantomData,
+        }
+    }
+
+    fn next_request<T, S: SessionState + Permits<L>>(&mut self, client: &Client<T, S>) -> Result<HttpRequest> {
+        self.params.set_page(self.next_page)?;
+        client.request(RequestBuilder::<_, L>::new(&self.params)).to_http()
+    }
+
+    /// Parses fetched page and queues its items.
+    /// Iteration is over when the last page is reached or service returns an empty one.
+    fn consume(&mut self, format: Format, response: HttpResponse) -> Result<()> {
+        let page: L::Page<'_> = parse_response(format, self.params.method(), response.status, &response.body)?;
+        let total_pages = L::total_pages(&page);
+        let items = L::into_items(page);
+
+        self.done = items.is_empty() || total_pages.is_none_or(|total| self.next_page >= total);
+        self.next_page += 1;
+
+        let map = &mut self.map;
+        self.items.extend(items.into_iter().map(map));
+        Ok(())
+    }
+
+    fn fail(&mut self, err: Error) -> Result<O> {
+        self.done = true;
+        Err(err)
+    }
+}
+
+// ----------------------------------------------------------------
+
+/// Iterator over items of a paginated list.
+/// Fetches page after page through client's transport, starting with requested page (1 by default),
+/// and stops after the last one (`totalPages`). Iteration stops after the first error as well.
+///
+/// Since data types borrow from response body, which only lives until the next page is fetched,
+/// each item is converted by a user-provided function (e.g. to an owned type).
+#[derive(Debug)]
+pub struct Paginated<'c, T, S, P, L, F, O>
+where
+    T: 'c,
+    S: SessionState + 'c,
+{
+    client: &'c Client<T, S>,
+    cursor: Cursor<P, L, F, O>,
+}
+
+impl<'c, T, S, P, L, F, O> Paginated<'c, T, S, P, L, F, O>
+where
+    T: Transport,
+    S: SessionState + Permits<L>,
+    P: Pageable + Debug,
+    L: Paged,
+    F: for<'b> FnMut(L::Item<'b>) -> O,
+{
+    /// Constructs iterator from method parameters.
+    /// Data type `L` has to be specified explicitly, consider `RequestBuilder::paginate()` instead.
+    pub fn new(client: &'c Client<T, S>, params: P, map: F) -> Paginated<'c, T, S, P, L, F, O> {
+        Paginated { client, cursor: Cursor::new(params, map) }
+    }
+
+    fn fetch(&mut self) -> Result<()> {
+        let request = self.cursor.next_request(self.client)?;
+        let response = self.client.execute(&self.cursor.params, &request)?;
+
+        let res = self.cursor.consume(self.client.format(), response);
+        self.client.observe(&res);
+        res
+    }
+}
+
+impl<'c, T, S, P, L, F, O> Iterator for Paginated<'c, T, S, P, L, F, O>
+where
+    T: Transport,
+    S: SessionState + Permits<L>,
+    P: Pageable + Debug,
+    L: Paged,
+    F: for<'b> FnMut(L::Item<'b>) -> O,
+{
+    type Item = Result<O>;
+
+    fn next(&mut self) -> Option<Result<O>> {
+        loop {
+            if let Some(item) = self.cursor.items.pop_front() {
+                return Some(Ok(item));
+            }
+            if self.cursor.done {
+                return None;
+            }
+            if let Err(err) = self.fetch() {
+                return Some(self.cursor.fail(err));
+            }
+        }
+    }
+}
+
+// ----------------------------------------------------------------
+
+/// Request of `PaginatedStream` that is waiting to be sent (rate limiting or retry delay)
+/// or waiting for response
+#[cfg(feature = "async")]
+enum InFlight<'c> {
+    Delay(DelayFuture<'c>, HttpRequest),
+    Response(ResponseFuture<'c>, HttpRequest),
+}
+
+/// Asynchronous counterpart of `Paginated`, yields items as a `Stream`
+#[cfg(feature = "async")]
+pub struct PaginatedStream<'c, T, S, P, L, F, O>
+where
+    T: 'c,
+    S: SessionState + 'c,
+{
+    client: &'c Client<T, S>,
+    cursor: Cursor<P, L, F, O>,
+    in_flight: Option<InFlight<'c>>,
+    attempt: u32,
+}
+
+#[cfg(feature = "async")]
+impl<'c, T, S, P, L, F, O> PaginatedStream<'c, T, S, P, L, F, O>
+where
+    T: AsyncTransport,
+    S: SessionState + Permits<L>,
+    P: Pageable + Debug,
+    L: Paged,
+    F: for<'b> FnMut(L::Item<'b>) -> O,
+{
+    /// Constructs stream from method parameters.
+    /// Data type `L` has to be specified explicitly, consider `RequestBuilder::paginate_async()` instead.
+    pub fn new(client: &'c Client<T, S>, params: P, map: F) -> PaginatedStream<'c, T, S, P, L, F, O> {
+        PaginatedStream {
+            client,
+            cursor: Cursor::new(params, map),
+            in_flight: None,
+            attempt: 1,
+        }
+    }
+
+    /// Time to wait before sending a request: retry delay (if it's a retry) plus rate limiter's one
+    fn delay(&self) -> Duration {
+        let retry = match self.attempt {
+            1 => Duration::ZERO,
+            attempt => self.client.retry_policy().delay(attempt - 1),
+        };
+        let api_key = self.client.credentials().api_key();
+        retry + self.client.rate_limiter().map_or(Duration::ZERO, |l| l.reserve(api_key))
+    }
+}
+
+// Fields are never pinned: in-flight future is boxed
+#[cfg(feature = "async")]
+impl<'c, T, S: SessionState, P, L, F, O> Unpin for PaginatedStream<'c, T, S, P, L, F, O> {}
+
+#[cfg(feature = "async")]
+impl<'c, T, S, P, L, F, O> Stream for PaginatedStream<'c, T, S, P, L, F, O>
+where
+    T: AsyncTransport,
+    S: SessionState + Permits<L>,
+    P: Pageable + Debug,
+    L: Paged,
+    F: for<'b> FnMut(L::Item<'b>) -> O,
+{
+    type Item = Result<O>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Result<O>>> {
+        let this = self.get_mut();
+        loop {
+            if let Some(item) = this.cursor.items.pop_front() {
+                return Poll::Ready(Some(Ok(item)));
+            }
+            if this.cursor.done {
+                return Poll::Ready(None);
+            }
+
+            let client = this.client;
+            let in_flight = match this.in_flight.take() {
+                Some(in_flight) => in_flight,
+                None => match this.cursor.next_request(client) {
+                    Ok(request) => InFlight::Delay(client.transport().delay(this.delay()), request),
+                    Err(err) => return Poll::Ready(Some(this.cursor.fail(err))),
+                },
+            };
+
+            let (response, request) = match in_flight {
+                InFlight::Delay(mut delay, request) => {
+                    if delay.as_mut().poll(cx).is_pending() {
+                        this.in_flight = Some(InFlight::Delay(delay, request));
+                        return Poll::Pending;
+                    }
+                    let response = client.transport().send_async(request.clone());
+                    this.in_flight = Some(InFlight::Response(response, request));
+                    continue;
+                }
+                InFlight::Response(mut response, request) => match response.as_mut().poll(cx) {
+                    Poll::Ready(response) => (response.and_then(|response| check_status(client.format(), response)), request),
+                    Poll::Pending => {
+                        this.in_flight = Some(InFlight::Response(response, request));
+                        return Poll::Pending;
+                    }
+                },
+            };
+
+            if let Err(ref err) = response {
+                if client.retry_policy().should_retry(&this.cursor.params, this.attempt, err) {
+                    client.observe(&response);
+                    this.attempt += 1;
+                    this.in_flight = Some(InFlight::Delay(client.transport().delay(this.delay()), request));
+                    continue;
+                }
+            }
+            this.attempt = 1;
+
+            let cursor = &mut this.cursor;
+            let res = response.and_then(|response| cursor.consume(client.format(), response));
+            client.observe(&res);
+            if let Err(err) = res {
+                return Poll::Ready(Some(cursor.fail(err)));
+            }
+        }
+    }
+}
+
+// ----------------------------------------------------------------
+
+impl<P, L> RequestBuilder<P, L>
+where
+    P: Pageable + Debug,
+    L: Paged,
+{
+    /// Iterates over items of all pages starting with the requested one (1 by default).
+    /// Each item is converted by `map` function, see `Paginated`.
+    pub fn paginate<'c, T, S, F, O>(self, client: &'c Client<T, S>, map: F) -> Paginated<'c, T, S, P, L, F, O>
+    where
+        T: Transport,
+        S: SessionState + Permits<L>,
+        F: for<'b> FnMut(L::Item<'b>) -> O,
+    {
+        Paginated::new(client, self.params, map)
+    }
+
+    /// Same as `paginate()`, but returns a `Stream` fetching pages through asynchronous transport
+    #[cfg(feature = "async")]
+    pub fn paginate_async<'c, T, S, F, O>(
+        self,
+        client: &'c Client<T, S>,
+        map: F,
+    ) -> PaginatedStream<'c, T, S, P, L, F, O>
+    where
+        T: AsyncTransport,
+        S: SessionState + Permits<L>,
+        F: for<'b> FnMut(L::Item<'b>) -> O,
+    {
+        PaginatedStream::new(client, self.params, map)
+    }
+}
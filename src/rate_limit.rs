@@ -0,0 +1,231 @@
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use error::{Error, Result};
+use structs::api_error::ApiErrorKind;
+
+// ----------------------------------------------------------------
+
+/// Source of time for `RateLimiter`.
+/// Replace `SystemClock` with `MockClock` to test rate limiting without actually waiting.
+pub trait Clock: Debug + Send + Sync {
+    fn now(&self) -> Instant;
+    fn sleep(&self, duration: Duration);
+}
+
+/// Clock backed by `std::time::Instant` and `std::thread::sleep()`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        thread::sleep(duration)
+    }
+}
+
+/// Clock that only moves when told to.
+/// `sleep()` returns immediately, advancing the time and recording requested duration.
+#[derive(Debug)]
+pub struct MockClock {
+    now: Mutex<Instant>,
+    sleeps: Mutex<Vec<Duration>>,
+}
+
+impl MockClock {
+    pub fn new() -> MockClock {
+        MockClock {
+            now: Mutex::new(Instant::now()),
+            sleeps: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Moves the time forward
+    pub fn advance(&self, duration: Duration) {
+        *self.now.lock().unwrap() += duration;
+    }
+
+    /// Returns durations of all `sleep()` calls so far
+    pub fn sleeps(&self) -> Vec<Duration> {
+        self.sleeps.lock().unwrap().clone()
+    }
+}
+
+impl Default for MockClock {
+    fn default() -> MockClock {
+        MockClock::new()
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        *self.now.lock().unwrap()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        self.sleeps.lock().unwrap().push(duration);
+        self.advance(duration);
+    }
+}
+
+// ----------------------------------------------------------------
+
+/// Token bucket state of a single API key
+#[derive(Debug)]
+struct Bucket {
+    /// Available tokens, negative when requests are queued up
+    tokens: f64,
+    /// Point in time tokens were counted at. Set to the future while backing off.
+    updated: Instant,
+    /// Current back off interval, zero if service didn't complain recently
+    backoff: Duration,
+}
+
+/// Token bucket rate limiter.
+/// Keeps a separate bucket per API key and can be shared across clients and threads (via `Arc`).
+///
+/// Each request takes a token, tokens are refilled at configured rate up to burst size.
+/// When service reports that rate limit is exceeded (error 29), the bucket is drained and
+/// frozen for a back off interval, which doubles with each consecutive report
+/// and resets after the first successful call.
+#[derive(Debug)]
+pub struct RateLimiter {
+    requests: u32,
+    interval: Duration,
+    burst: u32,
+    min_backoff: Duration,
+    max_backoff: Duration,
+    clock: Arc<dyn Clock>,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+impl RateLimiter {
+    /// Constructs limiter that allows `requests` per `interval` for each API key.
+    /// Burst size is equal to `requests` by default.
+    pub fn new(requests: u32, interval: Duration) -> RateLimiter {
+        RateLimiter {
+            requests: requests.max(1),
+            interval,
+            burst: requests.max(1),
+            min_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+            clock: Arc::new(SystemClock),
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Sets maximum number of requests that can be sent at once after a period of inactivity
+    pub fn with_burst(mut self, burst: u32) -> RateLimiter {
+        self.burst = burst.max(1);
+        self
+    }
+
+    /// Sets initial and maximum back off intervals used after service reports error 29
+    pub fn with_backoff(mut self, min: Duration, max: Duration) -> RateLimiter {
+        self.min_backoff = min;
+        self.max_backoff = max.max(min);
+        self
+    }
+
+    /// Replaces system clock, mostly for testing purposes
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> RateLimiter {
+        self.clock = clock;
+        self
+    }
+
+    /// Takes a token for given API key and returns how long caller has to wait before sending a request.
+    /// Tokens can be taken in advance, so concurrent callers get increasing waits.
+    pub fn reserve(&self, api_key: &str) -> Duration {
+        let now = self.clock.now();
+        let period = self.interval.as_secs_f64() / f64::from(self.requests);
+
+        let mut buckets = self.buckets.lock().unwrap();
+        let bucket = buckets.entry(api_key.to_owned()).or_insert_with(|| Bucket {
+            tokens: f64::from(self.burst),
+            updated: now,
+            backoff: Duration::ZERO,
+        });
+
+        if now > bucket.updated {
+            let refill = (now - bucket.updated).as_secs_f64() / period;
+            bucket.tokens = (bucket.tokens + refill).min(f64::from(self.burst));
+            bucket.updated = now;
+        }
+        bucket.tokens -= 1.0;
+
+        let frozen = bucket.updated.saturating_duration_since(now);
+        if bucket.tokens >= 0.0 {
+            frozen
+        } else {
+            frozen + Duration::from_secs_f64(-bucket.tokens * period)
+        }
+    }
+
+    /// Blocks current thread until a request for given API key can be sent
+    pub fn acquire(&self, api_key: &str) {
+        let wait = self.reserve(api_key);
+        if wait > Duration::ZERO {
+            self.clock.sleep(wait);
+        }
+    }
+
+    /// Drains the bucket and freezes it for the next back off interval
+    pub fn back_off(&self, api_key: &str) {
+        let now = self.clock.now();
+        let mut buckets = self.buckets.lock().unwrap();
+        let bucket = buckets.entry(api_key.to_owned()).or_insert_with(|| Bucket {
+            tokens: 0.0,
+            updated: now,
+            backoff: Duration::ZERO,
+        });
+
+        bucket.backoff = if bucket.backoff == Duration::ZERO {
+            self.min_backoff
+        } else {
+            (bucket.backoff * 2).min(self.max_backoff)
+        };
+        bucket.tokens = bucket.tokens.min(0.0);
+        bucket.updated = bucket.updated.max(now) + bucket.backoff;
+    }
+
+    /// Resets back off interval after a successful call
+    pub fn recover(&self, api_key: &str) {
+        if let Some(bucket) = self.buckets.lock().unwrap().get_mut(api_key) {
+            bucket.backoff = Duration::ZERO;
+        }
+    }
+
+    /// Returns current back off interval for given API key
+    pub fn backoff(&self, api_key: &str) -> Duration {
+        self.buckets
+            .lock()
+            .unwrap()
+            .get(api_key)
+            .map_or(Duration::ZERO, |bucket| bucket.backoff)
+    }
+
+    /// Adjusts the limiter according to the outcome of a call:
+    /// backs off on error 29 and recovers on success
+    pub fn observe<T>(&self, api_key: &str, result: &Result<T>) {
+        match *result {
+            Ok(_) => self.recover(api_key),
+            Err(Error::Api(ref err)) if err.error == ApiErrorKind::RateLimitExceeded => {
+                self.back_off(api_key)
+            }
+            Err(_) => {}
+        }
+    }
+}
+
+/// Lastfm allows about 5 requests per second
+impl Default for RateLimiter {
+    fn default() -> RateLimiter {
+        RateLimiter::new(5, Duration::from_secs(1))
+    }
+}
@@ -1,10 +1,15 @@
 use std::fmt::Debug;
-use std::io::{Error, ErrorKind, Result};
+use std::marker::PhantomData;
 use std::convert::TryFrom;
 
 use url::Url;
+use url::form_urlencoded::Serializer;
 use md5::compute as md5;
 
+use transport::{HttpMethod, HttpRequest};
+use credentials::{Credentials, SessionState, Permits};
+use error::{Error, Result};
+
 // ----------------------------------------------------------------
 
 /// Describes API method requests
@@ -25,10 +30,61 @@ pub trait RequestParams {
     /// All `write` (see https://www.last.fm/api/rest) API methods require user to be authenticated.
     /// For them session key has to be additionally provided in request body.
     fn needs_session_key(&self) -> bool;
+
+    /// Checks method arguments against documented limits and required-argument rules
+    /// (see `validate` module), so that obviously wrong requests don't go over the wire.
+    fn validate(&self) -> Result<()>;
+
+    /// HTTP method that should be used to call API method.
+    /// Signed methods (`write` and `auth` ones) are sent via POST,
+    /// so credentials and long parameter lists don't end up in the url.
+    fn http_method(&self) -> HttpMethod {
+        if self.needs_signature() {
+            HttpMethod::Post
+        } else {
+            HttpMethod::Get
+        }
+    }
+}
+
+impl<P: RequestParams + ?Sized> RequestParams for &P {
+    fn method(&self) -> &str {
+        (**self).method()
+    }
+
+    fn append_to(&self, url: &mut Url) {
+        (**self).append_to(url)
+    }
+
+    fn needs_signature(&self) -> bool {
+        (**self).needs_signature()
+    }
+
+    fn needs_session_key(&self) -> bool {
+        (**self).needs_session_key()
+    }
+
+    fn validate(&self) -> Result<()> {
+        (**self).validate()
+    }
+
+    fn http_method(&self) -> HttpMethod {
+        (**self).http_method()
+    }
+}
+
+/// Format of API responses
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    #[default]
+    Json,
+    /// Lastfm's native format, parsed with `xml::from_xml_str()`
+    #[cfg(feature = "xml")]
+    Xml,
 }
 
 /// Request information associated with a method and lastfm data type.
-/// Can be converted to a Url. No POST request support yet.
+/// Can be converted to a Url (GET only) or to an HTTP request (see `to_http()`).
 #[derive(Debug)]
 pub struct Request<'rq, T>
 where
@@ -39,6 +95,7 @@ where
     pub secret: Option<&'rq str>,
     pub session: Option<&'rq str>,
     pub params: T,
+    pub format: Format,
 }
 
 impl<'rq, T> Request<'rq, T>
@@ -53,13 +110,22 @@ where
         session: Option<&'rq str>,
         params: T
     ) -> Request<'rq, T> {
-        Request { base_url, api_key, secret, session, params }
+        Request { base_url, api_key, secret, session, params, format: Format::Json }
+    }
+
+    /// Sets format of the response (json by default)
+    pub fn with_format(mut self, format: Format) -> Request<'rq, T> {
+        self.format = format;
+        self
     }
 
     /// Converts Request object to an Url and appends method parameters to the query.
+    /// * Validates method arguments.
     /// * Appends a session key for `write` API methods.
     /// * Signs `write` and `auth` API methods.
     pub fn get_url(&self) -> Result<Url> {
+        self.params.validate()?;
+
         let mut url = self.make_url()?;
 
         if self.params.needs_session_key() {
@@ -72,13 +138,42 @@ where
         Ok(url)
     }
 
+    /// Converts Request object to an HTTP request.
+    /// Parameters and signature are the same as in `get_url()`, but for POST methods
+    /// (see `RequestParams::http_method()`) they're moved from the query to a form-encoded body.
+    pub fn to_http(&self) -> Result<HttpRequest> {
+        let mut url = self.get_url()?;
+
+        match self.params.http_method() {
+            HttpMethod::Get => Ok(HttpRequest::get(url)),
+            HttpMethod::Post => {
+                let body = Serializer::new(String::new())
+                    .extend_pairs(url.query_pairs())
+                    .finish();
+                url.set_query(None);
+
+                Ok(HttpRequest {
+                    method: HttpMethod::Post,
+                    url,
+                    headers: vec![(
+                        "Content-Type".to_owned(),
+                        "application/x-www-form-urlencoded".to_owned(),
+                    )],
+                    body: Some(body.into_bytes()),
+                })
+            }
+        }
+    }
+
     fn make_url(&self) -> Result<Url> {
-        let mut url = Url::parse(self.base_url)
-            .map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
+        let mut url = Url::parse(self.base_url).map_err(Error::InvalidBaseUrl)?;
         {
             let mut query = url.query_pairs_mut();
             query.append_pair("api_key", self.api_key);
-            query.append_pair("format", "json");
+            // Lastfm responds with xml unless asked otherwise
+            if self.format == Format::Json {
+                query.append_pair("format", "json");
+            }
             query.append_pair("method", self.params.method());
         }
         self.params.append_to(&mut url);
@@ -86,8 +181,7 @@ where
     }
 
     fn append_session_key(&self, mut base: Url) -> Result<Url> {
-        let sk = self.session.ok_or(
-            Error::new(ErrorKind::InvalidInput, "Missing session key"))?;
+        let sk = self.session.ok_or(Error::MissingSession)?;
         {
             let mut query = base.query_pairs_mut();
             query.append_pair("sk", sk);
@@ -98,7 +192,7 @@ where
     fn sign_url(&self, mut base: Url) -> Result<Url> {
         let mut signature = {
             let mut pairs = base.query_pairs()
-                .filter(|&(ref name, _)| name != "format" && name != "callback")
+                .filter(|(name, _)| name != "format" && name != "callback")
                 .collect::<Vec<_>>();
 
             pairs.sort_unstable();
@@ -109,10 +203,7 @@ where
                 acc
             })
         };
-        signature.push_str(self.secret.ok_or(
-            Error::new(ErrorKind::InvalidInput,
-                "All methods that need signature also need secret")
-        )?);
+        signature.push_str(self.secret.ok_or(Error::MissingSecret)?);
 
         let digest = format!("{:x}", md5(signature));
         base.query_pairs_mut().append_pair("api_sig", &digest);
@@ -133,6 +224,58 @@ where
 
 // ----------------------------------------------------------------
 
+/// Named-argument builder for API method parameters.
+/// Created by `builder()` functions of data types, which take required method arguments.
+/// Optional arguments are set via builder methods named after them.
+/// `T` is a data type the method returns: it binds setters to the method.
+#[derive(Debug)]
+pub struct RequestBuilder<P, T>
+where
+    P: RequestParams + Debug,
+{
+    pub(crate) params: P,
+    phantom: PhantomData<fn() -> T>,
+}
+
+impl<P, T> RequestBuilder<P, T>
+where
+    P: RequestParams + Debug,
+{
+    /// Constructs new builder from method parameters with optional arguments unset
+    pub fn new(params: P) -> RequestBuilder<P, T> {
+        RequestBuilder { params, phantom: PhantomData }
+    }
+
+    /// Returns method parameters
+    pub fn params(self) -> P {
+        self.params
+    }
+
+    /// Constructs Request object for built method parameters.
+    /// Methods that need a session key only accept authenticated credentials.
+    pub fn request<S>(self, credentials: &Credentials<S>) -> Request<'_, P>
+    where
+        S: SessionState + Permits<T>,
+    {
+        credentials.request(self.params)
+    }
+}
+
+/// Value accepted by `RequestBuilder` setters: the argument type itself
+/// or one convertible into it, like `UnixTimestamp` or `DateTime<Utc>` (`chrono` feature) for `Timestamp`.
+/// Unlike `Into`, it doesn't get in the way of inferring integer literal types.
+pub trait IntoArg<T> {
+    fn into_arg(self) -> T;
+}
+
+impl<T> IntoArg<T> for T {
+    fn into_arg(self) -> T {
+        self
+    }
+}
+
+// ----------------------------------------------------------------
+
 #[macro_export]
 macro_rules! cv {
     ($what:ident) => { &$what.to_string() }
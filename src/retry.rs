@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::Arc;
+use std::time::Duration;
+
+use request::RequestParams;
+use error::Error;
+use rate_limit::{Clock, SystemClock};
+
+// ----------------------------------------------------------------
+
+/// Describes how client retries requests that failed with a transient error
+/// (see `Error::is_retryable()`).
+///
+/// Delay before `n`-th retry grows exponentially: `base * 2^(n - 1)`, capped by maximum delay.
+/// With jitter enabled, actual delay is picked randomly from the upper half of that interval,
+/// so concurrent clients don't retry in lockstep.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+    jitter: bool,
+    methods: HashMap<String, u32>,
+    clock: Arc<dyn Clock>,
+}
+
+impl RetryPolicy {
+    /// Constructs policy that sends each request at most `max_attempts` times (including the first one)
+    pub fn new(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: max_attempts.max(1),
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            jitter: true,
+            methods: HashMap::new(),
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// Constructs policy that never retries
+    pub fn never() -> RetryPolicy {
+        RetryPolicy::new(1)
+    }
+
+    /// Sets delay before the first retry and maximum delay
+    pub fn with_backoff(mut self, base: Duration, max: Duration) -> RetryPolicy {
+        self.base_delay = base;
+        self.max_delay = max.max(base);
+        self
+    }
+
+    /// Enables or disables randomization of delays
+    pub fn with_jitter(mut self, jitter: bool) -> RetryPolicy {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Overrides maximum number of attempts for given API method (e.g. "track.getinfo").
+    /// This is the only way to enable retries for signed methods.
+    pub fn with_method(mut self, method: &str, max_attempts: u32) -> RetryPolicy {
+        self.methods.insert(method.to_lowercase(), max_attempts.max(1));
+        self
+    }
+
+    /// Replaces system clock used to wait between attempts, mostly for testing purposes
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> RetryPolicy {
+        self.clock = clock;
+        self
+    }
+
+    /// Returns maximum number of attempts for API method of given parameters.
+    /// Signed (`write` and `auth`) methods must not be sent twice: repeated request has a visible effect
+    /// (e.g. a duplicate scrobble), so they are only retried when enabled with `with_method()`.
+    pub fn max_attempts<P: RequestParams + ?Sized>(&self, params: &P) -> u32 {
+        match self.methods.get(&params.method().to_lowercase()) {
+            Some(&attempts) => attempts,
+            None if params.needs_signature() => 1,
+            None => self.max_attempts,
+        }
+    }
+
+    /// Decides whether request for given method parameters should be sent again
+    /// after `attempt`-th attempt (numbered from 1) failed with given error
+    pub fn should_retry<P: RequestParams + ?Sized>(&self, params: &P, attempt: u32, err: &Error) -> bool {
+        err.is_retryable() && attempt < self.max_attempts(params)
+    }
+
+    /// Returns delay before the next attempt after `attempt`-th one failed
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let exp = attempt.saturating_sub(1).min(31);
+        let delay = self.base_delay
+            .checked_mul(1 << exp)
+            .map_or(self.max_delay, |delay| delay.min(self.max_delay));
+
+        if self.jitter {
+            delay / 2 + delay.mul_f64(random_fraction() / 2.0)
+        } else {
+            delay
+        }
+    }
+
+    /// Blocks current thread for `delay(attempt)`
+    pub fn wait(&self, attempt: u32) {
+        self.clock.sleep(self.delay(attempt));
+    }
+}
+
+/// Retries transient errors up to 3 times in total
+impl Default for RetryPolicy {
+    fn default() -> RetryPolicy {
+        RetryPolicy::new(3)
+    }
+}
+
+/// Returns pseudo-random number in [0, 1) without pulling in an RNG crate:
+/// `RandomState` is seeded randomly for each instance.
+fn random_fraction() -> f64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u8(0);
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
@@ -0,0 +1,393 @@
+use std::io::{BufRead, BufReader, ErrorKind, Read};
+use std::marker::PhantomData;
+
+use serde::de::{Deserialize, Error as SerdeError};
+use serde_json::{self, Map, Value, Error as JsonError};
+use serde_path_to_error as path_to_error;
+
+use lastfm_type::LastfmType;
+use error::{Error, DeserializeError, Result};
+use structs::api_error::ApiError;
+use structs::common::PageInfo;
+
+// ----------------------------------------------------------------
+
+/// Implemented for data types that hold a list of items, so that the list can be streamed
+/// (see `stream_items()`). Like `Paged`, it is implemented for `'static` flavor of a data type.
+/// See list_t! macro.
+pub trait Listed: LastfmType<'static> {
+    type Item: Deserialize<'static>;
+
+    /// Json keys leading to the list inside of the wrapper object, e.g. `["albummatches", "album"]`
+    const LIST: &'static [&'static str];
+}
+
+/// Implements `Listed` for given data type, same syntax as paged_t!:
+/// * `list_t!(Data, field: Item)`;
+/// * `list_t!(Data, matches.field: Item)`.
+#[macro_export]
+macro_rules! list_t {
+    ($data_t:ident, $items:ident: $item_t:ident) => {
+        impl Listed for $data_t<'static> {
+            type Item = $item_t<'static>;
+
+            const LIST: &'static [&'static str] = &[stringify!($items)];
+        }
+    };
+    ($data_t:ident, $matches:ident.$items:ident: $item_t:ident) => {
+        impl Listed for $data_t<'static> {
+            type Item = $item_t<'static>;
+
+            const LIST: &'static [&'static str] = &[stringify!($matches), stringify!($items)];
+        }
+    };
+}
+
+// ----------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum State {
+    /// Looking for the list
+    Start,
+    /// Yielding list items
+    Items,
+    /// Reading whatever follows the list
+    Trailer,
+    Done,
+}
+
+/// Iterator over items of a list response read from `io::Read`, see `stream_items()`.
+/// Only the item being parsed is kept in memory, so it is suitable for pages with
+/// thousands of items. Items are owned `'static` objects.
+///
+/// The list is located by the keys data type `L` wraps it with, e.g. `recenttracks.track`.
+/// All the other fields are collected as metadata, which is available once iteration is over
+/// (`@attr` usually follows the list). One-item list that service sent as a bare object
+/// yields that single item.
+/// Items that fail to parse are reported as errors without stopping the iteration,
+/// malformed json and read failures stop it.
+#[derive(Debug)]
+pub struct ItemStream<L, R> {
+    reader: BufReader<R>,
+    position: usize,
+    state: State,
+    /// Objects that are being read, from the root one to the innermost one, along with their keys
+    stack: Vec<(String, Map<String, Value>)>,
+    metadata: Option<Map<String, Value>>,
+    list_path: Vec<String>,
+    index: usize,
+    buffer: Vec<u8>,
+    /// List turned out to be a single object (service sends one-item lists this way),
+    /// it is kept in the buffer
+    single: bool,
+    phantom: PhantomData<fn() -> L>,
+}
+
+/// Parses list response of data type `L` item by item, for example:
+/// ```ignore
+/// for track in stream_items::<user::GetRecentTracks>(response_body) {
+///     println!("{}", track?.name);
+/// }
+/// ```
+/// Service error responses are yielded as `Error::Api`. See `ItemStream`.
+pub fn stream_items<L>(reader: impl Read) -> ItemStream<L, impl Read>
+where
+    L: Listed,
+{
+    ItemStream::new(reader)
+}
+
+impl<L, R> ItemStream<L, R>
+where
+    L: Listed,
+    R: Read,
+{
+    pub fn new(reader: R) -> ItemStream<L, R> {
+        ItemStream {
+            reader: BufReader::new(reader),
+            position: 0,
+            state: State::Start,
+            stack: Vec::new(),
+            metadata: None,
+            list_path: Vec::new(),
+            index: 0,
+            buffer: Vec::new(),
+            single: false,
+            phantom: PhantomData,
+        }
+    }
+
+    /// All fields of the response except for the list, shaped as in the response.
+    /// Available once all items have been read.
+    pub fn metadata(&self) -> Option<&Map<String, Value>> {
+        self.metadata.as_ref()
+    }
+
+    /// Pagination metadata (`@attr` object next to the list), once all items have been read
+    pub fn page_info(&self) -> Option<PageInfo<'static>> {
+        let (_, parents) = self.list_path.split_last()?;
+        let mut map = self.metadata.as_ref()?;
+        for key in parents {
+            map = map.get(key)?.as_object()?;
+        }
+        PageInfo::deserialize(map.get("@attr")?.clone()).ok()
+    }
+
+    /// Path to the list, e.g. `recenttracks.track`, once it has been found
+    pub fn list_path(&self) -> Option<String> {
+        if self.list_path.is_empty() {
+            None
+        } else {
+            Some(self.list_path.join("."))
+        }
+    }
+
+    /// Moves on to the next item. Outer error means that iteration can't go on.
+    fn advance(&mut self) -> Result<Option<Result<L::Item>>> {
+        loop {
+            match self.state {
+                State::Start => {
+                    self.skip_whitespace()?;
+                    self.expect(b'{')?;
+                    self.stack.push((String::new(), Map::new()));
+                    if !self.read_members()? {
+                        self.state = State::Done;
+                        self.expect_end()?;
+                        return self.api_error();
+                    }
+                    if self.single {
+                        self.state = State::Trailer;
+                        let prefix = self.list_path.join(".");
+                        return Ok(Some(self.parse_buffer(prefix)));
+                    }
+                    self.state = State::Items;
+                }
+                State::Items => match self.next_item()? {
+                    Some(item) => return Ok(Some(item)),
+                    None => self.state = State::Trailer,
+                },
+                State::Trailer => {
+                    self.state = State::Done;
+                    self.read_members()?;
+                    self.expect_end()?;
+                }
+                State::Done => return Ok(None),
+            }
+        }
+    }
+
+    /// Reads object members into metadata until either list is found (returns `true`)
+    /// or the root object is over
+    fn read_members(&mut self) -> Result<bool> {
+        loop {
+            self.skip_whitespace()?;
+            match self.peek()? {
+                Some(b',') => self.bump(),
+                Some(b'}') => {
+                    self.bump();
+                    let (key, map) = self.stack.pop().expect("object stack is empty");
+                    match self.stack.last_mut() {
+                        Some(&mut (_, ref mut parent)) => {
+                            parent.insert(key, Value::Object(map));
+                        }
+                        None => {
+                            self.metadata = Some(map);
+                            return Ok(false);
+                        }
+                    }
+                }
+                Some(b'"') => {
+                    self.read_raw()?;
+                    let key: String = serde_json::from_slice(&self.buffer).map_err(|e| Error::Deserialize(e.into()))?;
+                    self.skip_whitespace()?;
+                    self.expect(b':')?;
+                    self.skip_whitespace()?;
+                    let is_list = self.state == State::Start && self.is_list(&key);
+                    match self.peek()? {
+                        Some(b'[') if is_list => {
+                            self.bump();
+                            self.list_path = self.stack.iter().skip(1).map(|(key, _)| key.clone()).collect();
+                            self.list_path.push(key);
+                            return Ok(true);
+                        }
+                        Some(b'{') if is_list => {
+                            self.read_raw()?;
+                            self.single = true;
+                            self.list_path = self.stack.iter().skip(1).map(|(key, _)| key.clone()).collect();
+                            self.list_path.push(key);
+                            return Ok(true);
+                        }
+                        Some(b'{') => {
+                            self.bump();
+                            self.stack.push((key, Map::new()));
+                        }
+                        _ => {
+                            self.read_raw()?;
+                            let value = serde_json::from_slice(&self.buffer).map_err(|e| Error::Deserialize(e.into()))?;
+                            if let Some(&mut (_, ref mut map)) = self.stack.last_mut() {
+                                map.insert(key, value);
+                            }
+                        }
+                    }
+                }
+                Some(_) => return Err(self.syntax_error("expected object key")),
+                None => return Err(self.syntax_error("unexpected end of input")),
+            }
+        }
+    }
+
+    /// Tells whether member with given key of the innermost object being read is the list:
+    /// keys of the enclosing objects have to match wrapper and list keys of the data type
+    fn is_list(&self, key: &str) -> bool {
+        let path = L::WRAPPER.into_iter().chain(L::LIST.iter().cloned());
+        let parents = self.stack.iter().skip(1).map(|(key, _)| key.as_str());
+        path.clone().count() == self.stack.len() && parents.chain(Some(key)).eq(path)
+    }
+
+    /// Reads next list item, `None` means that the list is over
+    fn next_item(&mut self) -> Result<Option<Result<L::Item>>> {
+        loop {
+            self.skip_whitespace()?;
+            match self.peek()? {
+                Some(b']') => {
+                    self.bump();
+                    return Ok(None);
+                }
+                Some(b',') => self.bump(),
+                Some(_) => break,
+                None => return Err(self.syntax_error("unexpected end of input")),
+            }
+        }
+
+        self.read_raw()?;
+        let prefix = format!("{}[{}]", self.list_path.join("."), self.index);
+        self.index += 1;
+        Ok(Some(self.parse_buffer(prefix)))
+    }
+
+    /// Parses item that has been read into the buffer, error path is prefixed with given one
+    fn parse_buffer(&self, prefix: String) -> Result<L::Item> {
+        // Reader-based deserializer doesn't borrow from the buffer, so items are 'static
+        let mut deserializer = serde_json::Deserializer::from_reader(&self.buffer[..]);
+        path_to_error::deserialize(&mut deserializer).map_err(|e| {
+            Error::Deserialize(DeserializeError::from(e).with_input(&self.buffer).with_path_prefix(&prefix))
+        })
+    }
+
+    /// Turns response without a list into API error, if it's one
+    fn api_error(&self) -> Result<Option<Result<L::Item>>> {
+        let metadata = match self.metadata {
+            Some(ref metadata) if metadata.contains_key("error") => metadata,
+            _ => return Ok(None),
+        };
+        match path_to_error::deserialize::<_, ApiError>(Value::Object(metadata.clone())) {
+            Ok(err) => Ok(Some(Err(Error::Api(err)))),
+            Err(e) => Err(Error::Deserialize(DeserializeError::from(e).with_api_error())),
+        }
+    }
+
+    // ----------------------------------------------------------------
+
+    /// Copies next json value as is into the buffer
+    fn read_raw(&mut self) -> Result<()> {
+        self.buffer.clear();
+        let (mut depth, mut in_string, mut escaped) = (0usize, false, false);
+
+        loop {
+            let byte = match self.peek()? {
+                Some(byte) => byte,
+                None if depth == 0 && !in_string && !self.buffer.is_empty() => return Ok(()),
+                None => return Err(self.syntax_error("unexpected end of input")),
+            };
+
+            if in_string {
+                if escaped {
+                    escaped = false;
+                } else if byte == b'\\' {
+                    escaped = true;
+                } else if byte == b'"' {
+                    in_string = false;
+                }
+            } else {
+                match byte {
+                    b'"' => in_string = true,
+                    b'{' | b'[' => depth += 1,
+                    b'}' | b']' if depth == 0 => return Ok(()),
+                    b'}' | b']' => depth -= 1,
+                    b',' | b' ' | b'\t' | b'\r' | b'\n' if depth == 0 => return Ok(()),
+                    _ => {}
+                }
+            }
+
+            self.buffer.push(byte);
+            self.bump();
+
+            if depth == 0 && !in_string && matches!(byte, b'"' | b'}' | b']') {
+                return Ok(());
+            }
+        }
+    }
+
+    fn skip_whitespace(&mut self) -> Result<()> {
+        while let Some(b' ') | Some(b'\t') | Some(b'\r') | Some(b'\n') = self.peek()? {
+            self.bump();
+        }
+        Ok(())
+    }
+
+    fn expect(&mut self, expected: u8) -> Result<()> {
+        match self.peek()? {
+            Some(byte) if byte == expected => {
+                self.bump();
+                Ok(())
+            }
+            _ => Err(self.syntax_error(&format!("expected `{}`", expected as char))),
+        }
+    }
+
+    fn expect_end(&mut self) -> Result<()> {
+        self.skip_whitespace()?;
+        match self.peek()? {
+            None => Ok(()),
+            Some(_) => Err(self.syntax_error("trailing characters")),
+        }
+    }
+
+    fn peek(&mut self) -> Result<Option<u8>> {
+        loop {
+            match self.reader.fill_buf() {
+                Ok(buf) => return Ok(buf.first().cloned()),
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => return Err(Error::Transport(Box::new(e))),
+            }
+        }
+    }
+
+    fn bump(&mut self) {
+        self.reader.consume(1);
+        self.position += 1;
+    }
+
+    fn syntax_error(&self, reason: &str) -> Error {
+        let message = format!("{} at byte {}", reason, self.position);
+        Error::Deserialize(JsonError::custom(message).into())
+    }
+}
+
+impl<L, R> Iterator for ItemStream<L, R>
+where
+    L: Listed,
+    R: Read,
+{
+    type Item = Result<L::Item>;
+
+    fn next(&mut self) -> Option<Result<L::Item>> {
+        match self.advance() {
+            Ok(item) => item,
+            Err(err) => {
+                self.state = State::Done;
+                Some(Err(err))
+            }
+        }
+    }
+}
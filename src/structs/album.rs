@@ -1,11 +1,24 @@
 use std::convert::Into;
 use std::borrow::Cow;
+use std::time::Duration;
 use std::marker::PhantomData;
 
 use url::Url as StdUrl;
 
-use lastfm_type::{LastfmType, Request, RequestParams};
-use super::common::{Url, Image, SearchQuery, str_to_option, str_to_val};
+use lastfm_type::{LastfmType, Request, RequestParams, RequestBuilder, IntoArg};
+use lastfm_type::{Credentials, SessionState, SessionFree, Permits};
+use error::Result;
+use owned::IntoOwned;
+use validate;
+use paginate::{self, Pageable, Paged};
+use stream::Listed;
+use super::common::{Url, Images, SearchQuery, str_to_option, str_to_val};
+use super::common::{val_to_str, option_to_str};
+use super::common::{str_to_option_bool, option_bool_to_str};
+use super::common::{Mbid, mbid_option};
+use super::common::{secs_to_duration, duration_to_secs};
+use super::entity::{self, HasName, HasMbid, HasImages, AlbumIdent};
+use super::wiki::Wiki;
 
 // ----------------------------------------------------------------
 
@@ -17,25 +30,19 @@ pub enum Params<'pr> {
         tags: &'pr str,
     },
     GetInfo {
-        artist: &'pr str,
-        album: &'pr str,
-        mbid: Option<&'pr str>,
-        autocorrect: Option<u32>,
+        album: AlbumIdent<'pr>,
+        autocorrect: Option<bool>,
         lang: Option<&'pr str>,
         username: Option<&'pr str>,
     },
     GetTags {
-        artist: &'pr str,
-        album: &'pr str,
-        mbid: Option<&'pr str>,
-        autocorrect: Option<u32>,
+        album: AlbumIdent<'pr>,
+        autocorrect: Option<bool>,
         user: Option<&'pr str>,
     },
     GetTopTags {
-        artist: &'pr str,
-        album: &'pr str,
-        mbid: Option<&'pr str>,
-        autocorrect: Option<u32>,
+        album: AlbumIdent<'pr>,
+        autocorrect: Option<bool>,
     },
     RemoveTag {
         artist: &'pr str,
@@ -62,17 +69,36 @@ impl<'pr> RequestParams for Params<'pr> {
     }
 
     fn needs_signature(&self) -> bool {
-        match *self {
-            Params::AddTags { .. } => true,
-            Params::RemoveTag { .. } => true,
-            _ => false,
-        }
+        matches!(*self, Params::AddTags { .. } | Params::RemoveTag { .. })
     }
 
     fn needs_session_key(&self) -> bool {
         self.needs_signature()
     }
 
+    fn validate(&self) -> Result<()> {
+        match *self {
+            Params::AddTags { artist, album, tags } => {
+                validate::required("artist", artist)?;
+                validate::required("album", album)?;
+                validate::tags(tags)
+            }
+            Params::GetInfo { ref album, .. } |
+            Params::GetTags { ref album, .. } |
+            Params::GetTopTags { ref album, .. } => album.validate(),
+            Params::RemoveTag { artist, album, tag } => {
+                validate::required("artist", artist)?;
+                validate::required("album", album)?;
+                validate::required("tag", tag)
+            }
+            Params::Search { album, limit, page } => {
+                validate::required("album", album)?;
+                validate::limit(limit, validate::MAX_LIMIT)?;
+                validate::page(page)
+            }
+        }
+    }
+
     fn append_to(&self, url: &mut StdUrl) {
         let mut query = url.query_pairs_mut();
         match *self {
@@ -84,51 +110,38 @@ impl<'pr> RequestParams for Params<'pr> {
                 params!(query, [artist: artist, album: album, tags: tags], []);
             }
             Params::GetInfo {
-                artist,
-                album,
-                mbid,
+                ref album,
                 autocorrect,
                 lang,
                 username,
             } => {
+                album.append_to(&mut query);
                 params!(
                     query,
-                    [artist: artist, album: album],
-                    [mbid: mbid, autocorrect: cv!(autocorrect), lang: lang, username: username]
+                    [],
+                    [autocorrect: &(autocorrect as u32).to_string(), lang: lang, username: username]
                 );
             }
             Params::GetTags {
-                artist,
-                album,
-                mbid,
+                ref album,
                 autocorrect,
                 user,
             } => {
-                query.append_pair("artist", artist);
-                query.append_pair("album", album);
-                if let Some(mbid) = mbid {
-                    query.append_pair("mbid", mbid);
-                }
+                album.append_to(&mut query);
                 if let Some(autocorrect) = autocorrect {
-                    query.append_pair("autocorrect", &autocorrect.to_string());
+                    query.append_pair("autocorrect", &(autocorrect as u32).to_string());
                 }
                 if let Some(user) = user {
                     query.append_pair("user", user);
                 }
             }
             Params::GetTopTags {
-                artist,
-                album,
-                mbid,
+                ref album,
                 autocorrect,
             } => {
-                query.append_pair("artist", artist);
-                query.append_pair("album", album);
-                if let Some(mbid) = mbid {
-                    query.append_pair("mbid", mbid);
-                }
+                album.append_to(&mut query);
                 if let Some(autocorrect) = autocorrect {
-                    query.append_pair("autocorrect", &autocorrect.to_string());
+                    query.append_pair("autocorrect", &(autocorrect as u32).to_string());
                 }
             }
             Params::RemoveTag { artist, album, tag } => {
@@ -149,6 +162,25 @@ impl<'pr> RequestParams for Params<'pr> {
     }
 }
 
+impl<'pr> Pageable for Params<'pr> {
+    fn page(&self) -> Option<u32> {
+        match *self {
+            Params::Search { page, .. } => page,
+            _ => None,
+        }
+    }
+
+    fn set_page(&mut self, value: u32) -> Result<()> {
+        match *self {
+            Params::Search { ref mut page, .. } => {
+                *page = Some(value);
+                Ok(())
+            }
+            _ => Err(paginate::not_paginated(self.method())),
+        }
+    }
+}
+
 // ----------------------------------------------------------------
 
 empty_lastfm_t!(
@@ -159,54 +191,111 @@ empty_lastfm_t!(
         artist: &'rq str,
         album: &'rq str,
         tags: &'rq str
-    ]
+    ],
+    [],
+    session
 );
 
 // ----------------------------------------------------------------
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Track<'dt> {
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
     pub url: Url<'dt>,
-    #[serde(deserialize_with = "str_to_val")]
-    pub duration: u32, 
+    #[serde(default)]
+    #[serde(deserialize_with = "secs_to_duration")]
+    #[serde(serialize_with = "duration_to_secs")]
+    pub duration: Option<Duration>,
     // artist, streamable and @attr fields omitted for now
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(Track { name, url, duration });
+entity_t!(Track: HasName);
+entity_t!(Track => Track);
+
+impl<'dt> From<Track<'dt>> for entity::Track<'dt> {
+    fn from(track: Track<'dt>) -> entity::Track<'dt> {
+        entity::Track {
+            url: track.url.non_empty(),
+            duration: track.duration,
+            ..entity::Track::new(track.name)
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Tracks<'dt> {
     #[serde(borrow)]
     pub track: Option<Vec<Track<'dt>>>,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(Tracks { track });
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Tag1<'dt> {
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
     pub url: Url<'dt>,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(Tag1 { name, url });
+entity_t!(Tag1: HasName);
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Tags<'dt> {
     #[serde(borrow)]
     pub tag: Option<Vec<Tag1<'dt>>>,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(Tags { tag });
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct GetInfo<'dt> {
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
+    #[serde(borrow)]
     pub artist: Cow<'dt, str>,
-    pub mbid: Option<&'dt str>,
+    #[serde(borrow)]
+    #[serde(default)]
+    #[serde(deserialize_with = "mbid_option")]
+    pub mbid: Option<Mbid<'dt>>,
     pub url: Url<'dt>,
-    pub image: Vec<Image<'dt>>,
+    pub image: Images<'dt>,
     #[serde(deserialize_with = "str_to_val")]
+    #[serde(serialize_with = "val_to_str")]
     pub listeners: u32,
     #[serde(deserialize_with = "str_to_val")]
+    #[serde(serialize_with = "val_to_str")]
     pub playcount: u32,
     #[serde(default)]
     #[serde(deserialize_with = "str_to_option")]
+    #[serde(serialize_with = "option_to_str")]
     pub userplaycount: Option<u32>,
     pub tracks: Tracks<'dt>,
     pub tags: Tags<'dt>,
+    #[serde(borrow)]
+    pub wiki: Option<Wiki<'dt>>,
+}
+
+owned_t!(GetInfo {
+    name, artist, mbid, url, image, listeners, playcount, userplaycount, tracks, tags, wiki,
+});
+entity_t!(GetInfo: HasName, HasMbid, HasImages);
+entity_t!(GetInfo => Album);
+
+impl<'dt> From<GetInfo<'dt>> for entity::Album<'dt> {
+    fn from(album: GetInfo<'dt>) -> entity::Album<'dt> {
+        entity::Album {
+            mbid: album.mbid,
+            url: album.url.non_empty(),
+            artist: Some(entity::ArtistRef::from_name(album.artist)),
+            image: album.image,
+            playcount: Some(album.playcount),
+            listeners: Some(album.listeners),
+            ..entity::Album::new(album.name)
+        }
+    }
 }
 
 lastfm_t!(
@@ -216,12 +305,12 @@ lastfm_t!(
     Params,
     GetInfo,
     [
-        artist: &'rq str,
-        album: &'rq str,
-        mbid: Option<&'rq str>,
-        autocorrect: Option<u32>,
-        lang: Option<&'rq str>,
-        username: Option<&'rq str>
+        album: impl Into<AlbumIdent<'rq>>
+    ],
+    [
+        autocorrect: bool,
+        lang: &'rq str,
+        username: &'rq str
     ]
 );
 
@@ -236,31 +325,38 @@ lastfm_t!(
     Params,
     GetTags,
     [
-        artist: &'rq str,
-        album: &'rq str,
-        mbid: Option<&'rq str>,
-        autocorrect: Option<u32>,
-        user: Option<&'rq str>
+        album: impl Into<AlbumIdent<'rq>>
+    ],
+    [
+        autocorrect: bool,
+        user: &'rq str
     ]
 );
 
 // ----------------------------------------------------------------
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Tag2<'dt> {
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
     #[serde(default)]
     #[serde(deserialize_with = "str_to_option")]
+    #[serde(serialize_with = "option_to_str")]
     pub count: Option<u32>,
     pub url: Url<'dt>,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(Tag2 { name, count, url });
+entity_t!(Tag2: HasName);
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct GetTopTags<'dt> {
     #[serde(borrow)]
     pub tag: Option<Vec<Tag2<'dt>>>,
 }
 
+owned_t!(GetTopTags { tag });
+
 lastfm_t!(
     toptags,
     GetTopTags,
@@ -268,10 +364,10 @@ lastfm_t!(
     Params,
     GetTopTags,
     [
-        artist: &'rq str,
-        album: &'rq str,
-        mbid: Option<&'rq str>,
-        autocorrect: Option<u32>
+        album: impl Into<AlbumIdent<'rq>>
+    ],
+    [
+        autocorrect: bool
     ]
 );
 
@@ -285,29 +381,55 @@ empty_lastfm_t!(
         artist: &'rq str,
         album: &'rq str,
         tag: &'rq str
-    ]
+    ],
+    [],
+    session
 );
 
 // ----------------------------------------------------------------
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Album<'dt> {
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
-    pub mbid: Option<&'dt str>,
+    #[serde(borrow)]
+    #[serde(default)]
+    #[serde(deserialize_with = "mbid_option")]
+    pub mbid: Option<Mbid<'dt>>,
+    #[serde(borrow)]
     pub artist: Cow<'dt, str>,
     pub url: Url<'dt>,
-    pub image: Vec<Image<'dt>>,
+    pub image: Images<'dt>,
     #[serde(default)]
-    #[serde(deserialize_with = "str_to_option")]
-    pub streamable: Option<u32>,
+    #[serde(deserialize_with = "str_to_option_bool")]
+    #[serde(serialize_with = "option_bool_to_str")]
+    pub streamable: Option<bool>,
+}
+
+owned_t!(Album { name, mbid, artist, url, image, streamable });
+entity_t!(Album: HasName, HasMbid, HasImages);
+entity_t!(Album => Album);
+
+impl<'dt> From<Album<'dt>> for entity::Album<'dt> {
+    fn from(album: Album<'dt>) -> entity::Album<'dt> {
+        entity::Album {
+            mbid: album.mbid,
+            url: album.url.non_empty(),
+            artist: Some(entity::ArtistRef::from_name(album.artist)),
+            image: album.image,
+            ..entity::Album::new(album.name)
+        }
+    }
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct SearchData<'dt> {
     #[serde(borrow)]
     pub album: Option<Vec<Album<'dt>>>,
 }
 
+owned_t!(SearchData { album });
+
 opensearch_t!(
     results,
     Search,
@@ -316,5 +438,8 @@ opensearch_t!(
     SearchData,
     Params,
     Search,
-    [album: &'rq str, limit: Option<u32>, page: Option<u32>]
+    [album: &'rq str],
+    [limit: u32, page: u32]
 );
+
+paged_t!(Search, albummatches.album: Album);
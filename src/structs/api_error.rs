@@ -1,6 +1,7 @@
 use std::error::Error as StdError;
 use std::fmt::{Display, Formatter, Result as FmtResult};
 use serde::de::{Deserialize, Deserializer, Visitor};
+use serde::ser::{Serialize, Serializer};
 
 /// Lastfm API error codes
 /// Source: https://www.last.fm/api/errorcodes
@@ -39,14 +40,14 @@ impl ApiErrorKind {
     /// Constructs error kind variant from numerical code
     fn from_u64(value: u64) -> ApiErrorKind {
         match value {
-            02 => ApiErrorKind::InvalidService,
-            03 => ApiErrorKind::InvalidMethod,
-            04 => ApiErrorKind::AuthenticationFailed,
-            05 => ApiErrorKind::InvalidFormat,
-            06 => ApiErrorKind::InvalidParameters,
-            07 => ApiErrorKind::InvalidResource,
-            08 => ApiErrorKind::OperationFailed,
-            09 => ApiErrorKind::InvalidSessionKey,
+            2 => ApiErrorKind::InvalidService,
+            3 => ApiErrorKind::InvalidMethod,
+            4 => ApiErrorKind::AuthenticationFailed,
+            5 => ApiErrorKind::InvalidFormat,
+            6 => ApiErrorKind::InvalidParameters,
+            7 => ApiErrorKind::InvalidResource,
+            8 => ApiErrorKind::OperationFailed,
+            9 => ApiErrorKind::InvalidSessionKey,
             10 => ApiErrorKind::InvalidApiKey,
             11 => ApiErrorKind::ServiceOffline,
             12 => ApiErrorKind::SubscribersOnly,
@@ -69,6 +70,39 @@ impl ApiErrorKind {
         }
     }
 
+    /// Returns numerical code of the variant (0 for unknown ones)
+    pub fn code(&self) -> u64 {
+        match *self {
+            ApiErrorKind::InvalidService => 2,
+            ApiErrorKind::InvalidMethod => 3,
+            ApiErrorKind::AuthenticationFailed => 4,
+            ApiErrorKind::InvalidFormat => 5,
+            ApiErrorKind::InvalidParameters => 6,
+            ApiErrorKind::InvalidResource => 7,
+            ApiErrorKind::OperationFailed => 8,
+            ApiErrorKind::InvalidSessionKey => 9,
+            ApiErrorKind::InvalidApiKey => 10,
+            ApiErrorKind::ServiceOffline => 11,
+            ApiErrorKind::SubscribersOnly => 12,
+            ApiErrorKind::InvalidMethodSignature => 13,
+            ApiErrorKind::UnauthorizedToken => 14,
+            ApiErrorKind::ItemNotAvailableForStreaming => 15,
+            ApiErrorKind::ServiceTemporaryUnavailable => 16,
+            ApiErrorKind::LoginRequired => 17,
+            ApiErrorKind::TrialExpired => 18,
+            ApiErrorKind::NotEnoughContent => 20,
+            ApiErrorKind::NotEnoughMembers => 21,
+            ApiErrorKind::NotEnoughFans => 22,
+            ApiErrorKind::NotEnoughNeighbours => 23,
+            ApiErrorKind::NoPeakRadio => 24,
+            ApiErrorKind::RadioNotFound => 25,
+            ApiErrorKind::ApiKeySuspended => 26,
+            ApiErrorKind::Deprecated => 27,
+            ApiErrorKind::RateLimitExceeded => 29,
+            ApiErrorKind::Unknown => 0,
+        }
+    }
+
     /// Returns error code description
     pub fn description(&self) -> &'static str {
         match *self {
@@ -119,6 +153,18 @@ impl ApiErrorKind {
             _ => "This error code is not covered by official API reference",
         }
     }
+
+    /// Indicates whether error is transient by nature,
+    /// so the same request may succeed if sent again later
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            *self,
+            ApiErrorKind::OperationFailed |
+            ApiErrorKind::ServiceOffline |
+            ApiErrorKind::ServiceTemporaryUnavailable |
+            ApiErrorKind::RateLimitExceeded
+        )
+    }
 }
 
 impl<'de> Deserialize<'de> for ApiErrorKind {
@@ -146,13 +192,22 @@ impl<'de> Deserialize<'de> for ApiErrorKind {
     }
 }
 
+impl Serialize for ApiErrorKind {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_u64(self.code())
+    }
+}
+
 impl Display for ApiErrorKind {
     fn fmt(&self, f: &mut Formatter) -> FmtResult {
         write!(f, "{}", self.description())
     }
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct ApiError {
     pub error: ApiErrorKind,
     pub message: String, 
@@ -4,8 +4,20 @@ use std::marker::PhantomData;
 
 use url::Url as StdUrl;
 
-use lastfm_type::{LastfmType, Request, RequestParams};
-use super::common::{Url, Image, SearchQuery, str_to_option, str_to_val};
+use lastfm_type::{LastfmType, Request, RequestParams, RequestBuilder, IntoArg};
+use lastfm_type::{Credentials, SessionState, SessionFree, Permits};
+use error::Result;
+use owned::IntoOwned;
+use validate;
+use paginate::{self, Pageable, Paged};
+use stream::Listed;
+use super::common::{PageInfo, Url, Images, SearchQuery, str_to_option, str_to_val};
+use super::common::{val_to_str, option_to_str};
+use super::common::{str_to_bool, bool_to_str, str_to_option_bool, option_bool_to_str};
+use super::common::{Mbid, mbid_option};
+use super::entity::{self, HasName, HasMbid, HasImages, ArtistIdent};
+use super::wiki::Wiki;
+pub use super::wiki::{Link, Links};
 
 // ----------------------------------------------------------------
 
@@ -14,40 +26,34 @@ pub enum Params<'pr> {
     AddTags { artist: &'pr str, tags: &'pr str },
     GetCorrection { artist: &'pr str },
     GetInfo {
-        artist: &'pr str,
-        mbid: Option<&'pr str>,
-        autocorrect: Option<u32>,
+        artist: ArtistIdent<'pr>,
+        autocorrect: Option<bool>,
         lang: Option<&'pr str>,
         username: Option<&'pr str>,
     },
     GetSimilar {
-        artist: &'pr str,
-        mbid: Option<&'pr str>,
-        autocorrect: Option<u32>,
+        artist: ArtistIdent<'pr>,
+        autocorrect: Option<bool>,
         limit: Option<u32>,
     },
     GetTags {
-        artist: &'pr str,
-        mbid: Option<&'pr str>,
-        autocorrect: Option<u32>,
+        artist: ArtistIdent<'pr>,
+        autocorrect: Option<bool>,
         user: Option<&'pr str>,
     },
     GetTopAlbums {
-        artist: &'pr str,
-        mbid: Option<&'pr str>,
-        autocorrect: Option<u32>,
+        artist: ArtistIdent<'pr>,
+        autocorrect: Option<bool>,
         limit: Option<u32>,
         page: Option<u32>,
     },
     GetTopTags {
-        artist: &'pr str,
-        mbid: Option<&'pr str>,
-        autocorrect: Option<u32>,
+        artist: ArtistIdent<'pr>,
+        autocorrect: Option<bool>,
     },
     GetTopTracks {
-        artist: &'pr str,
-        mbid: Option<&'pr str>,
-        autocorrect: Option<u32>,
+        artist: ArtistIdent<'pr>,
+        autocorrect: Option<bool>,
         limit: Option<u32>,
         page: Option<u32>,
     },
@@ -76,17 +82,45 @@ impl<'pr> RequestParams for Params<'pr> {
     }
 
     fn needs_signature(&self) -> bool {
-        match *self {
-            Params::AddTags { .. } => true,
-            Params::RemoveTag { .. } => true,
-            _ => false,
-        }
+        matches!(*self, Params::AddTags { .. } | Params::RemoveTag { .. })
     }
 
     fn needs_session_key(&self) -> bool {
         self.needs_signature()
     }
 
+    fn validate(&self) -> Result<()> {
+        match *self {
+            Params::AddTags { artist, tags } => {
+                validate::required("artist", artist)?;
+                validate::tags(tags)
+            }
+            Params::GetCorrection { artist } => validate::required("artist", artist),
+            Params::GetInfo { ref artist, .. } |
+            Params::GetTags { ref artist, .. } |
+            Params::GetTopTags { ref artist, .. } => artist.validate(),
+            Params::GetSimilar { ref artist, limit, .. } => {
+                artist.validate()?;
+                validate::limit(limit, validate::MAX_LIMIT)
+            }
+            Params::GetTopAlbums { ref artist, limit, page, .. } |
+            Params::GetTopTracks { ref artist, limit, page, .. } => {
+                artist.validate()?;
+                validate::limit(limit, validate::MAX_LIMIT)?;
+                validate::page(page)
+            }
+            Params::RemoveTag { artist, tag } => {
+                validate::required("artist", artist)?;
+                validate::required("tag", tag)
+            }
+            Params::Search { artist, limit, page } => {
+                validate::required("artist", artist)?;
+                validate::limit(limit, validate::MAX_LIMIT)?;
+                validate::page(page)
+            }
+        }
+    }
+
     fn append_to(&self, url: &mut StdUrl) {
         let mut query = url.query_pairs_mut();
         match *self {
@@ -98,18 +132,14 @@ impl<'pr> RequestParams for Params<'pr> {
                 query.append_pair("artist", artist);
             }
             Params::GetInfo {
-                artist,
-                mbid,
+                ref artist,
                 autocorrect,
                 lang,
                 username,
             } => {
-                query.append_pair("artist", artist);
-                if let Some(mbid) = mbid {
-                    query.append_pair("mbid", mbid);
-                }
+                artist.append_to(&mut query);
                 if let Some(autocorrect) = autocorrect {
-                    query.append_pair("autocorrect", &autocorrect.to_string());
+                    query.append_pair("autocorrect", &(autocorrect as u32).to_string());
                 }
                 if let Some(lang) = lang {
                     query.append_pair("lang", lang);
@@ -119,52 +149,40 @@ impl<'pr> RequestParams for Params<'pr> {
                 }
             }
             Params::GetSimilar {
-                artist,
-                mbid,
+                ref artist,
                 autocorrect,
                 limit,
             } => {
-                query.append_pair("artist", artist);
-                if let Some(mbid) = mbid {
-                    query.append_pair("mbid", mbid);
-                }
+                artist.append_to(&mut query);
                 if let Some(autocorrect) = autocorrect {
-                    query.append_pair("autocorrect", &autocorrect.to_string());
+                    query.append_pair("autocorrect", &(autocorrect as u32).to_string());
                 }
                 if let Some(limit) = limit {
                     query.append_pair("limit", &limit.to_string());
                 }
             }
             Params::GetTags {
-                artist,
-                mbid,
+                ref artist,
                 autocorrect,
                 user,
             } => {
-                query.append_pair("artist", artist);
-                if let Some(mbid) = mbid {
-                    query.append_pair("mbid", mbid);
-                }
+                artist.append_to(&mut query);
                 if let Some(autocorrect) = autocorrect {
-                    query.append_pair("autocorrect", &autocorrect.to_string());
+                    query.append_pair("autocorrect", &(autocorrect as u32).to_string());
                 }
                 if let Some(user) = user {
                     query.append_pair("user", user);
                 }
             }
             Params::GetTopAlbums {
-                artist,
-                mbid,
+                ref artist,
                 autocorrect,
                 limit,
                 page,
             } => {
-                query.append_pair("artist", artist);
-                if let Some(mbid) = mbid {
-                    query.append_pair("mbid", mbid);
-                }
+                artist.append_to(&mut query);
                 if let Some(autocorrect) = autocorrect {
-                    query.append_pair("autocorrect", &autocorrect.to_string());
+                    query.append_pair("autocorrect", &(autocorrect as u32).to_string());
                 }
                 if let Some(limit) = limit {
                     query.append_pair("limit", &limit.to_string());
@@ -174,31 +192,23 @@ impl<'pr> RequestParams for Params<'pr> {
                 }
             }
             Params::GetTopTags {
-                artist,
-                mbid,
+                ref artist,
                 autocorrect,
             } => {
-                query.append_pair("artist", artist);
-                if let Some(mbid) = mbid {
-                    query.append_pair("mbid", mbid);
-                }
+                artist.append_to(&mut query);
                 if let Some(autocorrect) = autocorrect {
-                    query.append_pair("autocorrect", &autocorrect.to_string());
+                    query.append_pair("autocorrect", &(autocorrect as u32).to_string());
                 }
             }
             Params::GetTopTracks {
-                artist,
-                mbid,
+                ref artist,
                 autocorrect,
                 limit,
                 page,
             } => {
-                query.append_pair("artist", artist);
-                if let Some(mbid) = mbid {
-                    query.append_pair("mbid", mbid);
-                }
+                artist.append_to(&mut query);
                 if let Some(autocorrect) = autocorrect {
-                    query.append_pair("autocorrect", &autocorrect.to_string());
+                    query.append_pair("autocorrect", &(autocorrect as u32).to_string());
                 }
                 if let Some(limit) = limit {
                     query.append_pair("limit", &limit.to_string());
@@ -228,6 +238,29 @@ impl<'pr> RequestParams for Params<'pr> {
     }
 }
 
+impl<'pr> Pageable for Params<'pr> {
+    fn page(&self) -> Option<u32> {
+        match *self {
+            Params::GetTopAlbums { page, .. } |
+            Params::GetTopTracks { page, .. } |
+            Params::Search { page, .. } => page,
+            _ => None,
+        }
+    }
+
+    fn set_page(&mut self, value: u32) -> Result<()> {
+        match *self {
+            Params::GetTopAlbums { ref mut page, .. } |
+            Params::GetTopTracks { ref mut page, .. } |
+            Params::Search { ref mut page, .. } => {
+                *page = Some(value);
+                Ok(())
+            }
+            _ => Err(paginate::not_paginated(self.method())),
+        }
+    }
+}
+
 // ----------------------------------------------------------------
 
 empty_lastfm_t!(
@@ -237,167 +270,232 @@ empty_lastfm_t!(
     [
         artist: &'rq str,
         tags: &'rq str
-    ]
+    ],
+    [],
+    session
 );
 
 // ----------------------------------------------------------------
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Artist1<'dt> {
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
-    pub mbid: Option<&'dt str>,
+    #[serde(borrow)]
+    #[serde(default)]
+    #[serde(deserialize_with = "mbid_option")]
+    pub mbid: Option<Mbid<'dt>>,
     pub url: Url<'dt>,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(Artist1 { name, mbid, url });
+entity_t!(Artist1: HasName, HasMbid);
+entity_t!(Artist1 => Artist);
+
+impl<'dt> From<Artist1<'dt>> for entity::Artist<'dt> {
+    fn from(artist: Artist1<'dt>) -> entity::Artist<'dt> {
+        entity::Artist { mbid: artist.mbid, url: artist.url.non_empty(), ..entity::Artist::new(artist.name) }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Correction<'dt> {
     #[serde(borrow)]
     pub artist: Option<Artist1<'dt>>,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(Correction { artist });
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct GetCorrections<'dt> {
     #[serde(borrow)]
     pub correction: Option<Correction<'dt>>,
 }
 
+owned_t!(GetCorrections { correction });
+
 lastfm_t!(
     corrections,
     GetCorrections,
     _Corrections,
     Params,
     GetCorrection,
-    [artist: &'rq str]
+    [artist: &'rq str],
+    []
 );
 
 // ----------------------------------------------------------------
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Stats {
     #[serde(deserialize_with = "str_to_val")]
+    #[serde(serialize_with = "val_to_str")]
     pub listeners: u32,
     #[serde(deserialize_with = "str_to_val")]
+    #[serde(serialize_with = "val_to_str")]
     pub playcount: u32,
     #[serde(default)]
     #[serde(deserialize_with = "str_to_option")]
+    #[serde(serialize_with = "option_to_str")]
     pub userplaycount: Option<u32>,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(Stats);
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Similar1<'dt> {
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
     pub url: Url<'dt>,
-    pub image: Vec<Image<'dt>>,
+    pub image: Images<'dt>,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(Similar1 { name, url, image });
+entity_t!(Similar1: HasName, HasImages);
+entity_t!(Similar1 => Artist);
+
+impl<'dt> From<Similar1<'dt>> for entity::Artist<'dt> {
+    fn from(artist: Similar1<'dt>) -> entity::Artist<'dt> {
+        entity::Artist { url: artist.url.non_empty(), image: artist.image, ..entity::Artist::new(artist.name) }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct SimilarList1<'dt> {
     #[serde(borrow)]
     pub artist: Option<Vec<Similar1<'dt>>>,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(SimilarList1 { artist });
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Tag1<'dt> {
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
     pub url: Url<'dt>,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(Tag1 { name, url });
+entity_t!(Tag1: HasName);
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Tags<'dt> {
     #[serde(borrow)]
     pub tag: Option<Vec<Tag1<'dt>>>,
 }
 
-#[derive(Deserialize, Debug)]
-pub struct Link<'dt> {
-    #[serde(rename = "#text")]
-    pub text: Cow<'dt, str>,
-    pub rel: Cow<'dt, str>,
-    pub href: Url<'dt>,
-}
+owned_t!(Tags { tag });
 
-#[derive(Deserialize, Debug)]
-pub struct Links<'dt> {
-    #[serde(borrow)]
-    pub link: Option<Link<'dt>>,
-}
+pub type Bio<'dt> = Wiki<'dt>;
 
-#[derive(Deserialize, Debug)]
-pub struct Bio<'dt> {
-    #[serde(borrow)]
-    pub links: Links<'dt>,
-    pub published: Option<&'dt str>,
-    pub summary: Option<String>, // may have escape sequences, therefore String
-    pub content: Option<String>, // may have escape sequences, therefore String
-}
-
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct GetInfo<'dt> {
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
-    pub mbid: Option<&'dt str>,
+    #[serde(borrow)]
+    #[serde(default)]
+    #[serde(deserialize_with = "mbid_option")]
+    pub mbid: Option<Mbid<'dt>>,
     pub url: Url<'dt>,
-    pub image: Vec<Image<'dt>>,
+    pub image: Images<'dt>,
     #[serde(default)]
-    #[serde(deserialize_with = "str_to_option")]
-    pub streamable: Option<u32>,
-    #[serde(deserialize_with = "str_to_val")]
-    pub ontour: u32,
+    #[serde(deserialize_with = "str_to_option_bool")]
+    #[serde(serialize_with = "option_bool_to_str")]
+    pub streamable: Option<bool>,
+    #[serde(deserialize_with = "str_to_bool")]
+    #[serde(serialize_with = "bool_to_str")]
+    pub ontour: bool,
     pub stats: Stats,
     #[serde(borrow)]
     pub similar: SimilarList1<'dt>,
     #[serde(borrow)]
     pub tags: Tags<'dt>,
+    #[serde(borrow)]
     pub bio: Bio<'dt>,
 }
 
+owned_t!(GetInfo { name, mbid, url, image, streamable, ontour, stats, similar, tags, bio });
+entity_t!(GetInfo: HasName, HasMbid, HasImages);
+entity_t!(GetInfo => Artist);
+
+impl<'dt> From<GetInfo<'dt>> for entity::Artist<'dt> {
+    fn from(artist: GetInfo<'dt>) -> entity::Artist<'dt> {
+        entity::Artist {
+            name: artist.name,
+            mbid: artist.mbid,
+            url: artist.url.non_empty(),
+            image: artist.image,
+            playcount: Some(artist.stats.playcount),
+            listeners: Some(artist.stats.listeners),
+            streamable: artist.streamable,
+        }
+    }
+}
+
 lastfm_t!(
     artist,
     GetInfo,
     _Info,
     Params,
     GetInfo,
+    [artist: impl Into<ArtistIdent<'rq>>],
     [
-        artist: &'rq str,
-        mbid: Option<&'rq str>,
-        autocorrect: Option<u32>,
-        lang: Option<&'rq str>,
-        username: Option<&'rq str>
+        autocorrect: bool,
+        lang: &'rq str,
+        username: &'rq str
     ]
 );
 
 // ----------------------------------------------------------------
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Similar2<'dt> {
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
-    pub mbid: Option<&'dt str>,
+    #[serde(borrow)]
+    #[serde(default)]
+    #[serde(deserialize_with = "mbid_option")]
+    pub mbid: Option<Mbid<'dt>>,
     #[serde(rename = "match")]
     #[serde(deserialize_with = "str_to_val")]
+    #[serde(serialize_with = "val_to_str")]
     pub similar_match: f32,
     pub url: Url<'dt>,
-    pub image: Vec<Image<'dt>>,
+    pub image: Images<'dt>,
     #[serde(default)]
-    #[serde(deserialize_with = "str_to_option")]
-    pub streamable: Option<u32>,
+    #[serde(deserialize_with = "str_to_option_bool")]
+    #[serde(serialize_with = "option_bool_to_str")]
+    pub streamable: Option<bool>,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(Similar2 { name, mbid, similar_match, url, image, streamable });
+entity_t!(Similar2: HasName, HasMbid, HasImages);
+entity_t!(Similar2 => Artist);
+
+impl<'dt> From<Similar2<'dt>> for entity::Artist<'dt> {
+    fn from(artist: Similar2<'dt>) -> entity::Artist<'dt> {
+        entity::Artist { mbid: artist.mbid, url: artist.url.non_empty(), image: artist.image, streamable: artist.streamable, ..entity::Artist::new(artist.name) }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct GetSimilar<'dt> {
     #[serde(borrow)]
     pub artist: Option<Vec<Similar2<'dt>>>,
 }
 
+owned_t!(GetSimilar { artist });
+
 lastfm_t!(
     similarartists,
     GetSimilar,
     _SimilarList,
     Params,
     GetSimilar,
+    [artist: impl Into<ArtistIdent<'rq>>],
     [
-        artist: &'rq str,
-        mbid: Option<&'rq str>,
-        autocorrect: Option<u32>,
-        limit: Option<u32>
+        autocorrect: bool,
+        limit: u32
     ]
 );
 
@@ -411,121 +509,196 @@ lastfm_t!(
     _UserTags,
     Params,
     GetTags,
+    [artist: impl Into<ArtistIdent<'rq>>],
     [
-        artist: &'rq str,
-        mbid: Option<&'rq str>,
-        autocorrect: Option<u32>,
-        user: Option<&'rq str>
+        autocorrect: bool,
+        user: &'rq str
     ]
 );
 
 // ----------------------------------------------------------------
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Album<'dt> {
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
-    pub mbid: Option<&'dt str>,
+    #[serde(borrow)]
+    #[serde(default)]
+    #[serde(deserialize_with = "mbid_option")]
+    pub mbid: Option<Mbid<'dt>>,
     pub url: Url<'dt>,
     pub playcount: u32,
     pub artist: Artist1<'dt>,
-    pub image: Vec<Image<'dt>>,
+    pub image: Images<'dt>,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(Album { name, mbid, url, playcount, artist, image });
+entity_t!(Album: HasName, HasMbid, HasImages);
+entity_t!(Album => Album);
+
+impl<'dt> From<Album<'dt>> for entity::Album<'dt> {
+    fn from(album: Album<'dt>) -> entity::Album<'dt> {
+        entity::Album {
+            mbid: album.mbid,
+            url: album.url.non_empty(),
+            artist: Some(album.artist.into()),
+            image: album.image,
+            playcount: Some(album.playcount),
+            ..entity::Album::new(album.name)
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct GetTopAlbums<'dt> {
     #[serde(borrow)]
     pub album: Option<Vec<Album<'dt>>>,
+    #[serde(rename = "@attr")]
+    pub page_info: Option<PageInfo<'dt>>,
 }
 
+owned_t!(GetTopAlbums { album, page_info });
+
 lastfm_t!(
     topalbums,
     GetTopAlbums,
     _TopAlbums,
     Params,
     GetTopAlbums,
+    [artist: impl Into<ArtistIdent<'rq>>],
     [
-        artist: &'rq str,
-        mbid: Option<&'rq str>,
-        autocorrect: Option<u32>,
-        limit: Option<u32>,
-        page: Option<u32>
+        autocorrect: bool,
+        limit: u32,
+        page: u32
     ]
 );
 
+paged_t!(GetTopAlbums, album: Album);
+
 // ----------------------------------------------------------------
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Tag2<'dt> {
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
     pub count: u32,
     pub url: Url<'dt>,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(Tag2 { name, count, url });
+entity_t!(Tag2: HasName);
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct GetTopTags<'dt> {
     #[serde(borrow)]
     pub tag: Option<Vec<Tag2<'dt>>>,
 }
 
+owned_t!(GetTopTags { tag });
+
 lastfm_t!(
     toptags,
     GetTopTags,
     _TopTags,
     Params,
     GetTopTags,
+    [artist: impl Into<ArtistIdent<'rq>>],
     [
-        artist: &'rq str,
-        mbid: Option<&'rq str>,
-        autocorrect: Option<u32>
+        autocorrect: bool
     ]
 );
 
 // ----------------------------------------------------------------
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Artist<'dt> {
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
-    pub mbid: Option<&'dt str>,
+    #[serde(borrow)]
+    #[serde(default)]
+    #[serde(deserialize_with = "mbid_option")]
+    pub mbid: Option<Mbid<'dt>>,
     pub url: Url<'dt>,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(Artist { name, mbid, url });
+entity_t!(Artist: HasName, HasMbid);
+entity_t!(Artist => Artist);
+
+impl<'dt> From<Artist<'dt>> for entity::Artist<'dt> {
+    fn from(artist: Artist<'dt>) -> entity::Artist<'dt> {
+        entity::Artist { mbid: artist.mbid, url: artist.url.non_empty(), ..entity::Artist::new(artist.name) }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Track<'dt> {
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
-    pub mbid: Option<&'dt str>,
+    #[serde(borrow)]
+    #[serde(default)]
+    #[serde(deserialize_with = "mbid_option")]
+    pub mbid: Option<Mbid<'dt>>,
     pub url: Url<'dt>,
     #[serde(deserialize_with = "str_to_val")]
+    #[serde(serialize_with = "val_to_str")]
     pub playcount: u32,
     #[serde(deserialize_with = "str_to_val")]
+    #[serde(serialize_with = "val_to_str")]
     pub listeners: u32,
     #[serde(default)]
-    #[serde(deserialize_with = "str_to_option")]
-    pub streamable: Option<u32>,
+    #[serde(deserialize_with = "str_to_option_bool")]
+    #[serde(serialize_with = "option_bool_to_str")]
+    pub streamable: Option<bool>,
     pub artist: Artist<'dt>,
-    pub image: Vec<Image<'dt>>,
+    pub image: Images<'dt>,
+}
+
+owned_t!(Track { name, mbid, url, playcount, listeners, streamable, artist, image });
+entity_t!(Track: HasName, HasMbid, HasImages);
+entity_t!(Track => Track);
+
+impl<'dt> From<Track<'dt>> for entity::Track<'dt> {
+    fn from(track: Track<'dt>) -> entity::Track<'dt> {
+        entity::Track {
+            mbid: track.mbid,
+            url: track.url.non_empty(),
+            artist: Some(track.artist.into()),
+            image: track.image,
+            playcount: Some(track.playcount),
+            listeners: Some(track.listeners),
+            streamable: track.streamable,
+            ..entity::Track::new(track.name)
+        }
+    }
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct GetTopTracks<'dt> {
     #[serde(borrow)]
     pub track: Option<Vec<Track<'dt>>>,
+    #[serde(rename = "@attr")]
+    pub page_info: Option<PageInfo<'dt>>,
 }
 
+owned_t!(GetTopTracks { track, page_info });
+
 lastfm_t!(
     toptracks,
     GetTopTracks,
     _TopTracks,
     Params,
     GetTopTracks,
+    [artist: impl Into<ArtistIdent<'rq>>],
     [
-        artist: &'rq str,
-        mbid: Option<&'rq str>,
-        autocorrect: Option<u32>,
-        limit: Option<u32>,
-        page: Option<u32>
+        autocorrect: bool,
+        limit: u32,
+        page: u32
     ]
 );
 
+paged_t!(GetTopTracks, track: Track);
+
 // ----------------------------------------------------------------
 
 empty_lastfm_t!(
@@ -535,30 +708,57 @@ empty_lastfm_t!(
     [
         artist: &'rq str,
         tag: &'rq str
-    ]
+    ],
+    [],
+    session
 );
 
 // ----------------------------------------------------------------
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Artist2<'dt> {
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
-    pub mbid: Option<&'dt str>,
+    #[serde(borrow)]
+    #[serde(default)]
+    #[serde(deserialize_with = "mbid_option")]
+    pub mbid: Option<Mbid<'dt>>,
     pub url: Url<'dt>,
     #[serde(deserialize_with = "str_to_val")]
+    #[serde(serialize_with = "val_to_str")]
     pub listeners: u32,
     #[serde(default)]
-    #[serde(deserialize_with = "str_to_option")]
-    pub streamable: Option<u32>,
-    pub image: Vec<Image<'dt>>,
+    #[serde(deserialize_with = "str_to_option_bool")]
+    #[serde(serialize_with = "option_bool_to_str")]
+    pub streamable: Option<bool>,
+    pub image: Images<'dt>,
+}
+
+owned_t!(Artist2 { name, mbid, url, listeners, streamable, image });
+entity_t!(Artist2: HasName, HasMbid, HasImages);
+entity_t!(Artist2 => Artist);
+
+impl<'dt> From<Artist2<'dt>> for entity::Artist<'dt> {
+    fn from(artist: Artist2<'dt>) -> entity::Artist<'dt> {
+        entity::Artist {
+            mbid: artist.mbid,
+            url: artist.url.non_empty(),
+            image: artist.image,
+            listeners: Some(artist.listeners),
+            streamable: artist.streamable,
+            ..entity::Artist::new(artist.name)
+        }
+    }
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct SearchData<'dt> {
     #[serde(borrow)]
     pub artist: Option<Vec<Artist2<'dt>>>,
 }
 
+owned_t!(SearchData { artist });
+
 opensearch_t!(
     results,
     Search,
@@ -567,5 +767,8 @@ opensearch_t!(
     SearchData,
     Params,
     Search,
-    [artist: &'rq str, limit: Option<u32>, page: Option<u32>]
+    [artist: &'rq str],
+    [limit: u32, page: u32]
 );
+
+paged_t!(Search, artistmatches.artist: Artist2);
@@ -1,7 +1,12 @@
 use std::borrow::Cow;
 use url::Url as StdUrl;
 
-use lastfm_type::{LastfmType, Request, RequestParams};
+use lastfm_type::{LastfmType, Request, RequestParams, RequestBuilder};
+use lastfm_type::{Credentials, SessionState, SessionFree, Permits};
+use error::Result;
+use owned::IntoOwned;
+use validate;
+use super::common::{str_to_bool, bool_to_str};
 
 // ----------------------------------------------------------------
 
@@ -32,6 +37,17 @@ impl<'pr> RequestParams for Params<'pr> {
         false
     }
 
+    fn validate(&self) -> Result<()> {
+        match *self {
+            Params::GetMobileSession { username, password } => {
+                validate::required("username", username)?;
+                validate::required("password", password)
+            }
+            Params::GetSession { token } => validate::required("token", token),
+            Params::GetToken => Ok(()),
+        }
+    }
+
     fn append_to(&self, url: &mut StdUrl) {
         let mut query = url.query_pairs_mut();
         match *self {
@@ -48,50 +64,68 @@ impl<'pr> RequestParams for Params<'pr> {
 
 // ----------------------------------------------------------------
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct GetMobileSession<'dt> {
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
+    #[serde(borrow)]
     pub key: Cow<'dt, str>,
-    pub subscriber: u32,
+    #[serde(deserialize_with = "str_to_bool")]
+    #[serde(serialize_with = "bool_to_str")]
+    pub subscriber: bool,
 }
 
+owned_t!(GetMobileSession { name, key, subscriber });
+
 lastfm_t!(
     session,
     GetMobileSession,
     _GetMobileSession,
     Params,
     GetMobileSession,
-    [username: &'rq str, password: &'rq str]
+    [username: &'rq str, password: &'rq str],
+    []
 );
 
 // ----------------------------------------------------------------
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct GetSession<'dt> {
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
+    #[serde(borrow)]
     pub key: Cow<'dt, str>,
-    pub subscriber: u32,
+    #[serde(deserialize_with = "str_to_bool")]
+    #[serde(serialize_with = "bool_to_str")]
+    pub subscriber: bool,
 }
 
+owned_t!(GetSession { name, key, subscriber });
+
 lastfm_t!(
     session,
     GetSession,
     _GetSession,
     Params,
     GetSession,
-    [token: &'rq str]
+    [token: &'rq str],
+    []
 );
 
 // ----------------------------------------------------------------
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct GetToken<'dt> {
+    #[serde(borrow)]
     pub token: Cow<'dt, str>,
 }
 
+owned_t!(GetToken { token });
+
 unwrapped_lastfm_t!(
     GetToken,
     Params,
     GetToken,
+    [],
     []
 );
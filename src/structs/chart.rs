@@ -1,10 +1,22 @@
 use std::convert::Into;
 use std::borrow::Cow;
+use std::time::Duration;
 
 use url::Url as StdUrl;
 
-use lastfm_type::{LastfmType, Request, RequestParams};
-use super::common::{Url, Image, Id2, Streamable, str_to_option, str_to_val};
+use lastfm_type::{LastfmType, Request, RequestParams, RequestBuilder, IntoArg};
+use lastfm_type::{Credentials, SessionState, SessionFree, Permits};
+use error::Result;
+use owned::IntoOwned;
+use validate;
+use paginate::{self, Pageable, Paged};
+use stream::Listed;
+use super::common::{PageInfo, Url, Images, Id2, Streamable, str_to_val};
+use super::common::{val_to_str};
+use super::common::{str_to_bool, bool_to_str, str_to_option_bool, option_bool_to_str};
+use super::common::{Mbid, mbid_option};
+use super::common::{secs_to_duration, duration_to_secs};
+use super::entity::{self, HasName, HasMbid, HasImages};
 
 // ----------------------------------------------------------------
 
@@ -43,6 +55,18 @@ impl<'pr> RequestParams for Params<'pr> {
         false
     }
 
+    fn validate(&self) -> Result<()> {
+        match *self {
+            Params::GetTopArtists { limit, page } |
+            Params::GetTopTags { limit, page } |
+            Params::GetTopTracks { limit, page } => {
+                validate::limit(limit, validate::MAX_LIMIT)?;
+                validate::page(page)
+            }
+            Params::Phantom(_) => panic!("this is a dummy item"),
+        }
+    }
+
     fn append_to(&self, url: &mut StdUrl) {
         let mut query = url.query_pairs_mut();
         match *self {
@@ -75,97 +99,201 @@ impl<'pr> RequestParams for Params<'pr> {
     }
 }
 
+impl<'pr> Pageable for Params<'pr> {
+    fn page(&self) -> Option<u32> {
+        match *self {
+            Params::GetTopArtists { page, .. } |
+            Params::GetTopTags { page, .. } |
+            Params::GetTopTracks { page, .. } => page,
+            _ => None,
+        }
+    }
+
+    fn set_page(&mut self, value: u32) -> Result<()> {
+        match *self {
+            Params::GetTopArtists { ref mut page, .. } |
+            Params::GetTopTags { ref mut page, .. } |
+            Params::GetTopTracks { ref mut page, .. } => {
+                *page = Some(value);
+                Ok(())
+            }
+            _ => Err(paginate::not_paginated(self.method())),
+        }
+    }
+}
+
 // ----------------------------------------------------------------
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Artist<'dt> {
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
-    pub mbid: Option<&'dt str>,
+    #[serde(borrow)]
+    #[serde(default)]
+    #[serde(deserialize_with = "mbid_option")]
+    pub mbid: Option<Mbid<'dt>>,
     pub url: Url<'dt>,
     #[serde(deserialize_with = "str_to_val")]
+    #[serde(serialize_with = "val_to_str")]
     pub playcount: u32,
     #[serde(deserialize_with = "str_to_val")]
+    #[serde(serialize_with = "val_to_str")]
     pub listeners: u32,
     #[serde(default)]
-    #[serde(deserialize_with = "str_to_option")]
-    pub streamable: Option<u32>,
-    pub image: Vec<Image<'dt>>,
+    #[serde(deserialize_with = "str_to_option_bool")]
+    #[serde(serialize_with = "option_bool_to_str")]
+    pub streamable: Option<bool>,
+    pub image: Images<'dt>,
+}
+
+owned_t!(Artist { name, mbid, url, playcount, listeners, streamable, image });
+entity_t!(Artist: HasName, HasMbid, HasImages);
+entity_t!(Artist => Artist);
+
+impl<'dt> From<Artist<'dt>> for entity::Artist<'dt> {
+    fn from(artist: Artist<'dt>) -> entity::Artist<'dt> {
+        entity::Artist {
+            mbid: artist.mbid,
+            url: artist.url.non_empty(),
+            image: artist.image,
+            playcount: Some(artist.playcount),
+            listeners: Some(artist.listeners),
+            streamable: artist.streamable,
+            ..entity::Artist::new(artist.name)
+        }
+    }
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct GetTopArtists<'dt> {
     #[serde(borrow)]
     pub artist: Option<Vec<Artist<'dt>>>,
+    #[serde(rename = "@attr")]
+    pub page_info: Option<PageInfo<'dt>>,
 }
 
+owned_t!(GetTopArtists { artist, page_info });
+
 lastfm_t!(
     artists,
     GetTopArtists,
     _TopArtists,
     Params,
     GetTopArtists,
-    [limit: Option<u32>, page: Option<u32>]
+    [],
+    [limit: u32, page: u32]
 );
 
+paged_t!(GetTopArtists, artist: Artist);
+
 // ----------------------------------------------------------------
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Tag<'dt> {
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
     pub url: Url<'dt>,
     #[serde(deserialize_with = "str_to_val")]
+    #[serde(serialize_with = "val_to_str")]
     pub reach: u32,
     #[serde(deserialize_with = "str_to_val")]
+    #[serde(serialize_with = "val_to_str")]
     pub taggings: u32,
-    #[serde(deserialize_with = "str_to_val")]
-    pub streamable: u32, 
+    #[serde(deserialize_with = "str_to_bool")]
+    #[serde(serialize_with = "bool_to_str")]
+    pub streamable: bool, 
     // wiki omitted for now: service always returns {}.
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(Tag { name, url, reach, taggings, streamable });
+entity_t!(Tag: HasName);
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct GetTopTags<'dt> {
     #[serde(borrow)]
     pub tag: Option<Vec<Tag<'dt>>>,
+    #[serde(rename = "@attr")]
+    pub page_info: Option<PageInfo<'dt>>,
 }
 
+owned_t!(GetTopTags { tag, page_info });
+
 lastfm_t!(
     tags,
     GetTopTags,
     _TopTags,
     Params,
     GetTopTags,
-    [limit: Option<u32>, page: Option<u32>]
+    [],
+    [limit: u32, page: u32]
 );
 
+paged_t!(GetTopTags, tag: Tag);
+
 // ----------------------------------------------------------------
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Track<'dt> {
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
-    pub mbid: Option<&'dt str>,
+    #[serde(borrow)]
+    #[serde(default)]
+    #[serde(deserialize_with = "mbid_option")]
+    pub mbid: Option<Mbid<'dt>>,
     pub url: Url<'dt>,
+    #[serde(default)]
+    #[serde(deserialize_with = "secs_to_duration")]
+    #[serde(serialize_with = "duration_to_secs")]
+    pub duration: Option<Duration>,
     #[serde(deserialize_with = "str_to_val")]
-    pub duration: u32,
-    #[serde(deserialize_with = "str_to_val")]
+    #[serde(serialize_with = "val_to_str")]
     pub playcount: u32,
     #[serde(deserialize_with = "str_to_val")]
+    #[serde(serialize_with = "val_to_str")]
     pub listeners: u32,
     pub streamable: Streamable,
     pub artist: Id2<'dt>,
-    pub image: Vec<Image<'dt>>,
+    pub image: Images<'dt>,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(Track { name, mbid, url, duration, playcount, listeners, streamable, artist, image });
+entity_t!(Track: HasName, HasMbid, HasImages);
+entity_t!(Track => Track);
+
+impl<'dt> From<Track<'dt>> for entity::Track<'dt> {
+    fn from(track: Track<'dt>) -> entity::Track<'dt> {
+        entity::Track {
+            mbid: track.mbid,
+            url: track.url.non_empty(),
+            artist: Some(track.artist.into()),
+            image: track.image,
+            playcount: Some(track.playcount),
+            listeners: Some(track.listeners),
+            streamable: Some(track.streamable.streamable),
+            duration: track.duration,
+            ..entity::Track::new(track.name)
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct GetTopTracks<'dt> {
     #[serde(borrow)]
     pub track: Option<Vec<Track<'dt>>>,
+    #[serde(rename = "@attr")]
+    pub page_info: Option<PageInfo<'dt>>,
 }
 
+owned_t!(GetTopTracks { track, page_info });
+
 lastfm_t!(
     tracks,
     GetTopTracks,
     _TopTracks,
     Params,
     GetTopTracks,
-    [limit: Option<u32>, page: Option<u32>]
+    [],
+    [limit: u32, page: u32]
 );
+
+paged_t!(GetTopTracks, track: Track);
@@ -4,16 +4,56 @@ use std::result::Result as StdResult;
 use std::fmt::{Display, Debug, Formatter, Result as FmtResult};
 use std::marker::PhantomData;
 use std::borrow::Cow;
+use std::cell::Cell;
+use std::ops::Deref;
+use std::slice::Iter;
+use std::vec::IntoIter;
+use std::time::Duration;
 
-use url::Url as StdUrl;
+use url::{Url as StdUrl, ParseError as UrlError};
 use serde::de::{Deserialize, Deserializer, Visitor, SeqAccess, MapAccess, Error as SerdeError};
 use serde::de::value::{MapAccessDeserializer, SeqAccessDeserializer};
+use serde::ser::{Serialize, Serializer};
 use serde_json as json;
 
+use owned::IntoOwned;
+use request::IntoArg;
+use error::Error;
+
 // ----------------------------------------------------------------
 
 pub type UnixTimestamp = i64;
 
+/// Point in time passed to the service, sent as unix timestamp.
+/// Converts from `UnixTimestamp` and, with `chrono` feature, from `DateTime<Utc>`
+/// (see `time::Timestamped` for the opposite direction).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Timestamp(pub UnixTimestamp);
+
+impl Timestamp {
+    pub fn unix(self) -> UnixTimestamp {
+        self.0
+    }
+}
+
+impl From<UnixTimestamp> for Timestamp {
+    fn from(timestamp: UnixTimestamp) -> Timestamp {
+        Timestamp(timestamp)
+    }
+}
+
+impl IntoArg<Timestamp> for UnixTimestamp {
+    fn into_arg(self) -> Timestamp {
+        Timestamp(self)
+    }
+}
+
+impl Display for Timestamp {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        Display::fmt(&self.0, f)
+    }
+}
+
 // ----------------------------------------------------------------
 
 // https://github.com/serde-rs/json/issues/373
@@ -45,6 +85,98 @@ where
 
 // ----------------------------------------------------------------
 
+/// Same as str_to_val, but also accepts plain json numbers
+/// (some methods return numeric values unquoted)
+pub fn num_or_str_to_val<'de, T, D>(deserializer: D) -> StdResult<T, D::Error>
+where
+    T: FromStr,
+    T::Err: Display,
+    D: Deserializer<'de>,
+{
+    match json::Value::deserialize(deserializer)? {
+        json::Value::String(s) => T::from_str(&s).map_err(SerdeError::custom),
+        json::Value::Number(n) => T::from_str(&n.to_string()).map_err(SerdeError::custom),
+        other => Err(SerdeError::custom(format!("expected string or number, got {}", other))),
+    }
+}
+
+/// Same as str_to_option, but also accepts plain json numbers; empty strings become `None`
+pub fn num_or_str_to_option<'de, T, D>(deserializer: D) -> StdResult<Option<T>, D::Error>
+where
+    T: FromStr,
+    T::Err: Display,
+    D: Deserializer<'de>,
+{
+    match Option::<json::Value>::deserialize(deserializer)? {
+        Some(json::Value::String(ref s)) if s.is_empty() => Ok(None),
+        Some(value) => num_or_str_to_val(value).map(Some).map_err(SerdeError::custom),
+        None => Ok(None),
+    }
+}
+
+// ----------------------------------------------------------------
+
+/// Deserializes string that service occasionally sends as a plain json number,
+/// borrowing it from the source when possible
+pub fn cow_or_num<'de: 'dt, 'dt, D>(deserializer: D) -> StdResult<Cow<'dt, str>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct CowOrNum;
+
+    impl<'de> Visitor<'de> for CowOrNum {
+        type Value = Cow<'de, str>;
+
+        fn expecting(&self, f: &mut Formatter) -> FmtResult {
+            f.write_str("string or number")
+        }
+
+        fn visit_borrowed_str<E>(self, value: &'de str) -> StdResult<Self::Value, E> {
+            Ok(Cow::Borrowed(value))
+        }
+
+        fn visit_str<E>(self, value: &str) -> StdResult<Self::Value, E> {
+            Ok(Cow::Owned(value.to_owned()))
+        }
+
+        fn visit_string<E>(self, value: String) -> StdResult<Self::Value, E> {
+            Ok(Cow::Owned(value))
+        }
+
+        fn visit_i64<E>(self, value: i64) -> StdResult<Self::Value, E> {
+            Ok(Cow::Owned(value.to_string()))
+        }
+
+        fn visit_u64<E>(self, value: u64) -> StdResult<Self::Value, E> {
+            Ok(Cow::Owned(value.to_string()))
+        }
+
+        fn visit_f64<E>(self, value: f64) -> StdResult<Self::Value, E> {
+            Ok(Cow::Owned(value.to_string()))
+        }
+    }
+
+    deserializer.deserialize_any(CowOrNum)
+}
+
+// ----------------------------------------------------------------
+
+/// Deserializes optional string, borrowing it from the source when possible.
+/// Falls back to an owned copy when the string contains escape sequences.
+/// (`#[serde(borrow)]` alone only has effect on plain `Cow<str>` fields, not on `Option<Cow<str>>`)
+pub fn cow_option<'de: 'dt, 'dt, D>(deserializer: D) -> StdResult<Option<Cow<'dt, str>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    struct Borrowed<'dt>(#[serde(borrow)] Cow<'dt, str>);
+
+    let value: Option<Borrowed<'dt>> = Deserialize::deserialize(deserializer)?;
+    Ok(value.map(|b| b.0))
+}
+
+// ----------------------------------------------------------------
+
 pub fn str_to_variant<'de, E, D>(deserializer: D) -> StdResult<E, D::Error>
 where
     E: TryFrom<u32>,
@@ -57,6 +189,174 @@ where
 
 // ----------------------------------------------------------------
 
+/// Interprets flag value: "1"/"0", "true"/"false", plain numbers or json booleans
+fn parse_flag(value: &json::Value) -> Option<bool> {
+    match *value {
+        json::Value::Bool(b) => Some(b),
+        json::Value::Number(ref n) => match n.as_u64() {
+            Some(0) => Some(false),
+            Some(1) => Some(true),
+            _ => None,
+        },
+        json::Value::String(ref s) => match s.trim() {
+            "1" | "true" => Some(true),
+            "0" | "false" => Some(false),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Deserializes flag (see `parse_flag()`) into bool, anything else is an error
+pub fn str_to_bool<'de, D>(deserializer: D) -> StdResult<bool, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = json::Value::deserialize(deserializer)?;
+    parse_flag(&value).ok_or_else(|| SerdeError::custom(format!("expected flag, got {}", value)))
+}
+
+/// Same as str_to_bool, but treats empty strings and placeholders (like "FIXME") as `None`
+pub fn str_to_option_bool<'de, D>(deserializer: D) -> StdResult<Option<bool>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = json::Value::deserialize(deserializer)?;
+    Ok(parse_flag(&value))
+}
+
+// ----------------------------------------------------------------
+
+/// Interprets duration value: amount of units as json number or string.
+/// Zero, null, empty strings and placeholders (like "FIXME") mean the duration is unknown.
+fn parse_units(value: &json::Value) -> StdResult<Option<u64>, String> {
+    match *value {
+        json::Value::Null => Ok(None),
+        json::Value::Number(ref n) => n.as_u64()
+            .map(|units| Some(units).filter(|&units| units > 0))
+            .ok_or_else(|| format!("expected duration, got {}", n)),
+        json::Value::String(ref s) => Ok(s.trim().parse().ok().filter(|&units| units > 0)),
+        ref other => Err(format!("expected duration, got {}", other)),
+    }
+}
+
+/// Deserializes duration given in seconds (see `parse_units()`)
+pub fn secs_to_duration<'de, D>(deserializer: D) -> StdResult<Option<Duration>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = json::Value::deserialize(deserializer)?;
+    Ok(parse_units(&value).map_err(SerdeError::custom)?.map(Duration::from_secs))
+}
+
+/// Deserializes duration given in milliseconds (see `parse_units()`)
+pub fn millis_to_duration<'de, D>(deserializer: D) -> StdResult<Option<Duration>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = json::Value::deserialize(deserializer)?;
+    Ok(parse_units(&value).map_err(SerdeError::custom)?.map(Duration::from_millis))
+}
+
+// ----------------------------------------------------------------
+
+thread_local!(static CLEAN_SHAPE: Cell<bool> = const { Cell::new(false) });
+
+/// Restores previous shape when dropped, so that a panicking serializer doesn't leave it switched
+struct ShapeGuard(bool);
+
+impl Drop for ShapeGuard {
+    fn drop(&mut self) {
+        CLEAN_SHAPE.with(|clean| clean.set(self.0));
+    }
+}
+
+/// Runs given closure with serialization helpers below switched to "clean" shape:
+/// values that API transfers as strings are written as numbers instead.
+pub fn with_clean_shape<R, F: FnOnce() -> R>(f: F) -> R {
+    let _guard = ShapeGuard(CLEAN_SHAPE.with(|clean| clean.replace(true)));
+    f()
+}
+
+fn is_clean_shape() -> bool {
+    CLEAN_SHAPE.with(Cell::get)
+}
+
+/// Serialization counterpart of str_to_val and num_or_str_to_val
+pub fn val_to_str<T, S>(value: &T, serializer: S) -> StdResult<S::Ok, S::Error>
+where
+    T: Display + Serialize,
+    S: Serializer,
+{
+    if is_clean_shape() {
+        value.serialize(serializer)
+    } else {
+        serializer.collect_str(value)
+    }
+}
+
+/// Serialization counterpart of str_to_option
+pub fn option_to_str<T, S>(value: &Option<T>, serializer: S) -> StdResult<S::Ok, S::Error>
+where
+    T: Display + Serialize,
+    S: Serializer,
+{
+    match *value {
+        Some(ref value) => val_to_str(value, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Serialization counterpart of str_to_variant
+pub fn variant_to_str<E, S>(value: &E, serializer: S) -> StdResult<S::Ok, S::Error>
+where
+    E: Clone + Into<u32>,
+    S: Serializer,
+{
+    val_to_str(&value.clone().into(), serializer)
+}
+
+/// Serialization counterpart of str_to_bool: "1"/"0", or plain bool in clean shape
+pub fn bool_to_str<S>(value: &bool, serializer: S) -> StdResult<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    if is_clean_shape() {
+        serializer.serialize_bool(*value)
+    } else {
+        serializer.serialize_str(if *value { "1" } else { "0" })
+    }
+}
+
+/// Serialization counterpart of str_to_option_bool
+pub fn option_bool_to_str<S>(value: &Option<bool>, serializer: S) -> StdResult<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match *value {
+        Some(ref value) => bool_to_str(value, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Serialization counterpart of secs_to_duration
+pub fn duration_to_secs<S>(value: &Option<Duration>, serializer: S) -> StdResult<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    option_to_str(&value.map(|duration| duration.as_secs()), serializer)
+}
+
+/// Serialization counterpart of millis_to_duration
+pub fn duration_to_millis<S>(value: &Option<Duration>, serializer: S) -> StdResult<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    option_to_str(&value.map(|duration| duration.as_millis()), serializer)
+}
+
+// ----------------------------------------------------------------
+
 pub trait Underlying<'de> {
     type Struct: Deserialize<'de>;
     type Arr: Deserialize<'de>;
@@ -81,6 +381,21 @@ impl<'de, T: Deserialize<'de>> Underlying<'de> for VecOrStruct<'de, T> {
     }
 }
 
+/// Always serialized as an array
+impl<'dt, T: Serialize> Serialize for VecOrStruct<'dt, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> StdResult<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'dt, T: IntoOwned> IntoOwned for VecOrStruct<'dt, T> {
+    type Owned = VecOrStruct<'static, T::Owned>;
+
+    fn into_owned(self) -> VecOrStruct<'static, T::Owned> {
+        VecOrStruct(IntoOwned::into_owned(self.0), PhantomData)
+    }
+}
+
 pub fn vec_or_struct<'de, T, D>(deserializer: D) -> StdResult<T, D::Error>
 where
     T: Deserialize<'de> + Debug + Underlying<'de>,
@@ -117,19 +432,66 @@ where
 
 // ----------------------------------------------------------------
 
-#[derive(Deserialize, Debug)]
-pub struct Url<'dt>(Cow<'dt, str>);
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Url<'dt>(#[serde(borrow)] Cow<'dt, str>);
+
+impl<'dt> IntoOwned for Url<'dt> {
+    type Owned = Url<'static>;
+
+    fn into_owned(self) -> Url<'static> {
+        Url(IntoOwned::into_owned(self.0))
+    }
+}
 
-impl<'dt> Into<StdUrl> for Url<'dt> {
-    fn into(self) -> StdUrl {
-        StdUrl::parse(&self.0).unwrap()
+impl<'dt> Url<'dt> {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Lastfm sends empty strings in place of missing links (images, mostly)
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// `None` for empty links
+    pub fn non_empty(self) -> Option<Url<'dt>> {
+        if self.is_empty() { None } else { Some(self) }
+    }
+}
+
+impl<'dt> From<Cow<'dt, str>> for Url<'dt> {
+    fn from(url: Cow<'dt, str>) -> Url<'dt> {
+        Url(url)
+    }
+}
+
+impl<'dt> TryFrom<Url<'dt>> for StdUrl {
+    type Error = UrlError;
+
+    fn try_from(url: Url<'dt>) -> StdResult<StdUrl, UrlError> {
+        StdUrl::parse(&url.0)
+    }
+}
+
+impl<'a, 'dt> TryFrom<&'a Url<'dt>> for StdUrl {
+    type Error = UrlError;
+
+    fn try_from(url: &'a Url<'dt>) -> StdResult<StdUrl, UrlError> {
+        StdUrl::parse(&url.0)
     }
 }
 
 // ----------------------------------------------------------------
 
-#[derive(Deserialize, Debug)]
+/// Hash of the image Lastfm serves in place of missing pictures (grey star)
+pub static PLACEHOLDER_IMAGE_HASH: &str = "2a96cbd8b46e442fc41c2b86b821562f";
+
+/// Image sizes, from the smallest to the largest.
+/// `Default` stands for entries without size, which duplicate one of the others.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum ImageSize {
+    #[serde(rename = "")]
+    Default,
     #[serde(rename = "small")]
     Small,
     #[serde(rename = "medium")]
@@ -140,68 +502,311 @@ pub enum ImageSize {
     ExtraLarge,
     #[serde(rename = "mega")]
     Mega,
-    #[serde(rename = "")]
-    Default,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(ImageSize);
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Image<'dt> {
     #[serde(rename = "#text")]
+    #[serde(borrow)]
     pub text: Url<'dt>,
     pub size: ImageSize,
 }
 
+owned_t!(Image { text, size });
+
+impl<'dt> Image<'dt> {
+    /// Indicates that there's no actual image: link is empty, points to Lastfm's placeholder
+    /// or entry has no size
+    pub fn is_missing(&self) -> bool {
+        self.text.is_empty() ||
+            self.text.as_str().contains(PLACEHOLDER_IMAGE_HASH) ||
+            self.size == ImageSize::Default
+    }
+
+    /// Parsed image link, unless image is missing or link is malformed
+    pub fn url(&self) -> Option<StdUrl> {
+        if self.is_missing() {
+            None
+        } else {
+            StdUrl::try_from(&self.text).ok()
+        }
+    }
+}
+
+/// Image links of an artist, album, track or user in various sizes.
+/// Missing entries (see `Image::is_missing()`) are kept as received, but skipped by lookups.
+#[derive(Deserialize, Serialize, Debug, Default)]
+#[serde(transparent)]
+pub struct Images<'dt>(#[serde(borrow)] Vec<Image<'dt>>);
+
+impl<'dt> IntoOwned for Images<'dt> {
+    type Owned = Images<'static>;
+
+    fn into_owned(self) -> Images<'static> {
+        Images(IntoOwned::into_owned(self.0))
+    }
+}
+
+impl<'dt> Images<'dt> {
+    /// Images that are not missing
+    pub fn non_empty<'a>(&'a self) -> impl Iterator<Item = &'a Image<'dt>> {
+        self.0.iter().filter(|image| !image.is_missing())
+    }
+
+    /// Image of given size, unless it's missing
+    pub fn get(&self, size: ImageSize) -> Option<&Image<'dt>> {
+        self.non_empty().find(|image| image.size == size)
+    }
+
+    /// The largest image that is not missing
+    pub fn largest(&self) -> Option<&Image<'dt>> {
+        self.non_empty().max_by_key(|image| image.size)
+    }
+
+    pub fn into_vec(self) -> Vec<Image<'dt>> {
+        self.0
+    }
+}
+
+impl<'dt> Deref for Images<'dt> {
+    type Target = [Image<'dt>];
+
+    fn deref(&self) -> &[Image<'dt>] {
+        &self.0
+    }
+}
+
+impl<'dt> From<Vec<Image<'dt>>> for Images<'dt> {
+    fn from(images: Vec<Image<'dt>>) -> Images<'dt> {
+        Images(images)
+    }
+}
+
+impl<'dt> IntoIterator for Images<'dt> {
+    type Item = Image<'dt>;
+    type IntoIter = IntoIter<Image<'dt>>;
+
+    fn into_iter(self) -> IntoIter<Image<'dt>> {
+        self.0.into_iter()
+    }
+}
+
+impl<'a, 'dt> IntoIterator for &'a Images<'dt> {
+    type Item = &'a Image<'dt>;
+    type IntoIter = Iter<'a, Image<'dt>>;
+
+    fn into_iter(self) -> Iter<'a, Image<'dt>> {
+        self.0.iter()
+    }
+}
+
 // ----------------------------------------------------------------
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub enum SearchQueryRole {
     #[serde(rename = "request")]
     Request,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(SearchQueryRole);
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct SearchQuery<'dt> {
     pub role: SearchQueryRole,
     #[serde(rename = "searchTerms")]
     #[serde(borrow)]
-    pub search_terms: Option<&'dt str>,
+    #[serde(default)]
+    #[serde(deserialize_with = "cow_option")]
+    pub search_terms: Option<Cow<'dt, str>>,
     #[serde(rename = "startPage")]
     #[serde(deserialize_with = "str_to_option")]
+    #[serde(serialize_with = "option_to_str")]
     pub start_page: Option<u32>,
 }
 
+owned_t!(SearchQuery { role, search_terms, start_page });
+
 // ----------------------------------------------------------------
 
-#[derive(Deserialize, Debug)]
+/// Pagination metadata (`@attr` object) of list responses.
+/// Context fields are set depending on the method: e.g. `user` for `user.*` methods,
+/// `country` for `geo.*` methods and so on.
+#[derive(Deserialize, Serialize, Debug)]
+pub struct PageInfo<'dt> {
+    #[serde(deserialize_with = "num_or_str_to_val")]
+    #[serde(serialize_with = "val_to_str")]
+    pub page: u32,
+    #[serde(rename = "perPage")]
+    #[serde(deserialize_with = "num_or_str_to_val")]
+    #[serde(serialize_with = "val_to_str")]
+    pub per_page: u32,
+    #[serde(rename = "totalPages")]
+    #[serde(deserialize_with = "num_or_str_to_val")]
+    #[serde(serialize_with = "val_to_str")]
+    pub total_pages: u32,
+    #[serde(deserialize_with = "num_or_str_to_val")]
+    #[serde(serialize_with = "val_to_str")]
+    pub total: u32,
+
+    #[serde(borrow)]
+    #[serde(default)]
+    #[serde(deserialize_with = "cow_option")]
+    pub user: Option<Cow<'dt, str>>,
+    #[serde(borrow)]
+    #[serde(default)]
+    #[serde(deserialize_with = "cow_option")]
+    pub artist: Option<Cow<'dt, str>>,
+    #[serde(borrow)]
+    #[serde(default)]
+    #[serde(deserialize_with = "cow_option")]
+    pub tag: Option<Cow<'dt, str>>,
+    #[serde(borrow)]
+    #[serde(default)]
+    #[serde(deserialize_with = "cow_option")]
+    pub country: Option<Cow<'dt, str>>,
+}
+
+owned_t!(PageInfo { page, per_page, total_pages, total, user, artist, tag, country });
+
+impl<'dt> PageInfo<'dt> {
+    /// Indicates whether there are no more pages after this one
+    pub fn is_last(&self) -> bool {
+        self.page >= self.total_pages
+    }
+}
+
+// ----------------------------------------------------------------
+
+/// MusicBrainz identifier: UUID in its canonical textual form,
+/// e.g. `bfcc6d75-a6a5-4bc6-8282-47aec8531818`
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Mbid<'dt>(Cow<'dt, str>);
+
+impl<'dt> Mbid<'dt> {
+    /// Checks that given string is a valid identifier
+    pub fn new<S: Into<Cow<'dt, str>>>(mbid: S) -> StdResult<Mbid<'dt>, Error> {
+        let mbid = mbid.into();
+        if is_uuid(&mbid) {
+            Ok(Mbid(mbid))
+        } else {
+            Err(Error::InvalidParameter {
+                name: "mbid",
+                reason: format!("'{}' is not a valid UUID", mbid),
+            })
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+fn is_uuid(value: &str) -> bool {
+    let groups: Vec<&str> = value.split('-').collect();
+    groups.iter().map(|group| group.len()).eq([8, 4, 4, 4, 12].iter().cloned()) &&
+        groups.iter().all(|group| group.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+impl<'a, 'dt> From<&'a Mbid<'dt>> for Mbid<'a> {
+    fn from(mbid: &'a Mbid<'dt>) -> Mbid<'a> {
+        Mbid(Cow::Borrowed(&mbid.0))
+    }
+}
+
+impl FromStr for Mbid<'static> {
+    type Err = Error;
+
+    fn from_str(mbid: &str) -> StdResult<Mbid<'static>, Error> {
+        Mbid::new(mbid.to_owned())
+    }
+}
+
+impl<'dt> Display for Mbid<'dt> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.write_str(&self.0)
+    }
+}
+
+impl<'dt> IntoOwned for Mbid<'dt> {
+    type Owned = Mbid<'static>;
+
+    fn into_owned(self) -> Mbid<'static> {
+        Mbid(IntoOwned::into_owned(self.0))
+    }
+}
+
+impl<'de: 'dt, 'dt> Deserialize<'de> for Mbid<'dt> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> StdResult<Mbid<'dt>, D::Error> {
+        #[derive(Deserialize)]
+        struct Borrowed<'dt>(#[serde(borrow)] Cow<'dt, str>);
+
+        let Borrowed(mbid) = Borrowed::deserialize(deserializer)?;
+        Mbid::new(mbid).map_err(|_| SerdeError::custom("expected mbid (UUID)"))
+    }
+}
+
+/// Deserializes optional mbid: Lastfm sends empty strings for unknown ones
+/// and occasionally garbage, both become `None` rather than failing the whole response
+pub fn mbid_option<'de: 'dt, 'dt, D>(deserializer: D) -> StdResult<Option<Mbid<'dt>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(cow_option(deserializer)?.and_then(|mbid| Mbid::new(mbid).ok()))
+}
+
+// ----------------------------------------------------------------
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Id1<'dt> {
     #[serde(rename = "#text")]
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
-    pub mbid: Option<&'dt str>,
+    #[serde(borrow)]
+    #[serde(default)]
+    #[serde(deserialize_with = "mbid_option")]
+    pub mbid: Option<Mbid<'dt>>,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(Id1 { name, mbid });
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Id2<'dt> {
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
-    pub mbid: Option<&'dt str>,
+    #[serde(borrow)]
+    #[serde(default)]
+    #[serde(deserialize_with = "mbid_option")]
+    pub mbid: Option<Mbid<'dt>>,
     pub url: Url<'dt>,
     #[serde(default)]
-    pub image: Vec<Image<'dt>>,
+    pub image: Images<'dt>,
 }
 
+owned_t!(Id2 { name, mbid, url, image });
+
 // ----------------------------------------------------------------
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Streamable {
     #[serde(rename = "#text")]
-    #[serde(deserialize_with = "str_to_val")]
-    pub streamable: u32,
-    #[serde(deserialize_with = "str_to_val")]
-    pub fulltrack: u32,
+    #[serde(deserialize_with = "str_to_bool")]
+    #[serde(serialize_with = "bool_to_str")]
+    pub streamable: bool,
+    #[serde(deserialize_with = "str_to_bool")]
+    #[serde(serialize_with = "bool_to_str")]
+    pub fulltrack: bool,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(Streamable);
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Rank {
     #[serde(deserialize_with = "str_to_val")]
+    #[serde(serialize_with = "val_to_str")]
     pub rank: u32,
 }
 
+owned_t!(Rank);
+
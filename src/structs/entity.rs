@@ -0,0 +1,441 @@
+use std::borrow::Cow;
+use std::time::Duration;
+
+use url::UrlQuery;
+use url::form_urlencoded::Serializer;
+
+use owned::IntoOwned;
+use error::Result;
+use validate;
+use super::common::{Url, Images, Mbid, Id1, Id2};
+
+// ----------------------------------------------------------------
+
+/// Implements entity traits and conversions for response item types:
+/// * `entity_t!(Type: HasName, HasMbid, HasImages)` implements listed traits
+///   on top of `name`, `mbid` and `image` fields;
+/// * `entity_t!(Type => Artist)` (or `Album`, `Track`) implements conversion into
+///   corresponding reference type, given that conversion into the full type exists.
+///
+/// Following should be included in order to use this macro:
+/// ```ignore
+/// use structs::entity::{HasName, HasMbid, HasImages};
+/// ```
+macro_rules! entity_t {
+    ($data_t:ident => Artist) => {
+        entity_t!(@ref $data_t, Artist, ArtistRef);
+    };
+    ($data_t:ident => Album) => {
+        entity_t!(@ref $data_t, Album, AlbumRef);
+    };
+    ($data_t:ident => Track) => {
+        entity_t!(@ref $data_t, Track, TrackRef);
+    };
+    ($data_t:ident: $($trait_t:ident),+) => {
+        $(entity_t!(@trait $data_t, $trait_t);)+
+    };
+
+    (@ref $data_t:ident, $full_t:ident, $ref_t:ident) => {
+        impl<'dt> From<$data_t<'dt>> for $crate::structs::entity::$ref_t<'dt> {
+            fn from(data: $data_t<'dt>) -> $crate::structs::entity::$ref_t<'dt> {
+                $crate::structs::entity::$full_t::from(data).into()
+            }
+        }
+    };
+    (@trait $data_t:ident, HasName) => {
+        impl<'dt> HasName for $data_t<'dt> {
+            fn name(&self) -> &str {
+                &self.name
+            }
+        }
+    };
+    (@trait $data_t:ident, HasMbid) => {
+        impl<'dt> HasMbid for $data_t<'dt> {
+            fn mbid(&self) -> Option<&$crate::structs::common::Mbid<'_>> {
+                self.mbid.as_ref()
+            }
+        }
+    };
+    (@trait $data_t:ident, HasImages) => {
+        impl<'dt> HasImages for $data_t<'dt> {
+            fn images(&self) -> &Images<'_> {
+                &self.image
+            }
+        }
+    };
+}
+
+// ----------------------------------------------------------------
+
+/// Anything with a name: artist, album, track, tag or user
+pub trait HasName {
+    fn name(&self) -> &str;
+}
+
+/// Entities that may have a MusicBrainz identifier
+pub trait HasMbid {
+    fn mbid(&self) -> Option<&Mbid<'_>>;
+}
+
+/// Entities that come with a set of images
+pub trait HasImages {
+    fn images(&self) -> &Images<'_>;
+}
+
+// ----------------------------------------------------------------
+
+/// Minimal artist description: enough to tell which artist it is and to look it up
+#[derive(Serialize, Debug, Clone)]
+pub struct ArtistRef<'dt> {
+    pub name: Cow<'dt, str>,
+    pub mbid: Option<Mbid<'dt>>,
+    pub url: Option<Url<'dt>>,
+}
+
+owned_t!(ArtistRef { name, mbid, url });
+entity_t!(ArtistRef: HasName, HasMbid);
+
+impl<'dt> ArtistRef<'dt> {
+    /// Artist known by name only, as in responses that send it as a plain string
+    pub fn from_name(name: Cow<'dt, str>) -> ArtistRef<'dt> {
+        ArtistRef { name, mbid: None, url: None }
+    }
+
+    /// Identifies artist for lookup methods, by mbid when it's known
+    pub fn ident(&self) -> ArtistIdent<'_> {
+        match self.mbid {
+            Some(ref mbid) => ArtistIdent::Mbid(mbid.into()),
+            None => ArtistIdent::Name(&self.name),
+        }
+    }
+}
+
+/// Minimal album description, artist is set when response mentions it
+#[derive(Serialize, Debug, Clone)]
+pub struct AlbumRef<'dt> {
+    pub name: Cow<'dt, str>,
+    pub mbid: Option<Mbid<'dt>>,
+    pub url: Option<Url<'dt>>,
+    pub artist: Option<ArtistRef<'dt>>,
+}
+
+owned_t!(AlbumRef { name, mbid, url, artist });
+entity_t!(AlbumRef: HasName, HasMbid);
+
+impl<'dt> AlbumRef<'dt> {
+    /// Identifies album for lookup methods, by mbid when it's known.
+    /// `None` if there's neither mbid nor artist.
+    pub fn ident(&self) -> Option<AlbumIdent<'_>> {
+        match (self.mbid.as_ref(), self.artist.as_ref()) {
+            (Some(mbid), _) => Some(AlbumIdent::Mbid(mbid.into())),
+            (None, Some(artist)) => Some(AlbumIdent::Name { artist: &artist.name, album: &self.name }),
+            (None, None) => None,
+        }
+    }
+}
+
+/// Minimal track description, artist is set when response mentions it
+#[derive(Serialize, Debug, Clone)]
+pub struct TrackRef<'dt> {
+    pub name: Cow<'dt, str>,
+    pub mbid: Option<Mbid<'dt>>,
+    pub url: Option<Url<'dt>>,
+    pub artist: Option<ArtistRef<'dt>>,
+}
+
+owned_t!(TrackRef { name, mbid, url, artist });
+entity_t!(TrackRef: HasName, HasMbid);
+
+impl<'dt> TrackRef<'dt> {
+    /// Identifies track for lookup methods, by mbid when it's known.
+    /// `None` if there's neither mbid nor artist.
+    pub fn ident(&self) -> Option<TrackIdent<'_>> {
+        match (self.mbid.as_ref(), self.artist.as_ref()) {
+            (Some(mbid), _) => Some(TrackIdent::Mbid(mbid.into())),
+            (None, Some(artist)) => Some(TrackIdent::Name { artist: &artist.name, track: &self.name }),
+            (None, None) => None,
+        }
+    }
+}
+
+// ----------------------------------------------------------------
+
+/// Artist with everything response items may tell about it.
+/// Fields that given method doesn't return are left `None` (or empty).
+#[derive(Serialize, Debug)]
+pub struct Artist<'dt> {
+    pub name: Cow<'dt, str>,
+    pub mbid: Option<Mbid<'dt>>,
+    pub url: Option<Url<'dt>>,
+    pub image: Images<'dt>,
+    pub playcount: Option<u32>,
+    pub listeners: Option<u32>,
+    pub streamable: Option<bool>,
+}
+
+owned_t!(Artist { name, mbid, url, image, playcount, listeners, streamable });
+entity_t!(Artist: HasName, HasMbid, HasImages);
+
+impl<'dt> Artist<'dt> {
+    /// Artist with given name and nothing else known about it
+    pub fn new(name: Cow<'dt, str>) -> Artist<'dt> {
+        Artist {
+            name,
+            mbid: None,
+            url: None,
+            image: Images::default(),
+            playcount: None,
+            listeners: None,
+            streamable: None,
+        }
+    }
+}
+
+impl<'dt> From<Artist<'dt>> for ArtistRef<'dt> {
+    fn from(artist: Artist<'dt>) -> ArtistRef<'dt> {
+        ArtistRef { name: artist.name, mbid: artist.mbid, url: artist.url }
+    }
+}
+
+/// Album with everything response items may tell about it.
+/// Fields that given method doesn't return are left `None` (or empty).
+#[derive(Serialize, Debug)]
+pub struct Album<'dt> {
+    pub name: Cow<'dt, str>,
+    pub mbid: Option<Mbid<'dt>>,
+    pub url: Option<Url<'dt>>,
+    pub artist: Option<ArtistRef<'dt>>,
+    pub image: Images<'dt>,
+    pub playcount: Option<u32>,
+    pub listeners: Option<u32>,
+}
+
+owned_t!(Album { name, mbid, url, artist, image, playcount, listeners });
+entity_t!(Album: HasName, HasMbid, HasImages);
+
+impl<'dt> Album<'dt> {
+    /// Album with given name and nothing else known about it
+    pub fn new(name: Cow<'dt, str>) -> Album<'dt> {
+        Album {
+            name,
+            mbid: None,
+            url: None,
+            artist: None,
+            image: Images::default(),
+            playcount: None,
+            listeners: None,
+        }
+    }
+}
+
+impl<'dt> From<Album<'dt>> for AlbumRef<'dt> {
+    fn from(album: Album<'dt>) -> AlbumRef<'dt> {
+        AlbumRef { name: album.name, mbid: album.mbid, url: album.url, artist: album.artist }
+    }
+}
+
+/// Track with everything response items may tell about it.
+/// Fields that given method doesn't return are left `None` (or empty).
+#[derive(Serialize, Debug)]
+pub struct Track<'dt> {
+    pub name: Cow<'dt, str>,
+    pub mbid: Option<Mbid<'dt>>,
+    pub url: Option<Url<'dt>>,
+    pub artist: Option<ArtistRef<'dt>>,
+    pub album: Option<AlbumRef<'dt>>,
+    pub image: Images<'dt>,
+    pub playcount: Option<u32>,
+    pub listeners: Option<u32>,
+    pub streamable: Option<bool>,
+    pub duration: Option<Duration>,
+}
+
+owned_t!(Track { name, mbid, url, artist, album, image, playcount, listeners, streamable, duration });
+entity_t!(Track: HasName, HasMbid, HasImages);
+
+impl<'dt> Track<'dt> {
+    /// Track with given name and nothing else known about it
+    pub fn new(name: Cow<'dt, str>) -> Track<'dt> {
+        Track {
+            name,
+            mbid: None,
+            url: None,
+            artist: None,
+            album: None,
+            image: Images::default(),
+            playcount: None,
+            listeners: None,
+            streamable: None,
+            duration: None,
+        }
+    }
+}
+
+impl<'dt> From<Track<'dt>> for TrackRef<'dt> {
+    fn from(track: Track<'dt>) -> TrackRef<'dt> {
+        TrackRef { name: track.name, mbid: track.mbid, url: track.url, artist: track.artist }
+    }
+}
+
+// ----------------------------------------------------------------
+
+/// Artist argument of lookup methods: either name or mbid.
+/// Converts from `&str` and `Mbid`, so `artist::GetInfo::builder("iamthemorning")` just works.
+#[derive(Debug, Clone)]
+pub enum ArtistIdent<'pr> {
+    Name(&'pr str),
+    Mbid(Mbid<'pr>),
+}
+
+impl<'pr> ArtistIdent<'pr> {
+    pub(crate) fn validate(&self) -> Result<()> {
+        match *self {
+            ArtistIdent::Name(artist) => validate::required("artist", artist),
+            ArtistIdent::Mbid(_) => Ok(()),
+        }
+    }
+
+    pub(crate) fn append_to(&self, query: &mut Serializer<UrlQuery>) {
+        match *self {
+            ArtistIdent::Name(artist) => query.append_pair("artist", artist),
+            ArtistIdent::Mbid(ref mbid) => query.append_pair("mbid", mbid.as_str()),
+        };
+    }
+}
+
+impl<'pr> From<&'pr str> for ArtistIdent<'pr> {
+    fn from(artist: &'pr str) -> ArtistIdent<'pr> {
+        ArtistIdent::Name(artist)
+    }
+}
+
+/// Album argument of lookup methods: either artist and album names or mbid.
+/// Converts from `(artist, album)` tuple and `Mbid`.
+#[derive(Debug, Clone)]
+pub enum AlbumIdent<'pr> {
+    Name { artist: &'pr str, album: &'pr str },
+    Mbid(Mbid<'pr>),
+}
+
+impl<'pr> AlbumIdent<'pr> {
+    pub(crate) fn validate(&self) -> Result<()> {
+        match *self {
+            AlbumIdent::Name { artist, album } => {
+                validate::required("artist", artist)?;
+                validate::required("album", album)
+            }
+            AlbumIdent::Mbid(_) => Ok(()),
+        }
+    }
+
+    pub(crate) fn append_to(&self, query: &mut Serializer<UrlQuery>) {
+        match *self {
+            AlbumIdent::Name { artist, album } => {
+                query.append_pair("artist", artist);
+                query.append_pair("album", album);
+            }
+            AlbumIdent::Mbid(ref mbid) => {
+                query.append_pair("mbid", mbid.as_str());
+            }
+        }
+    }
+}
+
+impl<'pr> From<(&'pr str, &'pr str)> for AlbumIdent<'pr> {
+    fn from((artist, album): (&'pr str, &'pr str)) -> AlbumIdent<'pr> {
+        AlbumIdent::Name { artist, album }
+    }
+}
+
+/// Track argument of lookup methods: either artist and track names or mbid.
+/// Converts from `(artist, track)` tuple and `Mbid`.
+#[derive(Debug, Clone)]
+pub enum TrackIdent<'pr> {
+    Name { artist: &'pr str, track: &'pr str },
+    Mbid(Mbid<'pr>),
+}
+
+impl<'pr> TrackIdent<'pr> {
+    pub(crate) fn validate(&self) -> Result<()> {
+        match *self {
+            TrackIdent::Name { artist, track } => {
+                validate::required("artist", artist)?;
+                validate::required("track", track)
+            }
+            TrackIdent::Mbid(_) => Ok(()),
+        }
+    }
+
+    pub(crate) fn append_to(&self, query: &mut Serializer<UrlQuery>) {
+        match *self {
+            TrackIdent::Name { artist, track } => {
+                query.append_pair("artist", artist);
+                query.append_pair("track", track);
+            }
+            TrackIdent::Mbid(ref mbid) => {
+                query.append_pair("mbid", mbid.as_str());
+            }
+        }
+    }
+}
+
+impl<'pr> From<(&'pr str, &'pr str)> for TrackIdent<'pr> {
+    fn from((artist, track): (&'pr str, &'pr str)) -> TrackIdent<'pr> {
+        TrackIdent::Name { artist, track }
+    }
+}
+
+macro_rules! ident_from_mbid {
+    ($($ident_t:ident),*) => {
+        $(
+            impl<'pr> From<Mbid<'pr>> for $ident_t<'pr> {
+                fn from(mbid: Mbid<'pr>) -> $ident_t<'pr> {
+                    $ident_t::Mbid(mbid)
+                }
+            }
+
+            impl<'pr, 'dt> From<&'pr Mbid<'dt>> for $ident_t<'pr> {
+                fn from(mbid: &'pr Mbid<'dt>) -> $ident_t<'pr> {
+                    $ident_t::Mbid(mbid.into())
+                }
+            }
+        )*
+    }
+}
+
+ident_from_mbid!(ArtistIdent, AlbumIdent, TrackIdent);
+
+// ----------------------------------------------------------------
+
+entity_t!(Id1: HasName, HasMbid);
+entity_t!(Id2: HasName, HasMbid, HasImages);
+
+impl<'dt> From<Id1<'dt>> for ArtistRef<'dt> {
+    fn from(id: Id1<'dt>) -> ArtistRef<'dt> {
+        ArtistRef { name: id.name, mbid: id.mbid, url: None }
+    }
+}
+
+impl<'dt> From<Id2<'dt>> for ArtistRef<'dt> {
+    fn from(id: Id2<'dt>) -> ArtistRef<'dt> {
+        ArtistRef { name: id.name, mbid: id.mbid, url: id.url.non_empty() }
+    }
+}
+
+impl<'dt> From<Id2<'dt>> for Artist<'dt> {
+    fn from(id: Id2<'dt>) -> Artist<'dt> {
+        Artist { mbid: id.mbid, url: id.url.non_empty(), image: id.image, ..Artist::new(id.name) }
+    }
+}
+
+impl<'dt> From<Id1<'dt>> for AlbumRef<'dt> {
+    fn from(id: Id1<'dt>) -> AlbumRef<'dt> {
+        AlbumRef { name: id.name, mbid: id.mbid, url: None, artist: None }
+    }
+}
+
+impl<'dt> From<Id2<'dt>> for AlbumRef<'dt> {
+    fn from(id: Id2<'dt>) -> AlbumRef<'dt> {
+        AlbumRef { name: id.name, mbid: id.mbid, url: id.url.non_empty(), artist: None }
+    }
+}
@@ -3,8 +3,17 @@ use std::borrow::Cow;
 
 use url::Url as StdUrl;
 
-use lastfm_type::{LastfmType, Request, RequestParams};
-use super::common::{Url, Image, Id2, Streamable, Rank, str_to_val};
+use lastfm_type::{LastfmType, Request, RequestParams, RequestBuilder, IntoArg};
+use lastfm_type::{Credentials, SessionState, SessionFree, Permits};
+use error::Result;
+use owned::IntoOwned;
+use validate;
+use paginate::{self, Pageable, Paged};
+use stream::Listed;
+use super::common::{PageInfo, Url, Images, Id2, Streamable, Rank};
+use super::common::{str_to_bool, bool_to_str};
+use super::common::{Mbid, mbid_option};
+use super::entity::{self, HasName, HasMbid, HasImages};
 
 // ----------------------------------------------------------------
 
@@ -41,6 +50,18 @@ impl<'pr> RequestParams for Params<'pr> {
         false
     }
 
+    fn validate(&self) -> Result<()> {
+        match *self {
+            Params::GetTopArtists { country, limit, page } |
+            Params::GetTopTracks { country, limit, page, .. } => {
+                validate::required("country", country)?;
+                validate::limit(limit, validate::MAX_LIMIT)?;
+                validate::page(page)
+            }
+            Params::Phantom(_) => panic!("this is a dummy item"),
+        }
+    }
+
     fn append_to(&self, url: &mut StdUrl) {
         let mut query = url.query_pairs_mut();
         match *self {
@@ -79,63 +100,139 @@ impl<'pr> RequestParams for Params<'pr> {
     }
 }
 
+impl<'pr> Pageable for Params<'pr> {
+    fn page(&self) -> Option<u32> {
+        match *self {
+            Params::GetTopArtists { page, .. } |
+            Params::GetTopTracks { page, .. } => page,
+            _ => None,
+        }
+    }
+
+    fn set_page(&mut self, value: u32) -> Result<()> {
+        match *self {
+            Params::GetTopArtists { ref mut page, .. } |
+            Params::GetTopTracks { ref mut page, .. } => {
+                *page = Some(value);
+                Ok(())
+            }
+            _ => Err(paginate::not_paginated(self.method())),
+        }
+    }
+}
+
 // ----------------------------------------------------------------
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Artist<'dt> {
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
-    pub mbid: Option<&'dt str>,
+    #[serde(borrow)]
+    #[serde(default)]
+    #[serde(deserialize_with = "mbid_option")]
+    pub mbid: Option<Mbid<'dt>>,
     pub url: Url<'dt>,
-    #[serde(deserialize_with = "str_to_val")]
-    pub streamable: u32,
-    pub image: Vec<Image<'dt>>,
+    #[serde(deserialize_with = "str_to_bool")]
+    #[serde(serialize_with = "bool_to_str")]
+    pub streamable: bool,
+    pub image: Images<'dt>,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(Artist { name, mbid, url, streamable, image });
+entity_t!(Artist: HasName, HasMbid, HasImages);
+entity_t!(Artist => Artist);
+
+impl<'dt> From<Artist<'dt>> for entity::Artist<'dt> {
+    fn from(artist: Artist<'dt>) -> entity::Artist<'dt> {
+        entity::Artist {
+            mbid: artist.mbid,
+            url: artist.url.non_empty(),
+            image: artist.image,
+            streamable: Some(artist.streamable),
+            ..entity::Artist::new(artist.name)
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct GetTopArtists<'dt> {
     #[serde(borrow)]
     pub artist: Option<Vec<Artist<'dt>>>,
+    #[serde(rename = "@attr")]
+    pub page_info: Option<PageInfo<'dt>>,
 }
 
+owned_t!(GetTopArtists { artist, page_info });
+
 lastfm_t!(
     topartists,
     GetTopArtists,
     _TopArtists,
     Params,
     GetTopArtists,
-    [country: &'rq str, limit: Option<u32>, page: Option<u32>]
+    [country: &'rq str],
+    [limit: u32, page: u32]
 );
 
+paged_t!(GetTopArtists, artist: Artist);
+
 // ----------------------------------------------------------------
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Track<'dt> {
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
-    pub mbid: Option<&'dt str>,
+    #[serde(borrow)]
+    #[serde(default)]
+    #[serde(deserialize_with = "mbid_option")]
+    pub mbid: Option<Mbid<'dt>>,
     pub url: Url<'dt>,
     pub streamable: Option<Streamable>,
     pub artist: Id2<'dt>,
-    pub image: Vec<Image<'dt>>,
+    pub image: Images<'dt>,
     #[serde(rename = "@attr")]
     pub rank: Option<Rank>,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(Track { name, mbid, url, streamable, artist, image, rank });
+entity_t!(Track: HasName, HasMbid, HasImages);
+entity_t!(Track => Track);
+
+impl<'dt> From<Track<'dt>> for entity::Track<'dt> {
+    fn from(track: Track<'dt>) -> entity::Track<'dt> {
+        entity::Track {
+            mbid: track.mbid,
+            url: track.url.non_empty(),
+            artist: Some(track.artist.into()),
+            image: track.image,
+            streamable: track.streamable.map(|streamable| streamable.streamable),
+            ..entity::Track::new(track.name)
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct GetTopTracks<'dt> {
     #[serde(borrow)]
     pub track: Option<Vec<Track<'dt>>>,
+    #[serde(rename = "@attr")]
+    pub page_info: Option<PageInfo<'dt>>,
 }
 
+owned_t!(GetTopTracks { track, page_info });
+
 lastfm_t!(
     tracks,
     GetTopTracks,
     _TopTracks,
     Params,
     GetTopTracks,
+    [country: &'rq str],
     [
-        country: &'rq str,
-        location: Option<&'rq str>,
-        limit: Option<u32>,
-        page: Option<u32>
+        location: &'rq str,
+        limit: u32,
+        page: u32
     ]
 );
+
+paged_t!(GetTopTracks, track: Track);
@@ -3,8 +3,18 @@ use std::borrow::Cow;
 
 use url::Url as StdUrl;
 
-use lastfm_type::{LastfmType, Request, RequestParams};
-use super::common::{Url, Image, str_to_val};
+use lastfm_type::{LastfmType, Request, RequestParams, RequestBuilder, IntoArg};
+use lastfm_type::{Credentials, SessionState, SessionFree, Permits};
+use error::Result;
+use owned::IntoOwned;
+use validate;
+use paginate::{Pageable, Paged};
+use stream::Listed;
+use super::common::{PageInfo, Url, Images, str_to_val};
+use super::common::{val_to_str};
+use super::common::{str_to_bool, bool_to_str};
+use super::common::{Mbid, mbid_option};
+use super::entity::{self, HasName, HasMbid, HasImages};
 
 // ----------------------------------------------------------------
 
@@ -32,6 +42,16 @@ impl<'pr> RequestParams for Params<'pr> {
         false
     }
 
+    fn validate(&self) -> Result<()> {
+        match *self {
+            Params::GetArtists { user, limit, page } => {
+                validate::required("user", user)?;
+                validate::limit(limit, validate::MAX_LIMIT)?;
+                validate::page(page)
+            }
+        }
+    }
+
     fn append_to(&self, url: &mut StdUrl) {
         let mut query = url.query_pairs_mut();
         
@@ -43,33 +63,81 @@ impl<'pr> RequestParams for Params<'pr> {
     }
 }
 
+impl<'pr> Pageable for Params<'pr> {
+    fn page(&self) -> Option<u32> {
+        match *self {
+            Params::GetArtists { page, .. } => page,
+        }
+    }
+
+    fn set_page(&mut self, value: u32) -> Result<()> {
+        match *self {
+            Params::GetArtists { ref mut page, .. } => {
+                *page = Some(value);
+                Ok(())
+            }
+        }
+    }
+}
+
 // ----------------------------------------------------------------
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Artist<'dt> {
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
-    pub mbid: Option<&'dt str>,
+    #[serde(borrow)]
+    #[serde(default)]
+    #[serde(deserialize_with = "mbid_option")]
+    pub mbid: Option<Mbid<'dt>>,
     url: Url<'dt>,
     #[serde(deserialize_with = "str_to_val")]
+    #[serde(serialize_with = "val_to_str")]
     pub playcount: u32,
     #[serde(deserialize_with = "str_to_val")]
+    #[serde(serialize_with = "val_to_str")]
     pub tagcount: u32,
-    #[serde(deserialize_with = "str_to_val")]
-    pub streamable: u32,
-    pub image: Vec<Image<'dt>>,
+    #[serde(deserialize_with = "str_to_bool")]
+    #[serde(serialize_with = "bool_to_str")]
+    pub streamable: bool,
+    pub image: Images<'dt>,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(Artist { name, mbid, url, playcount, tagcount, streamable, image });
+entity_t!(Artist: HasName, HasMbid, HasImages);
+entity_t!(Artist => Artist);
+
+impl<'dt> From<Artist<'dt>> for entity::Artist<'dt> {
+    fn from(artist: Artist<'dt>) -> entity::Artist<'dt> {
+        entity::Artist {
+            mbid: artist.mbid,
+            url: artist.url.non_empty(),
+            image: artist.image,
+            playcount: Some(artist.playcount),
+            streamable: Some(artist.streamable),
+            ..entity::Artist::new(artist.name)
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct GetArtists<'dt> {
     #[serde(borrow)]
     pub artist: Option<Vec<Artist<'dt>>>,
+    #[serde(rename = "@attr")]
+    pub page_info: Option<PageInfo<'dt>>,
 }
 
+owned_t!(GetArtists { artist, page_info });
+
 lastfm_t!(
     artists,
     GetArtists,
     _Artists,
     Params,
     GetArtists,
-    [user: &'rq str, limit: Option<u32>, page: Option<u32>]
+    [user: &'rq str],
+    [limit: u32, page: u32]
 );
+
+paged_t!(GetArtists, artist: Artist);
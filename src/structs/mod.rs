@@ -1,3 +1,5 @@
+#[macro_use]
+pub mod entity;
 pub mod album;
 pub mod api_error;
 pub mod artist;
@@ -9,3 +11,4 @@ pub mod common;
 pub mod tag;
 pub mod track;
 pub mod user;
+pub mod wiki;
@@ -1,17 +1,33 @@
 use std::convert::Into;
 use std::borrow::Cow;
+use std::time::Duration;
 use std::marker::PhantomData;
 
 use url::Url as StdUrl;
 
-use lastfm_type::{LastfmType, Request, RequestParams};
-use super::common::{Url, Image, str_to_option, str_to_val};
+use lastfm_type::{LastfmType, Request, RequestParams, RequestBuilder, IntoArg};
+use lastfm_type::{Credentials, SessionState, SessionFree, Permits};
+use error::Result;
+use owned::IntoOwned;
+use validate;
+use paginate::{self, Pageable, Paged};
+use stream::Listed;
+use super::common::{PageInfo, Url, Images, str_to_val};
+use super::common::{val_to_str};
+use super::common::{str_to_option_bool, option_bool_to_str};
+use super::common::{Mbid, mbid_option};
+use super::common::{secs_to_duration, duration_to_secs};
+use super::entity::{self, HasName, HasMbid, HasImages};
+pub use super::wiki::Wiki;
 
 // ----------------------------------------------------------------
 
 #[derive(Debug)]
 pub enum Params<'pr> {
-    GetInfo { tag: &'pr str },
+    GetInfo {
+        tag: &'pr str,
+        lang: Option<&'pr str>,
+    },
     GetSimilar { tag: &'pr str },
     GetTopAlbums {
         tag: &'pr str,
@@ -53,11 +69,30 @@ impl<'pr> RequestParams for Params<'pr> {
         false
     }
 
+    fn validate(&self) -> Result<()> {
+        match *self {
+            Params::GetInfo { tag, .. } |
+            Params::GetSimilar { tag } |
+            Params::GetWeeklyChartList { tag } => validate::required("tag", tag),
+            Params::GetTopAlbums { tag, limit, page } |
+            Params::GetTopArtists { tag, limit, page } |
+            Params::GetTopTracks { tag, limit, page } => {
+                validate::required("tag", tag)?;
+                validate::limit(limit, validate::MAX_LIMIT)?;
+                validate::page(page)
+            }
+            Params::GetTopTags => Ok(()),
+        }
+    }
+
     fn append_to(&self, url: &mut StdUrl) {
         let mut query = url.query_pairs_mut();
         match *self {
-            Params::GetInfo { tag } => {
+            Params::GetInfo { tag, lang } => {
                 query.append_pair("tag", tag);
+                if let Some(lang) = lang {
+                    query.append_pair("lang", lang);
+                }
             }
             Params::GetSimilar { tag } => {
                 query.append_pair("tag", tag);
@@ -97,16 +132,34 @@ impl<'pr> RequestParams for Params<'pr> {
     }
 }
 
-// ----------------------------------------------------------------
+impl<'pr> Pageable for Params<'pr> {
+    fn page(&self) -> Option<u32> {
+        match *self {
+            Params::GetTopAlbums { page, .. } |
+            Params::GetTopArtists { page, .. } |
+            Params::GetTopTracks { page, .. } => page,
+            _ => None,
+        }
+    }
 
-#[derive(Deserialize, Debug)]
-pub struct Wiki<'dt> {
-    pub summary: Cow<'dt, str>,
-    pub content: Option<&'dt str>,
+    fn set_page(&mut self, value: u32) -> Result<()> {
+        match *self {
+            Params::GetTopAlbums { ref mut page, .. } |
+            Params::GetTopArtists { ref mut page, .. } |
+            Params::GetTopTracks { ref mut page, .. } => {
+                *page = Some(value);
+                Ok(())
+            }
+            _ => Err(paginate::not_paginated(self.method())),
+        }
+    }
 }
 
-#[derive(Deserialize, Debug)]
+// ----------------------------------------------------------------
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct GetInfo<'dt> {
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
     pub total: u32,
     pub reach: u32,
@@ -114,174 +167,302 @@ pub struct GetInfo<'dt> {
     pub wiki: Option<Wiki<'dt>>,
 }
 
+owned_t!(GetInfo { name, total, reach, wiki });
+entity_t!(GetInfo: HasName);
+
 lastfm_t!(
     tag,
     GetInfo,
     _Info,
     Params,
     GetInfo,
-    [tag: &'rq str]
+    [tag: &'rq str],
+    [lang: &'rq str]
 );
 
 // ----------------------------------------------------------------
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Similar<'dt> {
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
     pub url: Url<'dt>,
-    pub streamable: Option<u32>,
+    #[serde(default)]
+    #[serde(deserialize_with = "str_to_option_bool")]
+    #[serde(serialize_with = "option_bool_to_str")]
+    pub streamable: Option<bool>,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(Similar { name, url, streamable });
+entity_t!(Similar: HasName);
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct GetSimilar<'dt> {
     #[serde(borrow)]
     pub tag: Option<Vec<Similar<'dt>>>,
 }
 
+owned_t!(GetSimilar { tag });
+
 lastfm_t!(
     similartags,
     GetSimilar,
     _SimilarList,
     Params,
     GetSimilar,
-    [tag: &'rq str]
+    [tag: &'rq str],
+    []
 );
 
 // ----------------------------------------------------------------
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Artist1<'dt> {
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
-    pub mbid: Option<&'dt str>,
+    #[serde(borrow)]
+    #[serde(default)]
+    #[serde(deserialize_with = "mbid_option")]
+    pub mbid: Option<Mbid<'dt>>,
     pub url: Url<'dt>,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(Artist1 { name, mbid, url });
+entity_t!(Artist1: HasName, HasMbid);
+entity_t!(Artist1 => Artist);
+
+impl<'dt> From<Artist1<'dt>> for entity::Artist<'dt> {
+    fn from(artist: Artist1<'dt>) -> entity::Artist<'dt> {
+        entity::Artist {
+            mbid: artist.mbid,
+            url: artist.url.non_empty(),
+            ..entity::Artist::new(artist.name)
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Album<'dt> {
+    #[serde(borrow)]
     name: Cow<'dt, str>,
-    mbid: Option<&'dt str>,
+    #[serde(borrow)]
+    #[serde(default)]
+    #[serde(deserialize_with = "mbid_option")]
+    mbid: Option<Mbid<'dt>>,
     url: Url<'dt>,
     playcount: Option<u32>,
     artist: Artist1<'dt>,
-    image: Vec<Image<'dt>>,
+    image: Images<'dt>,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(Album { name, mbid, url, playcount, artist, image });
+entity_t!(Album: HasName, HasMbid, HasImages);
+entity_t!(Album => Album);
+
+impl<'dt> From<Album<'dt>> for entity::Album<'dt> {
+    fn from(album: Album<'dt>) -> entity::Album<'dt> {
+        entity::Album {
+            mbid: album.mbid,
+            url: album.url.non_empty(),
+            artist: Some(album.artist.into()),
+            image: album.image,
+            playcount: album.playcount,
+            ..entity::Album::new(album.name)
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct GetTopAlbums<'dt> {
     #[serde(borrow)]
     album: Option<Vec<Album<'dt>>>,
+    #[serde(rename = "@attr")]
+    pub page_info: Option<PageInfo<'dt>>,
 }
 
+owned_t!(GetTopAlbums { album, page_info });
+
 lastfm_t!(
     albums,
     GetTopAlbums,
     _TopAlbums,
     Params,
     GetTopAlbums,
-    [tag: &'rq str, limit: Option<u32>, page: Option<u32>]
+    [tag: &'rq str],
+    [limit: u32, page: u32]
 );
 
+paged_t!(GetTopAlbums, album: Album);
+
 // ----------------------------------------------------------------
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Artist2<'dt> {
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
     pub url: Url<'dt>,
     #[serde(default)]
-    #[serde(deserialize_with = "str_to_option")]
-    pub streamable: Option<u32>,
-    pub image: Vec<Image<'dt>>,
+    #[serde(deserialize_with = "str_to_option_bool")]
+    #[serde(serialize_with = "option_bool_to_str")]
+    pub streamable: Option<bool>,
+    pub image: Images<'dt>,
+}
+
+owned_t!(Artist2 { name, url, streamable, image });
+entity_t!(Artist2: HasName, HasImages);
+entity_t!(Artist2 => Artist);
+
+impl<'dt> From<Artist2<'dt>> for entity::Artist<'dt> {
+    fn from(artist: Artist2<'dt>) -> entity::Artist<'dt> {
+        entity::Artist {
+            url: artist.url.non_empty(),
+            image: artist.image,
+            streamable: artist.streamable,
+            ..entity::Artist::new(artist.name)
+        }
+    }
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct GetTopArtists<'dt> {
     #[serde(borrow)]
     pub artist: Option<Vec<Artist2<'dt>>>,
+    #[serde(rename = "@attr")]
+    pub page_info: Option<PageInfo<'dt>>,
 }
 
+owned_t!(GetTopArtists { artist, page_info });
+
 lastfm_t!(
     topartists,
     GetTopArtists,
     _TopArtists,
     Params,
     GetTopArtists,
-    [tag: &'rq str, limit: Option<u32>, page: Option<u32>]
+    [tag: &'rq str],
+    [limit: u32, page: u32]
 );
 
+paged_t!(GetTopArtists, artist: Artist2);
+
 // ----------------------------------------------------------------
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Tag<'dt> {
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
     pub count: u32,
     pub reach: u32,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(Tag { name, count, reach });
+entity_t!(Tag: HasName);
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct GetTopTags<'dt> {
     #[serde(borrow)]
     pub tag: Option<Vec<Tag<'dt>>>,
 }
 
+owned_t!(GetTopTags { tag });
+
 lastfm_t!(
     toptags,
     GetTopTags,
     _TopTags,
     Params,
     GetTopTags,
+    [],
     []
 );
 
 // ----------------------------------------------------------------
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Track<'dt> {
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
-    pub mbid: Option<&'dt str>,
+    #[serde(borrow)]
+    #[serde(default)]
+    #[serde(deserialize_with = "mbid_option")]
+    pub mbid: Option<Mbid<'dt>>,
     pub url: Url<'dt>,
-    #[serde(deserialize_with = "str_to_val")]
-    pub duration: u32,
+    #[serde(default)]
+    #[serde(deserialize_with = "secs_to_duration")]
+    #[serde(serialize_with = "duration_to_secs")]
+    pub duration: Option<Duration>,
     pub artist: Artist1<'dt>,
-    pub image: Vec<Image<'dt>>,
+    pub image: Images<'dt>,
+}
+
+owned_t!(Track { name, mbid, url, duration, artist, image });
+entity_t!(Track: HasName, HasMbid, HasImages);
+entity_t!(Track => Track);
+
+impl<'dt> From<Track<'dt>> for entity::Track<'dt> {
+    fn from(track: Track<'dt>) -> entity::Track<'dt> {
+        entity::Track {
+            mbid: track.mbid,
+            url: track.url.non_empty(),
+            artist: Some(track.artist.into()),
+            image: track.image,
+            duration: track.duration,
+            ..entity::Track::new(track.name)
+        }
+    }
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct GetTopTracks<'dt> {
     #[serde(borrow)]
     pub track: Option<Vec<Track<'dt>>>,
+    #[serde(rename = "@attr")]
+    pub page_info: Option<PageInfo<'dt>>,
 }
 
+owned_t!(GetTopTracks { track, page_info });
+
 lastfm_t!(
     tracks,
     GetTopTracks,
     _TopTracks,
     Params,
     GetTopTracks,
-    [tag: &'rq str, limit: Option<u32>, page: Option<u32>]
+    [tag: &'rq str],
+    [limit: u32, page: u32]
 );
 
+paged_t!(GetTopTracks, track: Track);
+
 // ----------------------------------------------------------------
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct WeeklyChartItem {
     #[serde(deserialize_with = "str_to_val")]
+    #[serde(serialize_with = "val_to_str")]
     pub from: u32,
     #[serde(deserialize_with = "str_to_val")]
+    #[serde(serialize_with = "val_to_str")]
     pub to: u32,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(WeeklyChartItem);
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct GetWeeklyChartList<'dt> {
     #[serde(skip)]
     phantom: PhantomData<&'dt ()>,
     chart: Option<Vec<WeeklyChartItem>>,
 }
 
+owned_t!(GetWeeklyChartList { phantom, chart });
+
 lastfm_t!(
     weeklychartlist,
     GetWeeklyChartList,
     _WeeklyChartList,
     Params,
     GetWeeklyChartList,
-    [tag: &'rq str]
+    [tag: &'rq str],
+    []
 );
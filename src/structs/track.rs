@@ -1,16 +1,28 @@
 #![allow(non_snake_case)]
 
 use std::convert::{Into, TryFrom};
-use std::io::{Error, ErrorKind};
 use std::borrow::Cow;
+use std::time::Duration;
 use std::marker::PhantomData;
 
 use url::{Url as StdUrl,UrlQuery};
 use url::form_urlencoded::Serializer;
 
-use lastfm_type::{LastfmType, Request, RequestParams};
-use super::common::{UnixTimestamp, VecOrStruct, Url, Image, SearchQuery};
+use lastfm_type::{LastfmType, Request, RequestParams, RequestBuilder, IntoArg};
+use lastfm_type::{Credentials, SessionState, SessionFree, Permits};
+use error::{Error, Result};
+use owned::IntoOwned;
+use validate;
+use paginate::{self, Pageable, Paged};
+use stream::Listed;
+use super::common::{UnixTimestamp, Timestamp, VecOrStruct, Url, Images, SearchQuery};
 use super::common::{str_to_option, str_to_val, vec_or_struct, str_to_variant};
+use super::common::{val_to_str, option_to_str, variant_to_str};
+use super::common::{str_to_bool, bool_to_str, str_to_option_bool, option_bool_to_str};
+use super::common::{Mbid, mbid_option};
+use super::common::{secs_to_duration, duration_to_secs, millis_to_duration, duration_to_millis};
+use super::entity::{self, HasName, HasMbid, HasImages, TrackIdent};
+use super::wiki::Wiki;
 
 // ----------------------------------------------------------------
 
@@ -18,10 +30,11 @@ use super::common::{str_to_option, str_to_val, vec_or_struct, str_to_variant};
 pub struct ScrobbleTrack {
     pub artist: String,
     pub track: String,
-    pub timestamp_utc: UnixTimestamp,
+    pub timestamp_utc: Timestamp,
     pub album: Option<String>,
     pub track_number: Option<u32>,
-    pub duration: Option<u32>,
+    /// Sent in whole seconds
+    pub duration: Option<Duration>,
 
     pub mbid: Option<String>,
     pub album_artist: Option<String>,
@@ -30,11 +43,12 @@ pub struct ScrobbleTrack {
 }
 
 impl ScrobbleTrack {
-    pub fn new(artist: String, track: String, timestamp_utc: UnixTimestamp) -> ScrobbleTrack {
+    /// Timestamp is the moment track started playing: `UnixTimestamp`, or `DateTime<Utc>` with `chrono` feature
+    pub fn new<T: Into<Timestamp>>(artist: String, track: String, timestamp_utc: T) -> ScrobbleTrack {
         ScrobbleTrack {
-            artist: artist,
-            track: track,
-            timestamp_utc: timestamp_utc,
+            artist,
+            track,
+            timestamp_utc: timestamp_utc.into(),
             album: None,
             track_number: None,
             duration: None,
@@ -48,7 +62,7 @@ impl ScrobbleTrack {
         mut self,
         album: Option<String>,
         track_number: Option<u32>,
-        duration: Option<u32>
+        duration: Option<Duration>
     ) -> ScrobbleTrack
     {
         self.album = album;
@@ -77,11 +91,11 @@ impl ScrobbleTrack {
         query.append_pair(&key("track", i), &self.track);
         query.append_pair(&key("timestamp", i), &self.timestamp_utc.to_string());
 
-        if let Some(ref alb) = self.album { query.append_pair(&key("album", i), &alb); }
+        if let Some(ref alb) = self.album { query.append_pair(&key("album", i), alb); }
         if let Some(ref tn) = self.track_number { query.append_pair(&key("trackNumber", i), &tn.to_string()); }
-        if let Some(ref dr) = self.duration { query.append_pair(&key("duration", i), &dr.to_string()); }
-        if let Some(ref mbid) = self.mbid { query.append_pair(&key("mbid", i), &mbid); }
-        if let Some(ref alar) = self.album_artist { query.append_pair(&key("albumArtist", i), &alar); }
+        if let Some(ref dr) = self.duration { query.append_pair(&key("duration", i), &dr.as_secs().to_string()); }
+        if let Some(ref mbid) = self.mbid { query.append_pair(&key("mbid", i), mbid); }
+        if let Some(ref alar) = self.album_artist { query.append_pair(&key("albumArtist", i), alar); }
 
         if let Some(chosen) = self.chosen_by_user {
             query.append_pair(
@@ -104,31 +118,23 @@ pub enum Params<'pr> {
     },
     GetCorrection { artist: &'pr str, track: &'pr str },
     GetInfo {
-        artist: &'pr str,
-        track: &'pr str,
-        mbid: Option<&'pr str>,
-        autocorrect: Option<u32>,
+        track: TrackIdent<'pr>,
+        autocorrect: Option<bool>,
         username: Option<&'pr str>,
     },
     GetSimilar {
-        artist: &'pr str,
-        track: &'pr str,
-        mbid: Option<&'pr str>,
-        autocorrect: Option<u32>,
+        track: TrackIdent<'pr>,
+        autocorrect: Option<bool>,
         limit: Option<u32>,
     },
     GetTags {
-        artist: &'pr str,
-        track: &'pr str,
-        mbid: Option<&'pr str>,
-        autocorrect: Option<u32>,
+        track: TrackIdent<'pr>,
+        autocorrect: Option<bool>,
         user: Option<&'pr str>,
     },
     GetTopTags {
-        artist: &'pr str,
-        track: &'pr str,
-        mbid: Option<&'pr str>,
-        autocorrect: Option<u32>,
+        track: TrackIdent<'pr>,
+        autocorrect: Option<bool>,
     },
     Love {
         artist: &'pr str,
@@ -157,7 +163,7 @@ pub enum Params<'pr> {
         trackNumber: Option<u32>,
         context: Option<&'pr str>,
         mbid: Option<&'pr str>,
-        duration: Option<u32>,
+        duration: Option<Duration>,
         albumArtist: Option<&'pr str>,
     },
 }
@@ -181,21 +187,72 @@ impl<'pr> RequestParams for Params<'pr> {
     }
 
     fn needs_signature(&self) -> bool {
-        match *self {
-            Params::AddTags { .. } => true,
-            Params::Love { .. } => true,
-            Params::RemoveTag { .. } => true,
-            Params::Scrobble { .. } => true,
-            Params::Unlove { .. } => true,
-            Params::UpdateNowPlaying { .. } => true,
-            _ => false,
-        }
+        matches!(
+            *self,
+            Params::AddTags { .. } |
+            Params::Love { .. } |
+            Params::RemoveTag { .. } |
+            Params::Scrobble { .. } |
+            Params::Unlove { .. } |
+            Params::UpdateNowPlaying { .. }
+        )
     }
 
     fn needs_session_key(&self) -> bool {
         self.needs_signature()
     }
 
+    fn validate(&self) -> Result<()> {
+        match *self {
+            Params::AddTags { artist, track, tags } => {
+                validate::required("artist", artist)?;
+                validate::required("track", track)?;
+                validate::tags(tags)
+            }
+            Params::GetCorrection { artist, track } |
+            Params::Love { artist, track } |
+            Params::Unlove { artist, track } |
+            Params::UpdateNowPlaying { artist, track, .. } => {
+                validate::required("artist", artist)?;
+                validate::required("track", track)
+            }
+            Params::GetInfo { ref track, .. } |
+            Params::GetTags { ref track, .. } |
+            Params::GetTopTags { ref track, .. } => track.validate(),
+            Params::GetSimilar { ref track, limit, .. } => {
+                track.validate()?;
+                validate::limit(limit, validate::MAX_LIMIT)
+            }
+            Params::RemoveTag { artist, track, tag } => {
+                validate::required("artist", artist)?;
+                validate::required("track", track)?;
+                validate::required("tag", tag)
+            }
+            Params::Scrobble { batch } => {
+                if batch.is_empty() || batch.len() > validate::MAX_SCROBBLES {
+                    return Err(Error::InvalidParameter {
+                        name: "batch",
+                        reason: format!(
+                            "must contain 1 to {} tracks, got {}",
+                            validate::MAX_SCROBBLES,
+                            batch.len()
+                        ),
+                    });
+                }
+                for track in batch {
+                    validate::required("artist", &track.artist)?;
+                    validate::required("track", &track.track)?;
+                }
+                Ok(())
+            }
+            Params::Search { track, limit, page, .. } => {
+                validate::required("track", track)?;
+                validate::limit(limit, validate::MAX_LIMIT)?;
+                validate::page(page)
+            }
+        }
+    }
+
     fn append_to(&self, url: &mut StdUrl) {
         let mut query = url.query_pairs_mut();
         match *self {
@@ -213,75 +270,51 @@ impl<'pr> RequestParams for Params<'pr> {
                 query.append_pair("track", track);
             }
             Params::GetInfo {
-                artist,
-                track,
-                mbid,
+                ref track,
                 autocorrect,
                 username,
             } => {
-                query.append_pair("artist", artist);
-                query.append_pair("track", track);
-                if let Some(mbid) = mbid {
-                    query.append_pair("mbid", mbid);
-                }
+                track.append_to(&mut query);
                 if let Some(autocorrect) = autocorrect {
-                    query.append_pair("autocorrect", &autocorrect.to_string());
+                    query.append_pair("autocorrect", &(autocorrect as u32).to_string());
                 }
                 if let Some(username) = username {
                     query.append_pair("username", username);
                 }
             }
             Params::GetSimilar {
-                artist,
-                track,
-                mbid,
+                ref track,
                 autocorrect,
                 limit,
             } => {
-                query.append_pair("artist", artist);
-                query.append_pair("track", track);
-                if let Some(mbid) = mbid {
-                    query.append_pair("mbid", mbid);
-                }
+                track.append_to(&mut query);
                 if let Some(autocorrect) = autocorrect {
-                    query.append_pair("autocorrect", &autocorrect.to_string());
+                    query.append_pair("autocorrect", &(autocorrect as u32).to_string());
                 }
                 if let Some(limit) = limit {
                     query.append_pair("limit", &limit.to_string());
                 }
             }
             Params::GetTags {
-                artist,
-                track,
-                mbid,
+                ref track,
                 autocorrect,
                 user,
             } => {
-                query.append_pair("artist", artist);
-                query.append_pair("track", track);
-                if let Some(mbid) = mbid {
-                    query.append_pair("mbid", mbid);
-                }
+                track.append_to(&mut query);
                 if let Some(autocorrect) = autocorrect {
-                    query.append_pair("autocorrect", &autocorrect.to_string());
+                    query.append_pair("autocorrect", &(autocorrect as u32).to_string());
                 }
                 if let Some(user) = user {
                     query.append_pair("user", user);
                 }
             }
             Params::GetTopTags {
-                artist,
-                track,
-                mbid,
+                ref track,
                 autocorrect,
             } => {
-                query.append_pair("artist", artist);
-                query.append_pair("track", track);
-                if let Some(mbid) = mbid {
-                    query.append_pair("mbid", mbid);
-                }
+                track.append_to(&mut query);
                 if let Some(autocorrect) = autocorrect {
-                    query.append_pair("autocorrect", &autocorrect.to_string());
+                    query.append_pair("autocorrect", &(autocorrect as u32).to_string());
                 }
             }
             Params::Love { artist, track } => {
@@ -293,7 +326,7 @@ impl<'pr> RequestParams for Params<'pr> {
                 query.append_pair("track", track);
                 query.append_pair("tag", tag);
             }
-            Params::Scrobble { ref batch } => {
+            Params::Scrobble { batch } => {
                 for (i, v) in batch.iter().enumerate() {
                     v.append_to(&mut query, i);
                 }
@@ -337,7 +370,7 @@ impl<'pr> RequestParams for Params<'pr> {
                         trackNumber: cv!(trackNumber),
                         context: context,
                         mbid: mbid,
-                        duration: cv!(duration),
+                        duration: &duration.as_secs().to_string(),
                         albumArtist: albumArtist
                     ]
                 );
@@ -346,6 +379,25 @@ impl<'pr> RequestParams for Params<'pr> {
     }
 }
 
+impl<'pr> Pageable for Params<'pr> {
+    fn page(&self) -> Option<u32> {
+        match *self {
+            Params::Search { page, .. } => page,
+            _ => None,
+        }
+    }
+
+    fn set_page(&mut self, value: u32) -> Result<()> {
+        match *self {
+            Params::Search { ref mut page, .. } => {
+                *page = Some(value);
+                Ok(())
+            }
+            _ => Err(paginate::not_paginated(self.method())),
+        }
+    }
+}
+
 // ----------------------------------------------------------------
 
 empty_lastfm_t!(
@@ -356,91 +408,198 @@ empty_lastfm_t!(
         artist: &'rq str,
         track: &'rq str,
         tags: &'rq str
-    ]
+    ],
+    [],
+    session
 );
 
 // ----------------------------------------------------------------
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Artist<'dt> {
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
-    pub mbid: Option<&'dt str>,
+    #[serde(borrow)]
+    #[serde(default)]
+    #[serde(deserialize_with = "mbid_option")]
+    pub mbid: Option<Mbid<'dt>>,
     pub url: Url<'dt>,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(Artist { name, mbid, url });
+entity_t!(Artist: HasName, HasMbid);
+entity_t!(Artist => Artist);
+
+impl<'dt> From<Artist<'dt>> for entity::Artist<'dt> {
+    fn from(artist: Artist<'dt>) -> entity::Artist<'dt> {
+        entity::Artist {
+            mbid: artist.mbid,
+            url: artist.url.non_empty(),
+            ..entity::Artist::new(artist.name)
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Track<'dt> {
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
     pub url: Url<'dt>,
     #[serde(borrow)]
     pub artist: Artist<'dt>,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(Track { name, url, artist });
+entity_t!(Track: HasName);
+entity_t!(Track => Track);
+
+impl<'dt> From<Track<'dt>> for entity::Track<'dt> {
+    fn from(track: Track<'dt>) -> entity::Track<'dt> {
+        entity::Track {
+            url: track.url.non_empty(),
+            artist: Some(track.artist.into()),
+            ..entity::Track::new(track.name)
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Correction<'dt> {
     #[serde(borrow)]
     pub track: Track<'dt>,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(Correction { track });
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct GetCorrections<'dt> {
     #[serde(borrow)]
     correction: Correction<'dt>,
 }
 
+owned_t!(GetCorrections { correction });
+
 lastfm_t!(
     corrections,
     GetCorrections,
     _Correction,
     Params,
     GetCorrection,
-    [artist: &'rq str, track: &'rq str]
+    [artist: &'rq str, track: &'rq str],
+    []
 );
 
 // ----------------------------------------------------------------
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Album<'dt> {
+    #[serde(borrow)]
     pub artist: Cow<'dt, str>,
+    #[serde(borrow)]
     pub title: Cow<'dt, str>,
-    pub mbid: Option<&'dt str>,
+    #[serde(borrow)]
+    #[serde(default)]
+    #[serde(deserialize_with = "mbid_option")]
+    pub mbid: Option<Mbid<'dt>>,
     pub url: Url<'dt>,
-    pub image: Vec<Image<'dt>>,
+    pub image: Images<'dt>,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(Album { artist, title, mbid, url, image });
+entity_t!(Album: HasMbid, HasImages);
+entity_t!(Album => Album);
+
+impl<'dt> HasName for Album<'dt> {
+    fn name(&self) -> &str {
+        &self.title
+    }
+}
+
+impl<'dt> From<Album<'dt>> for entity::Album<'dt> {
+    fn from(album: Album<'dt>) -> entity::Album<'dt> {
+        entity::Album {
+            mbid: album.mbid,
+            url: album.url.non_empty(),
+            artist: Some(entity::ArtistRef::from_name(album.artist)),
+            image: album.image,
+            ..entity::Album::new(album.title)
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Tag1<'dt> {
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
     pub url: Url<'dt>,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(Tag1 { name, url });
+entity_t!(Tag1: HasName);
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Tags<'dt> {
     #[serde(borrow)]
     pub tag: Option<Vec<Tag1<'dt>>>,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(Tags { tag });
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct GetInfo<'dt> {
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
-    pub mbid: Option<&'dt str>,
+    #[serde(borrow)]
+    #[serde(default)]
+    #[serde(deserialize_with = "mbid_option")]
+    pub mbid: Option<Mbid<'dt>>,
     pub url: Url<'dt>,
+    #[serde(default)]
+    #[serde(deserialize_with = "millis_to_duration")]
+    #[serde(serialize_with = "duration_to_millis")]
+    pub duration: Option<Duration>,
     #[serde(deserialize_with = "str_to_val")]
-    pub duration: u32,
-    #[serde(deserialize_with = "str_to_val")]
+    #[serde(serialize_with = "val_to_str")]
     pub listeners: u32,
     #[serde(default)]
     #[serde(deserialize_with = "str_to_option")]
+    #[serde(serialize_with = "option_to_str")]
     pub playcount: Option<u32>,
     #[serde(default)]
     #[serde(deserialize_with = "str_to_option")]
+    #[serde(serialize_with = "option_to_str")]
     pub userplaycount: Option<u32>,
     #[serde(default)]
-    #[serde(deserialize_with = "str_to_option")]
-    pub userloved: Option<u32>,
+    #[serde(deserialize_with = "str_to_option_bool")]
+    #[serde(serialize_with = "option_bool_to_str")]
+    pub userloved: Option<bool>,
     pub artist: Artist<'dt>,
     pub album: Album<'dt>,
     pub toptags: Tags<'dt>,
+    #[serde(borrow)]
+    pub wiki: Option<Wiki<'dt>>,
+}
+
+owned_t!(GetInfo {
+    name, mbid, url, duration, listeners, playcount, userplaycount, userloved, artist, album,
+    toptags, wiki,
+});
+entity_t!(GetInfo: HasName, HasMbid);
+entity_t!(GetInfo => Track);
+
+impl<'dt> From<GetInfo<'dt>> for entity::Track<'dt> {
+    fn from(track: GetInfo<'dt>) -> entity::Track<'dt> {
+        entity::Track {
+            mbid: track.mbid,
+            url: track.url.non_empty(),
+            artist: Some(track.artist.into()),
+            album: Some(track.album.into()),
+            playcount: track.playcount,
+            listeners: Some(track.listeners),
+            duration: track.duration,
+            ..entity::Track::new(track.name)
+        }
+    }
 }
 
 lastfm_t!(
@@ -450,36 +609,64 @@ lastfm_t!(
     Params,
     GetInfo,
     [
-        artist: &'rq str,
-        track: &'rq str,
-        mbid: Option<&'rq str>,
-        autocorrect: Option<u32>,
-        username: Option<&'rq str>
+        track: impl Into<TrackIdent<'rq>>
+    ],
+    [
+        autocorrect: bool,
+        username: &'rq str
     ]
 );
 
 // ----------------------------------------------------------------
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Similar<'dt> {
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
-    pub mbid: Option<&'dt str>,
+    #[serde(borrow)]
+    #[serde(default)]
+    #[serde(deserialize_with = "mbid_option")]
+    pub mbid: Option<Mbid<'dt>>,
     pub url: Url<'dt>,
     #[serde(deserialize_with = "str_to_option")]
+    #[serde(serialize_with = "option_to_str")]
     pub playcount: Option<u32>,
     #[serde(rename = "match")]
     pub trackmatch: f32,
-    pub duration: u32,
+    #[serde(default)]
+    #[serde(deserialize_with = "secs_to_duration")]
+    #[serde(serialize_with = "duration_to_secs")]
+    pub duration: Option<Duration>,
     pub artist: Artist<'dt>,
-    pub image: Vec<Image<'dt>>,
+    pub image: Images<'dt>,
+}
+
+owned_t!(Similar { name, mbid, url, playcount, trackmatch, duration, artist, image });
+entity_t!(Similar: HasName, HasMbid, HasImages);
+entity_t!(Similar => Track);
+
+impl<'dt> From<Similar<'dt>> for entity::Track<'dt> {
+    fn from(track: Similar<'dt>) -> entity::Track<'dt> {
+        entity::Track {
+            mbid: track.mbid,
+            url: track.url.non_empty(),
+            artist: Some(track.artist.into()),
+            image: track.image,
+            playcount: track.playcount,
+            duration: track.duration,
+            ..entity::Track::new(track.name)
+        }
+    }
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct GetSimilar<'dt> {
     #[serde(borrow)]
     pub track: Option<Vec<Similar<'dt>>>,
 }
 
+owned_t!(GetSimilar { track });
+
 lastfm_t!(
     similartracks,
     GetSimilar,
@@ -487,11 +674,11 @@ lastfm_t!(
     Params,
     GetSimilar,
     [
-        artist: &'rq str,
-        track: &'rq str,
-        mbid: Option<&'rq str>,
-        autocorrect: Option<u32>,
-        limit: Option<u32>
+        track: impl Into<TrackIdent<'rq>>
+    ],
+    [
+        autocorrect: bool,
+        limit: u32
     ]
 );
 
@@ -506,29 +693,35 @@ lastfm_t!(
     Params,
     GetTags,
     [
-        artist: &'rq str,
-        track: &'rq str,
-        mbid: Option<&'rq str>,
-        autocorrect: Option<u32>,
-        user: Option<&'rq str>
+        track: impl Into<TrackIdent<'rq>>
+    ],
+    [
+        autocorrect: bool,
+        user: &'rq str
     ]
 );
 
 // ----------------------------------------------------------------
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Tag2<'dt> {
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
     pub count: u32,
     pub url: Url<'dt>,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(Tag2 { name, count, url });
+entity_t!(Tag2: HasName);
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct GetTopTags<'dt> {
     #[serde(borrow)]
     pub tag: Option<Vec<Tag2<'dt>>>,
 }
 
+owned_t!(GetTopTags { tag });
+
 lastfm_t!(
     toptags,
     GetTopTags,
@@ -536,10 +729,10 @@ lastfm_t!(
     Params,
     GetTopTags,
     [
-        artist: &'rq str,
-        track: &'rq str,
-        mbid: Option<&'rq str>,
-        autocorrect: Option<u32>
+        track: impl Into<TrackIdent<'rq>>
+    ],
+    [
+        autocorrect: bool
     ]
 );
 
@@ -552,7 +745,9 @@ empty_lastfm_t!(
     [
         artist: &'rq str,
         track: &'rq str
-    ]
+    ],
+    [],
+    session
 );
 
 // ----------------------------------------------------------------
@@ -565,27 +760,35 @@ empty_lastfm_t!(
         artist: &'rq str,
         track: &'rq str,
         tag: &'rq str
-    ]
+    ],
+    [],
+    session
 );
 
 // ----------------------------------------------------------------
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Summary {
     pub accepted: u32,
     pub ignored: u32,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(Summary);
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Field<'dt> {
-    #[serde(deserialize_with="str_to_val")]
-    pub corrected: u32,
+    #[serde(deserialize_with = "str_to_bool")]
+    #[serde(serialize_with = "bool_to_str")]
+    pub corrected: bool,
     #[serde(default)]
     #[serde(rename="#text")]
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(Field { corrected, name });
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Report<'dt> {
     #[serde(borrow)]
     pub artist: Field<'dt>,
@@ -594,12 +797,15 @@ pub struct Report<'dt> {
     pub album: Field<'dt>,
     pub track: Field<'dt>,
     #[serde(deserialize_with="str_to_val")]
+    #[serde(serialize_with="val_to_str")]
     pub timestamp: UnixTimestamp,
     #[serde(rename="ignoredMessage")]
     pub ignored_message: IgnoredMessage<'dt>,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(Report { artist, album_artist, album, track, timestamp, ignored_message });
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Scrobble<'dt> {
     #[serde(rename="@attr")]
     pub summary: Summary,
@@ -608,33 +814,58 @@ pub struct Scrobble<'dt> {
     pub scrobble: VecOrStruct<'dt, Report<'dt>>,
 }
 
+owned_t!(Scrobble { summary, scrobble });
+
 lastfm_t!(
     scrobbles,
     Scrobble,
     _Scrobble,
     Params,
     Scrobble,
-    [batch: &'rq ScrobbleBatch]
+    [batch: &'rq ScrobbleBatch],
+    [],
+    session
 );
 
 // ----------------------------------------------------------------
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Track2<'dt> {
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
+    #[serde(borrow)]
     pub artist: Cow<'dt, str>,
     pub url: Url<'dt>,
     #[serde(deserialize_with = "str_to_val")]
+    #[serde(serialize_with = "val_to_str")]
     pub listeners: u32,
-    pub image: Vec<Image<'dt>>,
+    pub image: Images<'dt>,
+}
+
+owned_t!(Track2 { name, artist, url, listeners, image });
+entity_t!(Track2: HasName, HasImages);
+entity_t!(Track2 => Track);
+
+impl<'dt> From<Track2<'dt>> for entity::Track<'dt> {
+    fn from(track: Track2<'dt>) -> entity::Track<'dt> {
+        entity::Track {
+            url: track.url.non_empty(),
+            artist: Some(entity::ArtistRef::from_name(track.artist)),
+            image: track.image,
+            listeners: Some(track.listeners),
+            ..entity::Track::new(track.name)
+        }
+    }
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct SearchData<'dt> {
     #[serde(borrow)]
     pub track: Option<Vec<Track2<'dt>>>,
 }
 
+owned_t!(SearchData { track });
+
 opensearch_t!(
     results,
     Search,
@@ -645,12 +876,16 @@ opensearch_t!(
     Search,
     [
         artist: &'rq str,
-        track: &'rq str,
-        limit: Option<u32>,
-        page: Option<u32>
+        track: &'rq str
+    ],
+    [
+        limit: u32,
+        page: u32
     ]
 );
 
+paged_t!(Search, trackmatches.track: Track2);
+
 // ----------------------------------------------------------------
 
 empty_lastfm_t!(
@@ -660,20 +895,26 @@ empty_lastfm_t!(
     [
         artist: &'rq str,
         track: &'rq str
-    ]
+    ],
+    [],
+    session
 );
 
 // ----------------------------------------------------------------
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct NowPlayingItem<'dt> {
     #[serde(rename="#text")]
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
-    #[serde(deserialize_with="str_to_val")]
-    pub corrected: u32,
+    #[serde(deserialize_with = "str_to_bool")]
+    #[serde(serialize_with = "bool_to_str")]
+    pub corrected: bool,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(NowPlayingItem { name, corrected });
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy)]
 pub enum IgnoredMessageCode {
     None,
     FilteredArtist,
@@ -683,10 +924,12 @@ pub enum IgnoredMessageCode {
     MaxDailyScrobblesExceeded,
 }
 
+owned_t!(IgnoredMessageCode);
+
 impl TryFrom<u32> for IgnoredMessageCode {
     type Error = Error;
 
-    fn try_from(value: u32) -> Result<IgnoredMessageCode, Self::Error> {
+    fn try_from(value: u32) -> Result<IgnoredMessageCode> {
         match value {
             0 => Ok(IgnoredMessageCode::None),
             1 => Ok(IgnoredMessageCode::FilteredArtist),
@@ -694,20 +937,33 @@ impl TryFrom<u32> for IgnoredMessageCode {
             3 => Ok(IgnoredMessageCode::TimestampTooFarInThePast),
             4 => Ok(IgnoredMessageCode::TimestampTooFarInTheFuture),
             5 => Ok(IgnoredMessageCode::MaxDailyScrobblesExceeded),
-            _ => Err(Error::new(ErrorKind::InvalidInput, "no matching variant")),
+            _ => Err(Error::InvalidParameter {
+                name: "ignoredMessage.code",
+                reason: format!("no matching variant for {}", value),
+            }),
         }
     }
 }
 
-#[derive(Deserialize, Debug)]
+impl From<IgnoredMessageCode> for u32 {
+    fn from(code: IgnoredMessageCode) -> u32 {
+        code as u32
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct IgnoredMessage<'dt> {
     #[serde(rename="#text")]
+    #[serde(borrow)]
     pub reason: Cow<'dt, str>,
     #[serde(deserialize_with="str_to_variant")]
+    #[serde(serialize_with="variant_to_str")]
     pub code: IgnoredMessageCode,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(IgnoredMessage { reason, code });
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct UpdateNowPlaying<'dt> {
     #[serde(borrow)]
     pub album: NowPlayingItem<'dt>,
@@ -717,6 +973,8 @@ pub struct UpdateNowPlaying<'dt> {
     pub track: NowPlayingItem<'dt>
 }
 
+owned_t!(UpdateNowPlaying { album, albumArtist, artist, ignoredMessage, track });
+
 lastfm_t!(
     nowplaying,
     UpdateNowPlaying,
@@ -725,12 +983,15 @@ lastfm_t!(
     UpdateNowPlaying,
     [
         artist: &'rq str,
-        track: &'rq str,
-        album: Option<&'rq str>,
-        trackNumber: Option<u32>,
-        context: Option<&'rq str>,
-        mbid: Option<&'rq str>,
-        duration: Option<u32>,
-        albumArtist: Option<&'rq str>
-    ]
+        track: &'rq str
+    ],
+    [
+        album: &'rq str,
+        trackNumber: u32,
+        context: &'rq str,
+        mbid: &'rq str,
+        duration: Duration,
+        albumArtist: &'rq str
+    ],
+    session
 );
@@ -1,10 +1,23 @@
 use std::convert::Into;
 use std::borrow::Cow;
+use std::time::Duration;
 
 use url::Url as StdUrl;
 
-use lastfm_type::{LastfmType, Request, RequestParams};
-use super::common::{UnixTimestamp, Url, Image, Id1, Id2, Streamable, Rank, str_to_option, str_to_val};
+use lastfm_type::{LastfmType, Request, RequestParams, RequestBuilder, IntoArg};
+use lastfm_type::{Credentials, SessionState, SessionFree, Permits};
+use error::Result;
+use owned::IntoOwned;
+use validate;
+use paginate::{self, Pageable, Paged};
+use stream::Listed;
+use super::common::{UnixTimestamp, Timestamp, Url, Images, Id1, Id2, Streamable, Rank, PageInfo};
+use super::common::{str_to_option, str_to_val, num_or_str_to_option, cow_option, cow_or_num};
+use super::common::{val_to_str, option_to_str};
+use super::common::{str_to_bool, bool_to_str, str_to_option_bool, option_bool_to_str};
+use super::common::{Mbid, mbid_option};
+use super::common::{secs_to_duration, duration_to_secs};
+use super::entity::{self, HasName, HasMbid, HasImages};
 
 // ----------------------------------------------------------------
 
@@ -25,8 +38,6 @@ impl TaggingType {
     }
 }
 
-pub type ChartDate = i64;
-
 #[derive(Debug, Clone, Copy)]
 pub enum Period {
     OneWeek,
@@ -76,8 +87,8 @@ pub enum Params<'pr> {
     GetRecentTracks {
         user: &'pr str,
         extended: Option<bool>,
-        from: Option<UnixTimestamp>,
-        to: Option<UnixTimestamp>,
+        from: Option<Timestamp>,
+        to: Option<Timestamp>,
         limit: Option<u32>,
         page: Option<u32>,
     },
@@ -102,19 +113,19 @@ pub enum Params<'pr> {
     },
     GetWeeklyAlbumChart {
         user: &'pr str,
-        from: Option<ChartDate>,
-        to: Option<ChartDate>,
+        from: Option<Timestamp>,
+        to: Option<Timestamp>,
     },
     GetWeeklyArtistChart {
         user: &'pr str,
-        from: Option<ChartDate>,
-        to: Option<ChartDate>,
+        from: Option<Timestamp>,
+        to: Option<Timestamp>,
     },
     GetWeeklyChartList { user: &'pr str },
     GetWeeklyTrackChart {
         user: &'pr str,
-        from: Option<ChartDate>,
-        to: Option<ChartDate>,
+        from: Option<Timestamp>,
+        to: Option<Timestamp>,
     },
 }
 
@@ -145,6 +156,44 @@ impl<'pr> RequestParams for Params<'pr> {
         false
     }
 
+    fn validate(&self) -> Result<()> {
+        match *self {
+            Params::GetInfo { user } |
+            Params::GetWeeklyChartList { user } => validate::required("user", user),
+            Params::GetFriends { user, limit, page, .. } |
+            Params::GetLovedTracks { user, limit, page } |
+            Params::GetTopAlbums { user, limit, page, .. } |
+            Params::GetTopArtists { user, limit, page, .. } |
+            Params::GetTopTracks { user, limit, page, .. } => {
+                validate::required("user", user)?;
+                validate::limit(limit, validate::MAX_LIMIT)?;
+                validate::page(page)
+            }
+            Params::GetPersonalTags { user, tag, limit, page, .. } => {
+                validate::required("user", user)?;
+                validate::required("tag", tag)?;
+                validate::limit(limit, validate::MAX_LIMIT)?;
+                validate::page(page)
+            }
+            Params::GetRecentTracks { user, from, to, limit, page, .. } => {
+                validate::required("user", user)?;
+                validate::range(from, to)?;
+                validate::limit(limit, validate::MAX_RECENT_TRACKS_LIMIT)?;
+                validate::page(page)
+            }
+            Params::GetTopTags { user, limit } => {
+                validate::required("user", user)?;
+                validate::limit(limit, validate::MAX_LIMIT)
+            }
+            Params::GetWeeklyAlbumChart { user, from, to } |
+            Params::GetWeeklyArtistChart { user, from, to } |
+            Params::GetWeeklyTrackChart { user, from, to } => {
+                validate::required("user", user)?;
+                validate::range(from, to)
+            }
+        }
+    }
+
     fn append_to(&self, url: &mut StdUrl) {
         let mut query = url.query_pairs_mut();
         match *self {
@@ -310,9 +359,40 @@ impl<'pr> RequestParams for Params<'pr> {
     }
 }
 
+impl<'pr> Pageable for Params<'pr> {
+    fn page(&self) -> Option<u32> {
+        match *self {
+            Params::GetFriends { page, .. } |
+            Params::GetLovedTracks { page, .. } |
+            Params::GetPersonalTags { page, .. } |
+            Params::GetRecentTracks { page, .. } |
+            Params::GetTopAlbums { page, .. } |
+            Params::GetTopArtists { page, .. } |
+            Params::GetTopTracks { page, .. } => page,
+            _ => None,
+        }
+    }
+
+    fn set_page(&mut self, value: u32) -> Result<()> {
+        match *self {
+            Params::GetFriends { ref mut page, .. } |
+            Params::GetLovedTracks { ref mut page, .. } |
+            Params::GetPersonalTags { ref mut page, .. } |
+            Params::GetRecentTracks { ref mut page, .. } |
+            Params::GetTopAlbums { ref mut page, .. } |
+            Params::GetTopArtists { ref mut page, .. } |
+            Params::GetTopTracks { ref mut page, .. } => {
+                *page = Some(value);
+                Ok(())
+            }
+            _ => Err(paginate::not_paginated(self.method())),
+        }
+    }
+}
+
 // ----------------------------------------------------------------
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub enum Gender {
     #[serde(rename = "m")]
     Male,
@@ -322,206 +402,383 @@ pub enum Gender {
     NotSpecified,
 }
 
-#[derive(Deserialize, Debug)]
-pub struct Date2<'dt> {
-    #[serde(deserialize_with = "str_to_val")]
-    pub unixtime: UnixTimestamp,
+owned_t!(Gender);
+
+/// Moment in time as service reports it: unix timestamp along with textual date like "13 Dec 2017, 09:37".
+/// Methods name the fields differently (`uts` or `unixtime`, `#text` or `date`) and sometimes
+/// repeat the timestamp instead of text, all of those shapes are accepted.
+/// Serialized as `uts` and `#text`.
+#[derive(Deserialize, Serialize, Debug)]
+pub struct Date<'dt> {
+    #[serde(alias = "unixtime")]
+    #[serde(default)]
+    #[serde(deserialize_with = "num_or_str_to_option")]
+    #[serde(serialize_with = "option_to_str")]
+    pub uts: Option<UnixTimestamp>,
     #[serde(rename = "#text")]
+    #[serde(alias = "date")]
+    #[serde(borrow)]
+    #[serde(default)]
+    #[serde(deserialize_with = "cow_or_num")]
     pub text: Cow<'dt, str>,
 }
 
-#[derive(Deserialize, Debug)]
-pub struct Date3<'dt> {
-    #[serde(deserialize_with = "str_to_option")]
-    pub uts: Option<UnixTimestamp>,
-    pub date: Cow<'dt, str>,
-}
+owned_t!(Date { uts, text });
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Track2<'dt> {
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
-    pub mbid: Option<&'dt str>,
+    #[serde(borrow)]
+    #[serde(default)]
+    #[serde(deserialize_with = "mbid_option")]
+    pub mbid: Option<Mbid<'dt>>,
     pub url: Url<'dt>,
     pub artist: Id2<'dt>,
     pub album: Id2<'dt>,
     #[serde(rename = "@attr")]
-    pub date: Date3<'dt>,
+    pub date: Date<'dt>,
+}
+
+owned_t!(Track2 { name, mbid, url, artist, album, date });
+entity_t!(Track2: HasName, HasMbid);
+entity_t!(Track2 => Track);
+
+impl<'dt> From<Track2<'dt>> for entity::Track<'dt> {
+    fn from(track: Track2<'dt>) -> entity::Track<'dt> {
+        let artist = Some(track.artist.into());
+        entity::Track {
+            mbid: track.mbid,
+            url: track.url.non_empty(),
+            album: track_album(track.album.into(), &artist),
+            artist,
+            ..entity::Track::new(track.name)
+        }
+    }
+}
+
+/// Album of a track by given artist, unless response leaves it blank
+fn track_album<'dt>(
+    album: entity::AlbumRef<'dt>,
+    artist: &Option<entity::ArtistRef<'dt>>,
+) -> Option<entity::AlbumRef<'dt>> {
+    if album.name.is_empty() {
+        None
+    } else {
+        Some(entity::AlbumRef { artist: artist.clone(), ..album })
+    }
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct User<'dt> {
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
     pub url: Url<'dt>,
+    #[serde(borrow)]
     pub country: Cow<'dt, str>,
     #[serde(deserialize_with = "str_to_val")]
+    #[serde(serialize_with = "val_to_str")]
     pub playcount: u32,
     #[serde(deserialize_with = "str_to_val")]
+    #[serde(serialize_with = "val_to_str")]
     pub playlists: u32,
-    #[serde(deserialize_with = "str_to_val")]
-    pub bootstrap: u32,
-    pub registered: Date2<'dt>,
-    pub image: Vec<Image<'dt>>,
+    #[serde(deserialize_with = "str_to_bool")]
+    #[serde(serialize_with = "bool_to_str")]
+    pub bootstrap: bool,
+    pub registered: Date<'dt>,
+    pub image: Images<'dt>,
     pub recenttrack: Option<Track2<'dt>>,
 
+    /// `None` when service returns a placeholder like FIXME instead of a flag
+    #[serde(default)]
+    #[serde(deserialize_with = "str_to_option_bool")]
+    #[serde(serialize_with = "option_bool_to_str")]
+    pub subscriber: Option<bool>,
+
     // Service always returns FIXME in fields below
-    pub subscriber: Option<&'dt str>,
     #[serde(rename = "type")]
-    pub u_type: Option<&'dt str>,
-    pub scrobblesource: Option<&'dt str>,
+    #[serde(borrow)]
+    #[serde(default)]
+    #[serde(deserialize_with = "cow_option")]
+    pub u_type: Option<Cow<'dt, str>>,
+    #[serde(borrow)]
+    #[serde(default)]
+    #[serde(deserialize_with = "cow_option")]
+    pub scrobblesource: Option<Cow<'dt, str>>,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(User {
+    name, url, country, playcount, playlists, bootstrap, registered, image, recenttrack,
+    subscriber, u_type, scrobblesource,
+});
+entity_t!(User: HasName, HasImages);
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct GetFriends<'dt> {
     #[serde(borrow)]
     pub user: Option<Vec<User<'dt>>>,
+    #[serde(rename = "@attr")]
+    pub page_info: Option<PageInfo<'dt>>,
 }
 
+owned_t!(GetFriends { user, page_info });
+
 lastfm_t!(
     friends,
     GetFriends,
     _Friends,
     Params,
     GetFriends,
+    [user: &'rq str],
     [
-        user: &'rq str,
-        recenttracks: Option<bool>,
-        limit: Option<u32>,
-        page: Option<u32>
+        recenttracks: bool,
+        limit: u32,
+        page: u32
     ]
 );
 
-// ----------------------------------------------------------------
+paged_t!(GetFriends, user: User);
 
-#[derive(Deserialize, Debug)]
-pub struct Date4 {
-    #[serde(deserialize_with = "str_to_val")]
-    pub unixtime: UnixTimestamp,
-    #[serde(rename = "#text")]
-    pub text: UnixTimestamp,
-}
+// ----------------------------------------------------------------
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct GetInfo<'dt> {
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
     pub url: Url<'dt>,
+    #[serde(borrow)]
     pub country: Cow<'dt, str>,
     #[serde(deserialize_with = "str_to_option")]
+    #[serde(serialize_with = "option_to_str")]
     pub age: Option<u32>,
     pub gender: Gender,
     #[serde(deserialize_with = "str_to_val")]
+    #[serde(serialize_with = "val_to_str")]
     pub playcount: u32,
     #[serde(deserialize_with = "str_to_val")]
+    #[serde(serialize_with = "val_to_str")]
     pub playlists: u32,
-    #[serde(deserialize_with = "str_to_val")]
-    pub bootstrap: u32,
-    pub registered: Date4,
-    pub image: Vec<Image<'dt>>,
+    #[serde(deserialize_with = "str_to_bool")]
+    #[serde(serialize_with = "bool_to_str")]
+    pub bootstrap: bool,
+    #[serde(borrow)]
+    pub registered: Date<'dt>,
+    pub image: Images<'dt>,
     pub recenttrack: Option<Track2<'dt>>,
 
+    /// `None` when service returns a placeholder like FIXME instead of a flag
+    #[serde(default)]
+    #[serde(deserialize_with = "str_to_option_bool")]
+    #[serde(serialize_with = "option_bool_to_str")]
+    pub subscriber: Option<bool>,
+
     // Service always returns FIXME in fields below
-    pub subscriber: Option<&'dt str>,
     #[serde(rename = "type")]
-    pub u_type: Option<&'dt str>,
-    pub scrobblesource: Option<&'dt str>,
+    #[serde(borrow)]
+    #[serde(default)]
+    #[serde(deserialize_with = "cow_option")]
+    pub u_type: Option<Cow<'dt, str>>,
+    #[serde(borrow)]
+    #[serde(default)]
+    #[serde(deserialize_with = "cow_option")]
+    pub scrobblesource: Option<Cow<'dt, str>>,
 }
 
+owned_t!(GetInfo {
+    name, url, country, age, gender, playcount, playlists, bootstrap, registered, image,
+    recenttrack, subscriber, u_type, scrobblesource,
+});
+entity_t!(GetInfo: HasName, HasImages);
+
 lastfm_t!(
     user,
     GetInfo,
     _Info,
     Params,
     GetInfo,
-    [user: &'rq str]
+    [user: &'rq str],
+    []
 );
 
 // ----------------------------------------------------------------
 
-#[derive(Deserialize, Debug)]
-pub struct Date1<'dt> {
-    #[serde(deserialize_with = "str_to_option")]
-    pub uts: Option<UnixTimestamp>,
-    #[serde(rename = "#text")]
-    pub text: Cow<'dt, str>,
-}
-
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Track3<'dt> {
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
-    pub mbid: Option<&'dt str>,
+    #[serde(borrow)]
+    #[serde(default)]
+    #[serde(deserialize_with = "mbid_option")]
+    pub mbid: Option<Mbid<'dt>>,
     pub url: Url<'dt>,
-    pub date: Date1<'dt>,
+    pub date: Date<'dt>,
     pub artist: Id2<'dt>,
-    pub image: Vec<Image<'dt>>,
+    pub image: Images<'dt>,
     pub streamable: Streamable,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(Track3 { name, mbid, url, date, artist, image, streamable });
+entity_t!(Track3: HasName, HasMbid, HasImages);
+entity_t!(Track3 => Track);
+
+impl<'dt> From<Track3<'dt>> for entity::Track<'dt> {
+    fn from(track: Track3<'dt>) -> entity::Track<'dt> {
+        entity::Track {
+            mbid: track.mbid,
+            url: track.url.non_empty(),
+            artist: Some(track.artist.into()),
+            image: track.image,
+            streamable: Some(track.streamable.streamable),
+            ..entity::Track::new(track.name)
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct GetLovedTracks<'dt> {
     #[serde(borrow)]
     pub track: Option<Vec<Track3<'dt>>>,
+    #[serde(rename = "@attr")]
+    pub page_info: Option<PageInfo<'dt>>,
 }
 
+owned_t!(GetLovedTracks { track, page_info });
+
 lastfm_t!(
     lovedtracks,
     GetLovedTracks,
     _LovedTracks,
     Params,
     GetLovedTracks,
-    [user: &'rq str, limit: Option<u32>, page: Option<u32>]
+    [user: &'rq str],
+    [limit: u32, page: u32]
 );
 
+paged_t!(GetLovedTracks, track: Track3);
+
 // ----------------------------------------------------------------
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Artist1<'dt> {
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
-    pub mbid: Option<&'dt str>,
+    #[serde(borrow)]
+    #[serde(default)]
+    #[serde(deserialize_with = "mbid_option")]
+    pub mbid: Option<Mbid<'dt>>,
     pub url: Url<'dt>,
-    #[serde(deserialize_with = "str_to_option")]
-    pub streamable: Option<u32>,
-    pub image: Vec<Image<'dt>>,
+    #[serde(deserialize_with = "str_to_option_bool")]
+    #[serde(serialize_with = "option_bool_to_str")]
+    pub streamable: Option<bool>,
+    pub image: Images<'dt>,
+}
+
+owned_t!(Artist1 { name, mbid, url, streamable, image });
+entity_t!(Artist1: HasName, HasMbid, HasImages);
+entity_t!(Artist1 => Artist);
+
+impl<'dt> From<Artist1<'dt>> for entity::Artist<'dt> {
+    fn from(artist: Artist1<'dt>) -> entity::Artist<'dt> {
+        entity::Artist {
+            mbid: artist.mbid,
+            url: artist.url.non_empty(),
+            image: artist.image,
+            streamable: artist.streamable,
+            ..entity::Artist::new(artist.name)
+        }
+    }
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct ArtistTaggings<'dt> {
     #[serde(borrow)]
     pub artist: Option<Vec<Artist1<'dt>>>,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(ArtistTaggings { artist });
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Track4<'dt> {
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
-    pub mbid: Option<&'dt str>,
+    #[serde(borrow)]
+    #[serde(default)]
+    #[serde(deserialize_with = "mbid_option")]
+    pub mbid: Option<Mbid<'dt>>,
     pub url: Url<'dt>,
     pub streamable: Streamable,
     pub artist: Id2<'dt>,
-    pub image: Vec<Image<'dt>>,
+    pub image: Images<'dt>,
 
-    // Service always returns FIXME in fields below
-    pub duration: Option<&'dt str>,
+    /// `None` when service returns a placeholder like FIXME instead of a duration
+    #[serde(default)]
+    #[serde(deserialize_with = "secs_to_duration")]
+    #[serde(serialize_with = "duration_to_secs")]
+    pub duration: Option<Duration>,
+}
+
+owned_t!(Track4 { name, mbid, url, streamable, artist, image, duration });
+entity_t!(Track4: HasName, HasMbid, HasImages);
+entity_t!(Track4 => Track);
+
+impl<'dt> From<Track4<'dt>> for entity::Track<'dt> {
+    fn from(track: Track4<'dt>) -> entity::Track<'dt> {
+        entity::Track {
+            mbid: track.mbid,
+            url: track.url.non_empty(),
+            artist: Some(track.artist.into()),
+            image: track.image,
+            streamable: Some(track.streamable.streamable),
+            duration: track.duration,
+            ..entity::Track::new(track.name)
+        }
+    }
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct TrackTaggings<'dt> {
     #[serde(borrow)]
     pub track: Option<Vec<Track4<'dt>>>,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(TrackTaggings { track });
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Album1<'dt> {
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
-    pub mbid: Option<&'dt str>,
+    #[serde(borrow)]
+    #[serde(default)]
+    #[serde(deserialize_with = "mbid_option")]
+    pub mbid: Option<Mbid<'dt>>,
     pub url: Url<'dt>,
     pub artist: Id2<'dt>,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(Album1 { name, mbid, url, artist });
+entity_t!(Album1: HasName, HasMbid);
+entity_t!(Album1 => Album);
+
+impl<'dt> From<Album1<'dt>> for entity::Album<'dt> {
+    fn from(album: Album1<'dt>) -> entity::Album<'dt> {
+        entity::Album {
+            mbid: album.mbid,
+            url: album.url.non_empty(),
+            artist: Some(album.artist.into()),
+            ..entity::Album::new(album.name)
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct AlbumTaggings<'dt> {
     #[serde(borrow)]
     pub album: Option<Vec<Album1<'dt>>>,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(AlbumTaggings { album });
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct GetTaggings<'dt> {
     #[serde(borrow)]
     pub artists: Option<ArtistTaggings<'dt>>,
@@ -529,8 +786,12 @@ pub struct GetTaggings<'dt> {
     pub tracks: Option<TrackTaggings<'dt>>,
     #[serde(borrow)]
     pub albums: Option<AlbumTaggings<'dt>>,
+    #[serde(rename = "@attr")]
+    pub page_info: Option<PageInfo<'dt>>,
 }
 
+owned_t!(GetTaggings { artists, tracks, albums, page_info });
+
 lastfm_t!(
     taggings,
     GetTaggings,
@@ -540,312 +801,528 @@ lastfm_t!(
     [
         user: &'rq str,
         tag: &'rq str,
-        taggingtype: TaggingType,
-        limit: Option<u32>,
-        page: Option<u32>
+        taggingtype: TaggingType
+    ],
+    [
+        limit: u32,
+        page: u32
     ]
 );
 
 // ----------------------------------------------------------------
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct NowPlaying {
     #[serde(deserialize_with = "str_to_val")]
+    #[serde(serialize_with = "val_to_str")]
     pub nowplaying: bool,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(NowPlaying);
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Track5<'dt> {
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
-    pub mbid: Option<&'dt str>,
+    #[serde(borrow)]
+    #[serde(default)]
+    #[serde(deserialize_with = "mbid_option")]
+    pub mbid: Option<Mbid<'dt>>,
     pub url: Url<'dt>,
-    #[serde(deserialize_with = "str_to_option")]
-    pub streamable: Option<u32>,
-    #[serde(deserialize_with = "str_to_val")]
-    pub loved: u32,
+    #[serde(deserialize_with = "str_to_option_bool")]
+    #[serde(serialize_with = "option_bool_to_str")]
+    pub streamable: Option<bool>,
+    #[serde(deserialize_with = "str_to_bool")]
+    #[serde(serialize_with = "bool_to_str")]
+    pub loved: bool,
     pub artist: Id2<'dt>,
     pub album: Id1<'dt>,
-    pub image: Vec<Image<'dt>>,
-    pub date: Option<Date1<'dt>>,
+    pub image: Images<'dt>,
+    pub date: Option<Date<'dt>>,
     #[serde(rename = "@attr")]
     pub now: Option<NowPlaying>,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(Track5 { name, mbid, url, streamable, loved, artist, album, image, date, now });
+entity_t!(Track5: HasName, HasMbid, HasImages);
+entity_t!(Track5 => Track);
+
+impl<'dt> From<Track5<'dt>> for entity::Track<'dt> {
+    fn from(track: Track5<'dt>) -> entity::Track<'dt> {
+        let artist = Some(track.artist.into());
+        entity::Track {
+            mbid: track.mbid,
+            url: track.url.non_empty(),
+            album: track_album(track.album.into(), &artist),
+            artist,
+            image: track.image,
+            streamable: track.streamable,
+            ..entity::Track::new(track.name)
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct GetRecentTracks<'dt> {
     #[serde(borrow)]
     pub track: Option<Vec<Track5<'dt>>>,
+    #[serde(rename = "@attr")]
+    pub page_info: Option<PageInfo<'dt>>,
 }
 
+owned_t!(GetRecentTracks { track, page_info });
+
 lastfm_t!(
     recenttracks,
     GetRecentTracks,
     _RecentTracks,
     Params,
     GetRecentTracks,
+    [user: &'rq str],
     [
-        user: &'rq str,
-        extended: Option<bool>,
-        from: Option<UnixTimestamp>,
-        to: Option<UnixTimestamp>,
-        limit: Option<u32>,
-        page: Option<u32>
+        extended: bool,
+        from: Timestamp,
+        to: Timestamp,
+        limit: u32,
+        page: u32
     ]
 );
 
+paged_t!(GetRecentTracks, track: Track5);
+
 // ----------------------------------------------------------------
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Album2<'dt> {
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
-    pub mbid: Option<&'dt str>,
+    #[serde(borrow)]
+    #[serde(default)]
+    #[serde(deserialize_with = "mbid_option")]
+    pub mbid: Option<Mbid<'dt>>,
     pub url: Url<'dt>,
     #[serde(deserialize_with = "str_to_val")]
+    #[serde(serialize_with = "val_to_str")]
     pub playcount: u32,
     pub artist: Id2<'dt>,
-    pub image: Vec<Image<'dt>>,
+    pub image: Images<'dt>,
     #[serde(rename = "@attr")]
     pub rank: Rank,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(Album2 { name, mbid, url, playcount, artist, image, rank });
+entity_t!(Album2: HasName, HasMbid, HasImages);
+entity_t!(Album2 => Album);
+
+impl<'dt> From<Album2<'dt>> for entity::Album<'dt> {
+    fn from(album: Album2<'dt>) -> entity::Album<'dt> {
+        entity::Album {
+            mbid: album.mbid,
+            url: album.url.non_empty(),
+            artist: Some(album.artist.into()),
+            image: album.image,
+            playcount: Some(album.playcount),
+            ..entity::Album::new(album.name)
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct GetTopAlbums<'dt> {
     #[serde(borrow)]
     pub album: Option<Vec<Album2<'dt>>>,
+    #[serde(rename = "@attr")]
+    pub page_info: Option<PageInfo<'dt>>,
 }
 
+owned_t!(GetTopAlbums { album, page_info });
+
 lastfm_t!(
     topalbums,
     GetTopAlbums,
     _GetTopAlbums,
     Params,
     GetTopAlbums,
+    [user: &'rq str],
     [
-        user: &'rq str,
-        period: Option<Period>,
-        limit: Option<u32>,
-        page: Option<u32>
+        period: Period,
+        limit: u32,
+        page: u32
     ]
 );
 
+paged_t!(GetTopAlbums, album: Album2);
+
 // ----------------------------------------------------------------
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Artist2<'dt> {
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
-    pub mbid: Option<&'dt str>,
+    #[serde(borrow)]
+    #[serde(default)]
+    #[serde(deserialize_with = "mbid_option")]
+    pub mbid: Option<Mbid<'dt>>,
     pub url: Url<'dt>,
     #[serde(deserialize_with = "str_to_val")]
+    #[serde(serialize_with = "val_to_str")]
     pub playcount: u32,
-    #[serde(deserialize_with = "str_to_option")]
-    pub streamable: Option<u32>,
-    pub image: Vec<Image<'dt>>,
+    #[serde(deserialize_with = "str_to_option_bool")]
+    #[serde(serialize_with = "option_bool_to_str")]
+    pub streamable: Option<bool>,
+    pub image: Images<'dt>,
     #[serde(rename = "@attr")]
     pub rank: Rank,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(Artist2 { name, mbid, url, playcount, streamable, image, rank });
+entity_t!(Artist2: HasName, HasMbid, HasImages);
+entity_t!(Artist2 => Artist);
+
+impl<'dt> From<Artist2<'dt>> for entity::Artist<'dt> {
+    fn from(artist: Artist2<'dt>) -> entity::Artist<'dt> {
+        entity::Artist {
+            mbid: artist.mbid,
+            url: artist.url.non_empty(),
+            image: artist.image,
+            playcount: Some(artist.playcount),
+            streamable: artist.streamable,
+            ..entity::Artist::new(artist.name)
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct GetTopArtists<'dt> {
     #[serde(borrow)]
     pub artist: Option<Vec<Artist2<'dt>>>,
+    #[serde(rename = "@attr")]
+    pub page_info: Option<PageInfo<'dt>>,
 }
 
+owned_t!(GetTopArtists { artist, page_info });
+
 lastfm_t!(
     topartists,
     GetTopArtists,
     _GetTopArtists,
     Params,
     GetTopArtists,
+    [user: &'rq str],
     [
-        user: &'rq str,
-        period: Option<Period>,
-        limit: Option<u32>,
-        page: Option<u32>
+        period: Period,
+        limit: u32,
+        page: u32
     ]
 );
 
+paged_t!(GetTopArtists, artist: Artist2);
+
 // ----------------------------------------------------------------
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Tag<'dt> {
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
     #[serde(deserialize_with = "str_to_val")]
+    #[serde(serialize_with = "val_to_str")]
     pub count: u32,
     pub url: Url<'dt>,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(Tag { name, count, url });
+entity_t!(Tag: HasName);
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct GetTopTags<'dt> {
     #[serde(borrow)]
     pub tag: Option<Vec<Tag<'dt>>>,
 }
 
+owned_t!(GetTopTags { tag });
+
 lastfm_t!(
     toptags,
     GetTopTags,
     _GetTopTags,
     Params,
     GetTopTags,
-    [user: &'rq str, limit: Option<u32>]
+    [user: &'rq str],
+    [limit: u32]
 );
 
 // ----------------------------------------------------------------
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Track6<'dt> {
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
-    pub mbid: Option<&'dt str>,
+    #[serde(borrow)]
+    #[serde(default)]
+    #[serde(deserialize_with = "mbid_option")]
+    pub mbid: Option<Mbid<'dt>>,
+    #[serde(borrow)]
     pub url: Cow<'dt, str>,
     #[serde(deserialize_with = "str_to_val")]
+    #[serde(serialize_with = "val_to_str")]
     pub playcount: u32,
-    #[serde(deserialize_with = "str_to_val")]
-    pub duration: u32,
+    #[serde(default)]
+    #[serde(deserialize_with = "secs_to_duration")]
+    #[serde(serialize_with = "duration_to_secs")]
+    pub duration: Option<Duration>,
     pub streamable: Streamable,
     pub artist: Id2<'dt>,
-    pub image: Vec<Image<'dt>>,
+    pub image: Images<'dt>,
     #[serde(rename = "@attr")]
     pub rank: Rank,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(Track6 { name, mbid, url, playcount, duration, streamable, artist, image, rank });
+entity_t!(Track6: HasName, HasMbid, HasImages);
+entity_t!(Track6 => Track);
+
+impl<'dt> From<Track6<'dt>> for entity::Track<'dt> {
+    fn from(track: Track6<'dt>) -> entity::Track<'dt> {
+        entity::Track {
+            mbid: track.mbid,
+            url: Url::from(track.url).non_empty(),
+            artist: Some(track.artist.into()),
+            image: track.image,
+            playcount: Some(track.playcount),
+            streamable: Some(track.streamable.streamable),
+            duration: track.duration,
+            ..entity::Track::new(track.name)
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct GetTopTracks<'dt> {
     #[serde(borrow)]
     pub track: Option<Vec<Track6<'dt>>>,
+    #[serde(rename = "@attr")]
+    pub page_info: Option<PageInfo<'dt>>,
 }
 
+owned_t!(GetTopTracks { track, page_info });
+
 lastfm_t!(
     toptracks,
     GetTopTracks,
     _GetTopTracks,
     Params,
     GetTopTracks,
+    [user: &'rq str],
     [
-        user: &'rq str,
-        period: Option<Period>,
-        limit: Option<u32>,
-        page: Option<u32>
+        period: Period,
+        limit: u32,
+        page: u32
     ]
 );
 
+paged_t!(GetTopTracks, track: Track6);
+
 // ----------------------------------------------------------------
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Album3<'dt> {
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
-    pub mbid: Option<&'dt str>,
+    #[serde(borrow)]
+    #[serde(default)]
+    #[serde(deserialize_with = "mbid_option")]
+    pub mbid: Option<Mbid<'dt>>,
     pub url: Url<'dt>,
     #[serde(deserialize_with = "str_to_option")]
+    #[serde(serialize_with = "option_to_str")]
     pub playcount: Option<u32>,
     pub artist: Id1<'dt>,
     #[serde(rename = "@attr")]
     pub rank: Rank,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(Album3 { name, mbid, url, playcount, artist, rank });
+entity_t!(Album3: HasName, HasMbid);
+entity_t!(Album3 => Album);
+
+impl<'dt> From<Album3<'dt>> for entity::Album<'dt> {
+    fn from(album: Album3<'dt>) -> entity::Album<'dt> {
+        entity::Album {
+            mbid: album.mbid,
+            url: album.url.non_empty(),
+            artist: Some(album.artist.into()),
+            playcount: album.playcount,
+            ..entity::Album::new(album.name)
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct GetWeeklyAlbumChart<'dt> {
     #[serde(borrow)]
     pub album: Option<Vec<Album3<'dt>>>,
 }
 
+owned_t!(GetWeeklyAlbumChart { album });
+
 lastfm_t!(
     weeklyalbumchart,
     GetWeeklyAlbumChart,
     _GetWeeklyAlbumChart,
     Params,
     GetWeeklyAlbumChart,
+    [user: &'rq str],
     [
-        user: &'rq str,
-        from: Option<ChartDate>,
-        to: Option<ChartDate>
+        from: Timestamp,
+        to: Timestamp
     ]
 );
 
+list_t!(GetWeeklyAlbumChart, album: Album3);
+
 // ----------------------------------------------------------------
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Artist3<'dt> {
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
-    pub mbid: Option<&'dt str>,
+    #[serde(borrow)]
+    #[serde(default)]
+    #[serde(deserialize_with = "mbid_option")]
+    pub mbid: Option<Mbid<'dt>>,
     pub url: Url<'dt>,
     #[serde(deserialize_with = "str_to_val")]
+    #[serde(serialize_with = "val_to_str")]
     pub playcount: u32,
     #[serde(rename = "@attr")]
     pub rank: Rank,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(Artist3 { name, mbid, url, playcount, rank });
+entity_t!(Artist3: HasName, HasMbid);
+entity_t!(Artist3 => Artist);
+
+impl<'dt> From<Artist3<'dt>> for entity::Artist<'dt> {
+    fn from(artist: Artist3<'dt>) -> entity::Artist<'dt> {
+        entity::Artist {
+            mbid: artist.mbid,
+            url: artist.url.non_empty(),
+            playcount: Some(artist.playcount),
+            ..entity::Artist::new(artist.name)
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct GetWeeklyArtistChart<'dt> {
     #[serde(borrow)]
     pub artist: Option<Vec<Artist3<'dt>>>,
 }
 
+owned_t!(GetWeeklyArtistChart { artist });
+
 lastfm_t!(
     weeklyartistchart,
     GetWeeklyArtistChart,
     _GetWeeklyArtistChart,
     Params,
     GetWeeklyArtistChart,
+    [user: &'rq str],
     [
-        user: &'rq str,
-        from: Option<ChartDate>,
-        to: Option<ChartDate>
+        from: Timestamp,
+        to: Timestamp
     ]
 );
 
+list_t!(GetWeeklyArtistChart, artist: Artist3);
+
 // ----------------------------------------------------------------
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Track7<'dt> {
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
-    pub mbid: Option<&'dt str>,
+    #[serde(borrow)]
+    #[serde(default)]
+    #[serde(deserialize_with = "mbid_option")]
+    pub mbid: Option<Mbid<'dt>>,
     pub url: Url<'dt>,
     #[serde(deserialize_with = "str_to_val")]
+    #[serde(serialize_with = "val_to_str")]
     pub playcount: u32,
     pub artist: Id1<'dt>,
     #[serde(rename = "@attr")]
     pub rank: Rank,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(Track7 { name, mbid, url, playcount, artist, rank });
+entity_t!(Track7: HasName, HasMbid);
+entity_t!(Track7 => Track);
+
+impl<'dt> From<Track7<'dt>> for entity::Track<'dt> {
+    fn from(track: Track7<'dt>) -> entity::Track<'dt> {
+        entity::Track {
+            mbid: track.mbid,
+            url: track.url.non_empty(),
+            artist: Some(track.artist.into()),
+            playcount: Some(track.playcount),
+            ..entity::Track::new(track.name)
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct GetWeeklyTrackChart<'dt> {
     #[serde(borrow)]
     pub track: Option<Vec<Track7<'dt>>>,
 }
 
+owned_t!(GetWeeklyTrackChart { track });
+
 lastfm_t!(
     weeklytrackchart,
     GetWeeklyTrackChart,
     _GetWeeklyTrackChart,
     Params,
     GetWeeklyTrackChart,
+    [user: &'rq str],
     [
-        user: &'rq str,
-        from: Option<ChartDate>,
-        to: Option<ChartDate>
+        from: Timestamp,
+        to: Timestamp
     ]
 );
 
+list_t!(GetWeeklyTrackChart, track: Track7);
+
 // ----------------------------------------------------------------
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Chart<'dt> {
     #[serde(rename = "#text")]
+    #[serde(borrow)]
     pub name: Cow<'dt, str>,
+    #[serde(borrow)]
     pub from: Cow<'dt, str>,
+    #[serde(borrow)]
     pub to: Cow<'dt, str>,
 }
 
-#[derive(Deserialize, Debug)]
+owned_t!(Chart { name, from, to });
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct GetWeeklyChartList<'dt> {
     #[serde(borrow)]
     pub chart: Option<Vec<Chart<'dt>>>,
 }
 
+owned_t!(GetWeeklyChartList { chart });
+
 lastfm_t!(
     weeklychartlist,
     GetWeeklyChartList,
     _GetWeeklyChartList,
     Params,
     GetWeeklyChartList,
-    [user: &'rq str]
+    [user: &'rq str],
+    []
 );
@@ -0,0 +1,272 @@
+use std::borrow::Cow;
+
+use owned::IntoOwned;
+#[cfg(feature = "chrono")]
+use time::{self, DateTime, Utc};
+use super::common::{Url, cow_option};
+
+// ----------------------------------------------------------------
+
+/// Attribution that service appends to summaries: `<a href="...">Read more on Last.fm</a>`
+static READ_MORE: &str = "Read more on Last.fm";
+/// License note that service appends to full texts
+static LICENSE: &str = "User-contributed text is available under the Creative Commons By-SA License";
+
+// ----------------------------------------------------------------
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct Link<'dt> {
+    #[serde(rename = "#text")]
+    #[serde(borrow)]
+    pub text: Cow<'dt, str>,
+    #[serde(borrow)]
+    pub rel: Cow<'dt, str>,
+    pub href: Url<'dt>,
+}
+
+owned_t!(Link { text, rel, href });
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct Links<'dt> {
+    #[serde(borrow)]
+    pub link: Option<Link<'dt>>,
+}
+
+owned_t!(Links { link });
+
+/// Artist biography, album, track or tag wiki.
+/// `summary` and `content` are kept as service returns them: html with anchors and attribution footer,
+/// use `*_text()` and `*_markdown()` methods to render them.
+#[derive(Deserialize, Serialize, Debug)]
+pub struct Wiki<'dt> {
+    /// Textual date like "13 Dec 2017, 09:17", see `published_utc()` for typed one
+    #[serde(borrow)]
+    #[serde(default)]
+    #[serde(deserialize_with = "cow_option")]
+    pub published: Option<Cow<'dt, str>>,
+    #[serde(borrow)]
+    #[serde(default)]
+    pub summary: Cow<'dt, str>,
+    #[serde(borrow)]
+    #[serde(default)]
+    #[serde(deserialize_with = "cow_option")]
+    pub content: Option<Cow<'dt, str>>,
+    #[serde(borrow)]
+    #[serde(default)]
+    pub links: Option<Links<'dt>>,
+}
+
+owned_t!(Wiki { published, summary, content, links });
+
+impl<'dt> Wiki<'dt> {
+    pub fn summary_text(&self) -> String {
+        to_text(&self.summary)
+    }
+
+    pub fn summary_markdown(&self) -> String {
+        to_markdown(&self.summary)
+    }
+
+    /// Full text, or summary if service didn't return one
+    pub fn content_text(&self) -> String {
+        to_text(self.full())
+    }
+
+    /// Full text, or summary if service didn't return one
+    pub fn content_markdown(&self) -> String {
+        to_markdown(self.full())
+    }
+
+    /// Anchors of the full text (or summary), attribution footer excluded
+    pub fn anchors(&self) -> Vec<Anchor> {
+        anchors(self.full())
+    }
+
+    /// Publication date, `None` if it's missing or can't be parsed
+    #[cfg(feature = "chrono")]
+    pub fn published_utc(&self) -> Option<DateTime<Utc>> {
+        self.published.as_ref().and_then(|published| time::parse_text(published))
+    }
+
+    fn full(&self) -> &str {
+        match self.content {
+            Some(ref content) if !content.trim().is_empty() => content,
+            _ => &self.summary,
+        }
+    }
+}
+
+// ----------------------------------------------------------------
+
+/// Link found in wiki text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Anchor {
+    pub text: String,
+    pub href: String,
+}
+
+/// Cuts "Read more on Last.fm" link and license note off the end of wiki text
+pub fn strip_footer(html: &str) -> &str {
+    let mut html = html;
+    if let Some(pos) = html.rfind(LICENSE) {
+        html = &html[..pos];
+    }
+    if let Some(pos) = html.rfind(READ_MORE) {
+        html = &html[..html[..pos].rfind("<a").unwrap_or(pos)];
+    }
+    html.trim_end()
+}
+
+/// Renders wiki text as plain text: tags are dropped, entities decoded, footer stripped
+pub fn to_text(html: &str) -> String {
+    render(html, plain, |text, _| text.to_owned())
+}
+
+/// Renders wiki text as Markdown: anchors become `[text](href)`, footer is stripped,
+/// characters that Markdown would take for formatting are escaped
+pub fn to_markdown(html: &str) -> String {
+    render(html, escape_markdown, |text, href| {
+        if text.is_empty() {
+            format!("<{}>", href)
+        } else {
+            format!("[{}]({})", text, href)
+        }
+    })
+}
+
+/// Lists anchors of wiki text in order of appearance, footer excluded
+pub fn anchors(html: &str) -> Vec<Anchor> {
+    let mut anchors = Vec::new();
+    render(html, plain, |text, href| {
+        anchors.push(Anchor { text: text.to_owned(), href: href.to_owned() });
+        String::new()
+    });
+    anchors
+}
+
+/// Walks through the html, replacing each `<a href="...">...</a>` with what `anchor` returns
+/// for its text and decoded href. Text is decoded and passed through `escape`, anchor text included.
+/// Line breaks and paragraphs become new lines, other tags are dropped.
+fn render<F>(html: &str, escape: fn(&str) -> Cow<str>, mut anchor: F) -> String
+where
+    F: FnMut(&str, &str) -> String,
+{
+    let mut out = String::new();
+    let mut open: Option<(String, usize)> = None;
+    let mut rest = strip_footer(html);
+
+    while let Some(start) = rest.find('<') {
+        let end = match rest[start..].find('>') {
+            Some(end) => start + end,
+            None => break,
+        };
+        out.push_str(&escape(&decode(&rest[..start])));
+        let tag = &rest[start + 1..end];
+        rest = &rest[end + 1..];
+
+        let closing = tag.starts_with('/');
+        let name = tag.trim_start_matches('/')
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .next()
+            .unwrap_or("")
+            .to_ascii_lowercase();
+
+        match (name.as_str(), closing) {
+            ("a", false) => {
+                let href = attribute(tag, "href").map(decode).unwrap_or_default();
+                open = Some((href.into_owned(), out.len()));
+            }
+            ("a", true) => {
+                if let Some((href, at)) = open.take() {
+                    let text = out.split_off(at);
+                    out.push_str(&anchor(text.trim(), &href));
+                }
+            }
+            ("br", _) => out.push('\n'),
+            ("p", true) => out.push_str("\n\n"),
+            _ => {}
+        }
+    }
+    out.push_str(&escape(&decode(rest)));
+
+    out.trim().to_owned()
+}
+
+fn plain(text: &str) -> Cow<'_, str> {
+    Cow::Borrowed(text)
+}
+
+fn escape_markdown(text: &str) -> Cow<'_, str> {
+    let special = |c: char| matches!(c, '\\' | '[' | ']' | '*' | '_' | '`');
+    if !text.contains(special) {
+        return Cow::Borrowed(text);
+    }
+
+    let mut out = String::with_capacity(text.len() + 8);
+    for c in text.chars() {
+        if special(c) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    Cow::Owned(out)
+}
+
+/// Value of a quoted tag attribute, like `href="..."`.
+/// Attribute name has to follow whitespace, so that `data-href="..."` doesn't pass for `href`.
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let key = format!("{}=", name);
+    let (pos, _) = tag.match_indices(&key)
+        .find(|&(pos, _)| tag[..pos].ends_with(char::is_whitespace))?;
+    let value = &tag[pos + key.len()..];
+    let quote = value.chars().next().filter(|&c| c == '"' || c == '\'')?;
+    let value = &value[1..];
+    value.find(quote).map(|end| &value[..end])
+}
+
+/// Decodes html entities: named ones that service uses and numeric ones
+fn decode(text: &str) -> Cow<'_, str> {
+    if !text.contains('&') {
+        return Cow::Borrowed(text);
+    }
+
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let decoded = rest.find(';')
+            .filter(|&end| end <= 10)
+            .and_then(|end| entity(&rest[1..end]).map(|c| (c, end)));
+        match decoded {
+            Some((c, end)) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+
+    Cow::Owned(out)
+}
+
+fn entity(name: &str) -> Option<char> {
+    match name {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        "nbsp" => Some(' '),
+        _ if name.starts_with("#x") || name.starts_with("#X") => {
+            u32::from_str_radix(&name[2..], 16).ok().and_then(char::from_u32)
+        }
+        _ if name.starts_with('#') => name[1..].parse().ok().and_then(char::from_u32),
+        _ => None,
+    }
+}
@@ -0,0 +1,105 @@
+pub use chrono::{DateTime, Utc};
+
+use chrono::{NaiveDateTime, TimeZone};
+
+use structs::common::{UnixTimestamp, Timestamp};
+use request::IntoArg;
+use structs::{tag, track, user, wiki};
+
+// ----------------------------------------------------------------
+
+/// Formats of textual dates, like "13 Dec 2017, 09:17" in wikis and recent tracks
+static TEXT_FORMATS: &[&str] = &["%d %b %Y, %H:%M", "%d %b %Y, %H:%M:%S", "%d %b %Y %H:%M"];
+
+/// Converts unix timestamp into UTC instant, `None` if it is out of range
+pub fn from_unix(timestamp: UnixTimestamp) -> Option<DateTime<Utc>> {
+    Utc.timestamp_opt(timestamp, 0).single()
+}
+
+/// Converts UTC instant into unix timestamp, dropping fractions of a second
+pub fn to_unix(instant: &DateTime<Utc>) -> UnixTimestamp {
+    instant.timestamp()
+}
+
+/// Parses a date the way Lastfm writes it: unix timestamp, "13 Dec 2017, 09:17" (always UTC)
+/// or RFC 2822 / RFC 3339 string with explicit offset. `None` for blank or unknown strings.
+pub fn parse_text(text: &str) -> Option<DateTime<Utc>> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    if let Ok(timestamp) = text.parse() {
+        return from_unix(timestamp);
+    }
+
+    TEXT_FORMATS.iter()
+        .filter_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+        .map(|naive| Utc.from_utc_datetime(&naive))
+        .chain(DateTime::parse_from_rfc2822(text).ok().map(|dt| dt.with_timezone(&Utc)))
+        .chain(DateTime::parse_from_rfc3339(text).ok().map(|dt| dt.with_timezone(&Utc)))
+        .next()
+}
+
+// ----------------------------------------------------------------
+
+/// Data types that point at a single moment in time
+pub trait Timestamped {
+    /// `None` when the service left the date blank or wrote it in unknown format
+    fn utc(&self) -> Option<DateTime<Utc>>;
+}
+
+impl<'dt> Timestamped for user::Date<'dt> {
+    fn utc(&self) -> Option<DateTime<Utc>> {
+        self.uts.and_then(from_unix).or_else(|| parse_text(&self.text))
+    }
+}
+
+impl<'dt> Timestamped for wiki::Wiki<'dt> {
+    fn utc(&self) -> Option<DateTime<Utc>> {
+        self.published_utc()
+    }
+}
+
+impl<'dt> Timestamped for track::Report<'dt> {
+    fn utc(&self) -> Option<DateTime<Utc>> {
+        from_unix(self.timestamp)
+    }
+}
+
+impl Timestamped for Timestamp {
+    fn utc(&self) -> Option<DateTime<Utc>> {
+        from_unix(self.0)
+    }
+}
+
+impl From<DateTime<Utc>> for Timestamp {
+    fn from(instant: DateTime<Utc>) -> Timestamp {
+        Timestamp(to_unix(&instant))
+    }
+}
+
+impl IntoArg<Timestamp> for DateTime<Utc> {
+    fn into_arg(self) -> Timestamp {
+        self.into()
+    }
+}
+
+// ----------------------------------------------------------------
+
+/// Data types that describe a time range, like weekly chart boundaries
+pub trait TimeRange {
+    /// Start and end of the range, `None` if any of them can't be parsed
+    fn utc_range(&self) -> Option<(DateTime<Utc>, DateTime<Utc>)>;
+}
+
+impl<'dt> TimeRange for user::Chart<'dt> {
+    fn utc_range(&self) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+        Some((parse_text(&self.from)?, parse_text(&self.to)?))
+    }
+}
+
+impl TimeRange for tag::WeeklyChartItem {
+    fn utc_range(&self) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+        Some((from_unix(self.from.into())?, from_unix(self.to.into())?))
+    }
+}
@@ -0,0 +1,241 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+#[cfg(feature = "async")]
+use std::future::{self, Future};
+#[cfg(feature = "async")]
+use std::pin::Pin;
+#[cfg(feature = "async")]
+use std::time::Duration;
+
+use url::Url;
+
+use error::{Error, Result};
+
+// ----------------------------------------------------------------
+
+/// HTTP method used to call an API method
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HttpMethod {
+    Get,
+    Post,
+}
+
+impl HttpMethod {
+    pub fn to_str(&self) -> &str {
+        match *self {
+            HttpMethod::Get => "GET",
+            HttpMethod::Post => "POST",
+        }
+    }
+}
+
+/// Prepared API request, ready to be sent over the wire.
+/// For POST requests method parameters are form-encoded into the body
+/// and url has no query.
+#[derive(Debug, Clone)]
+pub struct HttpRequest {
+    pub method: HttpMethod,
+    pub url: Url,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<Vec<u8>>,
+}
+
+impl HttpRequest {
+    /// Constructs GET request with no headers and no body
+    pub fn get(url: Url) -> HttpRequest {
+        HttpRequest {
+            method: HttpMethod::Get,
+            url,
+            headers: Vec::new(),
+            body: None,
+        }
+    }
+
+    /// Returns value of the first header with given name (case insensitive)
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// Raw HTTP response: status code and body bytes
+#[derive(Debug, Clone)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    /// Constructs new response from status code and body
+    pub fn new(status: u16, body: Vec<u8>) -> HttpResponse {
+        HttpResponse { status, body }
+    }
+
+    /// Indicates whether status code is 2xx
+    pub fn is_success(&self) -> bool {
+        self.status >= 200 && self.status < 300
+    }
+}
+
+// ----------------------------------------------------------------
+
+/// Sends prepared requests and returns raw responses.
+/// Implement this trait to plug any HTTP client into `client::Client`.
+/// Note that non-2xx statuses are not errors on this level:
+/// Lastfm reports API errors in response body, so it has to be returned as is.
+pub trait Transport {
+    fn send(&self, request: &HttpRequest) -> Result<HttpResponse>;
+}
+
+impl<T: Transport + ?Sized> Transport for &T {
+    fn send(&self, request: &HttpRequest) -> Result<HttpResponse> {
+        (**self).send(request)
+    }
+}
+
+/// Future returned by `AsyncTransport`
+#[cfg(feature = "async")]
+pub type ResponseFuture<'t> = Pin<Box<dyn Future<Output = Result<HttpResponse>> + Send + 't>>;
+
+/// Future returned by `AsyncTransport::delay()`
+#[cfg(feature = "async")]
+pub type DelayFuture<'t> = Pin<Box<dyn Future<Output = ()> + Send + 't>>;
+
+/// Asynchronous counterpart of `Transport`.
+/// Used by `paginate::PaginatedStream`.
+/// Since there's no runtime-agnostic timer, transport provides one for rate limiting.
+#[cfg(feature = "async")]
+pub trait AsyncTransport {
+    fn send_async(&self, request: HttpRequest) -> ResponseFuture<'_>;
+
+    /// Returns future that completes after given duration
+    fn delay(&self, duration: Duration) -> DelayFuture<'_>;
+}
+
+#[cfg(feature = "async")]
+impl<T: AsyncTransport + ?Sized> AsyncTransport for &T {
+    fn send_async(&self, request: HttpRequest) -> ResponseFuture<'_> {
+        (**self).send_async(request)
+    }
+
+    fn delay(&self, duration: Duration) -> DelayFuture<'_> {
+        (**self).delay(duration)
+    }
+}
+
+// ----------------------------------------------------------------
+
+/// Transport that never touches the network.
+/// Replies with previously queued responses (in FIFO order) and records every sent request.
+/// Meant to be used in tests.
+#[derive(Debug, Default)]
+pub struct InMemoryTransport {
+    responses: Mutex<VecDeque<HttpResponse>>,
+    requests: Mutex<Vec<HttpRequest>>,
+}
+
+impl InMemoryTransport {
+    pub fn new() -> InMemoryTransport {
+        Default::default()
+    }
+
+    /// Queues a response to be returned by one of the next `send()` calls
+    pub fn push_response(&self, status: u16, body: &str) {
+        self.responses.lock().unwrap().push_back(
+            HttpResponse::new(status, body.as_bytes().to_vec()),
+        );
+    }
+
+    /// Returns copies of all requests sent so far
+    pub fn requests(&self) -> Vec<HttpRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+impl Transport for InMemoryTransport {
+    fn send(&self, request: &HttpRequest) -> Result<HttpResponse> {
+        self.requests.lock().unwrap().push(request.clone());
+        self.responses.lock().unwrap().pop_front().ok_or_else(|| {
+            Error::Transport("No more queued responses in InMemoryTransport".into())
+        })
+    }
+}
+
+/// Responses are ready immediately, delays are skipped
+#[cfg(feature = "async")]
+impl AsyncTransport for InMemoryTransport {
+    fn send_async(&self, request: HttpRequest) -> ResponseFuture<'_> {
+        Box::pin(future::ready(self.send(&request)))
+    }
+
+    fn delay(&self, _duration: Duration) -> DelayFuture<'_> {
+        Box::pin(future::ready(()))
+    }
+}
+
+// ----------------------------------------------------------------
+
+#[cfg(feature = "blocking")]
+pub use self::blocking::BlockingTransport;
+
+#[cfg(feature = "blocking")]
+mod blocking {
+    use ureq::Agent;
+
+    use error::{Error, Result};
+    use super::{Transport, HttpMethod, HttpRequest, HttpResponse};
+
+    /// Blocking transport based on `ureq` crate
+    #[derive(Debug, Clone)]
+    pub struct BlockingTransport {
+        agent: Agent,
+    }
+
+    impl BlockingTransport {
+        pub fn new() -> BlockingTransport {
+            let config = Agent::config_builder()
+                .http_status_as_error(false)
+                .build();
+            BlockingTransport { agent: Agent::new_with_config(config) }
+        }
+    }
+
+    impl Default for BlockingTransport {
+        fn default() -> BlockingTransport {
+            BlockingTransport::new()
+        }
+    }
+
+    impl Transport for BlockingTransport {
+        fn send(&self, request: &HttpRequest) -> Result<HttpResponse> {
+            let url = request.url.as_str();
+            let response = match request.method {
+                HttpMethod::Get => {
+                    let mut rq = self.agent.get(url);
+                    for (name, value) in &request.headers {
+                        rq = rq.header(name.as_str(), value.as_str());
+                    }
+                    rq.call()
+                }
+                HttpMethod::Post => {
+                    let mut rq = self.agent.post(url);
+                    for (name, value) in &request.headers {
+                        rq = rq.header(name.as_str(), value.as_str());
+                    }
+                    rq.send(request.body.as_ref().map_or(&[][..], Vec::as_slice))
+                }
+            };
+            let mut response = response.map_err(|e| Error::Transport(Box::new(e)))?;
+
+            let status = response.status().as_u16();
+            let body = response
+                .body_mut()
+                .read_to_vec()
+                .map_err(|e| Error::Transport(Box::new(e)))?;
+
+            Ok(HttpResponse::new(status, body))
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use std::fmt::Display;
+
+use error::{Error, Result};
+
+// ----------------------------------------------------------------
+
+/// Maximum number of items per page service accepts for most methods
+pub const MAX_LIMIT: u32 = 1000;
+/// Maximum number of items per page for `user.getrecenttracks`
+pub const MAX_RECENT_TRACKS_LIMIT: u32 = 200;
+/// Maximum number of tags `*.addtags` methods accept at once
+pub const MAX_TAGS: usize = 10;
+/// Maximum number of tracks `track.scrobble` accepts at once
+pub const MAX_SCROBBLES: usize = 50;
+
+fn invalid<R: Into<String>>(name: &'static str, reason: R) -> Error {
+    Error::InvalidParameter { name, reason: reason.into() }
+}
+
+// ----------------------------------------------------------------
+
+/// Checks that required string argument is not empty
+pub fn required(name: &'static str, value: &str) -> Result<()> {
+    if value.trim().is_empty() {
+        return Err(invalid(name, "must not be empty"));
+    }
+    Ok(())
+}
+
+/// Checks that number of items per page is within 1..=max
+pub fn limit(value: Option<u32>, max: u32) -> Result<()> {
+    match value {
+        Some(0) => Err(invalid("limit", "must be positive")),
+        Some(limit) if limit > max => {
+            Err(invalid("limit", format!("must not exceed {}, got {}", max, limit)))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Checks that page number is positive (pages are numbered from 1)
+pub fn page(value: Option<u32>) -> Result<()> {
+    match value {
+        Some(0) => Err(invalid("page", "pages are numbered from 1")),
+        _ => Ok(()),
+    }
+}
+
+/// Checks comma-separated tag list: not empty and not longer than `MAX_TAGS`
+pub fn tags(value: &str) -> Result<()> {
+    required("tags", value)?;
+
+    let count = value.split(',').filter(|t| !t.trim().is_empty()).count();
+    if count > MAX_TAGS {
+        return Err(invalid(
+            "tags",
+            format!("at most {} tags are accepted, got {}", MAX_TAGS, count),
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that `from` does not come after `to` when both are set
+pub fn range<T: PartialOrd + Display>(from: Option<T>, to: Option<T>) -> Result<()> {
+    match (from, to) {
+        (Some(ref from), Some(ref to)) if from > to => {
+            Err(invalid("from", format!("{} comes after 'to' {}", from, to)))
+        }
+        _ => Ok(()),
+    }
+}
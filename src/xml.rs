@@ -0,0 +1,348 @@
+use std::borrow::Cow;
+use std::mem;
+use std::str;
+use std::result::Result as StdResult;
+use std::vec::IntoIter;
+
+use roxmltree::{Document, Node, StringStorage};
+use serde::de::{Deserializer, Visitor, MapAccess, SeqAccess, DeserializeSeed};
+use serde::de::Error as SerdeError;
+use serde::de::value::CowStrDeserializer;
+use serde_json::Error as JsonError;
+use serde_path_to_error as path_to_error;
+
+use lastfm_type::LastfmType;
+use error::{Error, DeserializeError, Result};
+use structs::api_error::ApiError;
+
+// ----------------------------------------------------------------
+
+/// Parses given data type from xml string slice.
+/// Response is expected to be wrapped with `<lfm status="...">` element:
+/// failed responses are turned into `Error::Api`.
+/// Strings are borrowed from the source, except for the ones containing entities.
+pub fn from_xml_str<'de, Lt: LastfmType<'de>>(xml: &'de str) -> Result<Lt> {
+    let document = Document::parse(xml).map_err(Error::Xml)?;
+    let value = document_value(&document)?;
+    path_to_error::deserialize::<_, Lt::Outer>(value)
+        .map(Into::into)
+        .map_err(|e| Error::Deserialize(e.into()))
+}
+
+/// Parses given data type from xml byte slice (see `from_xml_str()`)
+pub fn from_xml_slice<'de, Lt: LastfmType<'de>>(xml: &'de [u8]) -> Result<Lt> {
+    from_xml_str(utf8(xml)?)
+}
+
+/// Parses given data type from xml string or byte slice into an owned `'static` object
+/// (see `from_json_owned()`)
+pub fn from_xml_owned<J, Lt>(xml: J) -> Result<Lt>
+where
+    J: AsRef<[u8]>,
+    Lt: LastfmType<'static>,
+{
+    let document = Document::parse(utf8(xml.as_ref())?).map_err(Error::Xml)?;
+    let value = document_value(&document)?.into_owned();
+    path_to_error::deserialize::<_, <Lt as LastfmType<'static>>::Outer>(value)
+        .map(Into::into)
+        .map_err(|e| Error::Deserialize(e.into()))
+}
+
+/// Extracts API error from failed response, if it is one.
+/// Only the root element and its `<error>` child are looked at.
+pub(crate) fn parse_api_error(xml: &[u8]) -> Option<ApiError> {
+    let xml = str::from_utf8(xml).ok()?;
+    let document = Document::parse(xml).ok()?;
+    api_error(document.root_element())?.ok()
+}
+
+fn utf8(xml: &[u8]) -> Result<&str> {
+    str::from_utf8(xml).map_err(|e| Error::Deserialize(JsonError::custom(e).into()))
+}
+
+/// Converts document contents into a value, unwrapping `<lfm>` root element
+fn document_value<'de>(document: &Document<'de>) -> Result<Value<'de>> {
+    let root = document.root_element();
+    if root.tag_name().name() != "lfm" {
+        let name = element_name(root);
+        return Ok(Value::Map(vec![(name, element_value(root))]));
+    }
+
+    match api_error(root) {
+        Some(Ok(err)) => Err(Error::Api(err)),
+        Some(Err(err)) => Err(err),
+        None => Ok(children_value(root)),
+    }
+}
+
+/// Reads API error from `<lfm status="failed">` root element: `code` attribute and text of its `<error>` child.
+/// `None` if the response didn't fail.
+fn api_error(root: Node) -> Option<Result<ApiError>> {
+    if !root.has_tag_name("lfm") || root.attribute("status") != Some("failed") {
+        return None;
+    }
+
+    let error = match root.children().find(|node| node.has_tag_name("error")) {
+        Some(error) => error,
+        None => return Some(Err(Error::Deserialize(JsonError::custom("failed response without error").into()))),
+    };
+    let fields = vec![
+        (Cow::Borrowed("error"), Value::Text(Cow::Borrowed(error.attribute("code").unwrap_or("0")))),
+        (Cow::Borrowed("message"), Value::Text(text(error))),
+    ];
+    Some(
+        path_to_error::deserialize(Value::Map(fields))
+            .map_err(|e| Error::Deserialize(DeserializeError::from(e).with_api_error())),
+    )
+}
+
+// ----------------------------------------------------------------
+
+/// Intermediate representation of xml element, shaped the same way Lastfm converts xml to json:
+/// * element that has child elements becomes a map of them, repeated ones are grouped in sequences,
+///   attributes are put to `@attr` map;
+/// * element without child elements becomes a string, or, if it has attributes, a map of them
+///   along with `#text` key for the contents.
+#[derive(Debug)]
+enum Value<'de> {
+    Text(Cow<'de, str>),
+    Map(Vec<(Cow<'de, str>, Value<'de>)>),
+    Seq(Vec<Value<'de>>),
+}
+
+impl<'de> Value<'de> {
+    fn into_owned(self) -> Value<'static> {
+        match self {
+            Value::Text(text) => Value::Text(Cow::Owned(text.into_owned())),
+            Value::Map(entries) => Value::Map(
+                entries
+                    .into_iter()
+                    .map(|(k, v)| (Cow::Owned(k.into_owned()), v.into_owned()))
+                    .collect(),
+            ),
+            Value::Seq(items) => Value::Seq(items.into_iter().map(Value::into_owned).collect()),
+        }
+    }
+}
+
+fn storage<'de>(storage: &StringStorage<'de>) -> Cow<'de, str> {
+    match *storage {
+        StringStorage::Borrowed(s) => Cow::Borrowed(s),
+        ref owned => Cow::Owned(owned.as_str().to_owned()),
+    }
+}
+
+fn text<'de>(node: Node<'_, 'de>) -> Cow<'de, str> {
+    node.text_storage().map_or(Cow::Borrowed(""), storage)
+}
+
+/// Element name along with namespace prefix, e.g. `opensearch:Query`
+fn element_name<'de>(node: Node<'_, 'de>) -> Cow<'de, str> {
+    let name = node.tag_name();
+    match name.namespace().and_then(|ns| node.lookup_prefix(ns)) {
+        Some(prefix) => Cow::Owned(format!("{}:{}", prefix, name.name())),
+        None => Cow::Borrowed(name.name()),
+    }
+}
+
+fn attributes<'de>(node: Node<'_, 'de>) -> Vec<(Cow<'de, str>, Value<'de>)> {
+    node.attributes()
+        .map(|attr| (Cow::Borrowed(attr.name()), Value::Text(storage(attr.value_storage()))))
+        .collect()
+}
+
+fn element_value<'de>(node: Node<'_, 'de>) -> Value<'de> {
+    if node.children().any(|child| child.is_element()) {
+        let mut value = children_value(node);
+        if let Value::Map(ref mut entries) = value {
+            if node.attributes().len() > 0 {
+                entries.push((Cow::Borrowed("@attr"), Value::Map(attributes(node))));
+            }
+        }
+        value
+    } else if node.attributes().len() > 0 {
+        let mut entries = attributes(node);
+        entries.push((Cow::Borrowed("#text"), Value::Text(text(node))));
+        Value::Map(entries)
+    } else {
+        Value::Text(text(node))
+    }
+}
+
+fn children_value<'de>(node: Node<'_, 'de>) -> Value<'de> {
+    let mut entries: Vec<(Cow<'de, str>, Value<'de>)> = Vec::new();
+    for child in node.children().filter(Node::is_element) {
+        let name = element_name(child);
+        let value = element_value(child);
+
+        match entries.iter_mut().find(|entry| entry.0 == name) {
+            Some(&mut (_, Value::Seq(ref mut items))) => items.push(value),
+            Some(entry) => {
+                let first = mem::replace(&mut entry.1, Value::Seq(Vec::new()));
+                entry.1 = Value::Seq(vec![first, value]);
+            }
+            None => entries.push((name, value)),
+        }
+    }
+    Value::Map(entries)
+}
+
+// ----------------------------------------------------------------
+
+macro_rules! deserialize_parsed {
+    ($($method:ident => $visit:ident),*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> StdResult<V::Value, JsonError> {
+                match self {
+                    Value::Text(ref text) => {
+                        visitor.$visit(text.trim().parse().map_err(JsonError::custom)?)
+                    }
+                    other => other.deserialize_any(visitor),
+                }
+            }
+        )*
+    }
+}
+
+/// Xml has no types, so values are converted to whatever is requested by data structure:
+/// strings are parsed into numbers, and single elements are treated as one-item sequences.
+impl<'de> Deserializer<'de> for Value<'de> {
+    type Error = JsonError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> StdResult<V::Value, JsonError> {
+        match self {
+            Value::Text(Cow::Borrowed(text)) => visitor.visit_borrowed_str(text),
+            Value::Text(Cow::Owned(text)) => visitor.visit_string(text),
+            Value::Map(entries) => visitor.visit_map(MapValue { entries: entries.into_iter(), value: None }),
+            Value::Seq(items) => visitor.visit_seq(SeqValue(items.into_iter())),
+        }
+    }
+
+    deserialize_parsed!(
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64
+    );
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> StdResult<V::Value, JsonError> {
+        match self {
+            Value::Text(ref text) => match text.trim() {
+                "1" | "true" => visitor.visit_bool(true),
+                "0" | "false" | "" => visitor.visit_bool(false),
+                other => Err(JsonError::custom(format!("expected boolean, got {}", other))),
+            },
+            other => other.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> StdResult<V::Value, JsonError> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> StdResult<V::Value, JsonError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> StdResult<V::Value, JsonError> {
+        match self {
+            Value::Seq(items) => visitor.visit_seq(SeqValue(items.into_iter())),
+            Value::Text(ref text) if text.trim().is_empty() => {
+                visitor.visit_seq(SeqValue(Vec::new().into_iter()))
+            }
+            single => visitor.visit_seq(SeqValue(vec![single].into_iter())),
+        }
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> StdResult<V::Value, JsonError> {
+        match self {
+            Value::Text(text) => {
+                let entries = vec![(Cow::Borrowed("#text"), Value::Text(text))];
+                visitor.visit_map(MapValue { entries: entries.into_iter(), value: None })
+            }
+            other => other.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> StdResult<V::Value, JsonError> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> StdResult<V::Value, JsonError> {
+        match self {
+            Value::Text(text) => visitor.visit_enum(CowStrDeserializer::new(text)),
+            _ => Err(JsonError::custom("expected unit variant")),
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> StdResult<V::Value, JsonError> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> StdResult<V::Value, JsonError> {
+        visitor.visit_unit()
+    }
+
+    ::serde::forward_to_deserialize_any! {
+        char str string bytes byte_buf unit_struct tuple tuple_struct identifier i128 u128
+    }
+}
+
+struct MapValue<'de> {
+    entries: IntoIter<(Cow<'de, str>, Value<'de>)>,
+    value: Option<Value<'de>>,
+}
+
+impl<'de> MapAccess<'de> for MapValue<'de> {
+    type Error = JsonError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> StdResult<Option<K::Value>, JsonError> {
+        match self.entries.next() {
+            Some((key, value)) => {
+                self.value = Some(value);
+                seed.deserialize(CowStrDeserializer::new(key)).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<S: DeserializeSeed<'de>>(&mut self, seed: S) -> StdResult<S::Value, JsonError> {
+        match self.value.take() {
+            Some(value) => seed.deserialize(value),
+            None => Err(JsonError::custom("value is missing")),
+        }
+    }
+}
+
+struct SeqValue<'de>(IntoIter<Value<'de>>);
+
+impl<'de> SeqAccess<'de> for SeqValue<'de> {
+    type Error = JsonError;
+
+    fn next_element_seed<S: DeserializeSeed<'de>>(&mut self, seed: S) -> StdResult<Option<S::Value>, JsonError> {
+        match self.0.next() {
+            Some(value) => seed.deserialize(value).map(Some),
+            None => Ok(None),
+        }
+    }
+}
@@ -2,18 +2,16 @@
 
 extern crate lastfm_parse_rs as lastfm;
 
-mod common;
-use common::test_fn;
+mod net;
+use net::test_fn;
 
 use lastfm::album::GetInfo;
 test_fn!(
     test_album_getinfo,
     GetInfo,
     [
-        "hannah fury",
-        "subterfuge",
-        None,
-        Some(1),
+        ("hannah fury", "subterfuge"),
+        Some(true),
         None,
         Some("xenzh")
     ]
@@ -23,14 +21,14 @@ use lastfm::album::GetTags;
 test_fn!(
     test_album_gettags,
     GetTags,
-    ["rome", "the hyperion machine", None, Some(1), Some("xenzh")]
+    [("rome", "the hyperion machine"), Some(true), Some("xenzh")]
 );
 
 use lastfm::album::GetTopTags;
 test_fn!(
     test_album_gettoptags,
     GetTopTags,
-    ["the bad plus", "never stop", None, Some(1)]
+    [("the bad plus", "never stop"), Some(true)]
 );
 
 use lastfm::album::Search;
@@ -0,0 +1,73 @@
+#![cfg(feature = "audit")]
+
+extern crate lastfm_parse_rs as lastfm;
+
+use std::fs;
+
+use lastfm::audit::{audit_json, audit_method, Report};
+use lastfm::error::Error;
+use lastfm::tag::GetInfo;
+
+
+fn paths(items: &[&str]) -> Vec<String> {
+    items.iter().map(|s| s.to_string()).collect()
+}
+
+#[test]
+fn test_audit_json() {
+    let json = r#"{ "tag": { "name": "ethno", "total": 10, "reach": 20, "streamable": "1" } }"#;
+    let report = audit_json::<GetInfo>(json).unwrap();
+
+    assert_eq!(report.unmapped.into_iter().collect::<Vec<_>>(), paths(&["tag.streamable"]));
+    assert_eq!(report.unfilled.into_iter().collect::<Vec<_>>(), paths(&["tag.wiki"]));
+}
+
+#[test]
+fn test_audit_nested_lists() {
+    let json = fs::read_to_string("tests/fixtures/album.getinfo/lighthouse.json").unwrap();
+    let report = audit_method("album.getinfo", &json).unwrap().unwrap();
+
+    assert!(report.unmapped.contains("album.tracks.track[].artist"));
+    assert!(!report.unmapped.contains("album.wiki"));
+    assert!(report.unfilled.contains("album.userplaycount"));
+}
+
+#[test]
+fn test_audit_merge() {
+    let mut report = Report::default();
+    report.unmapped.insert("a".to_owned());
+    report.unfilled.insert("b".to_owned());
+    report.unfilled.insert("c".to_owned());
+
+    let mut other = Report::default();
+    other.unmapped.insert("d".to_owned());
+    other.unfilled.insert("c".to_owned());
+
+    report.merge(other);
+    assert_eq!(report.unmapped.into_iter().collect::<Vec<_>>(), paths(&["a", "d"]));
+    assert_eq!(report.unfilled.into_iter().collect::<Vec<_>>(), paths(&["c"]));
+}
+
+#[test]
+fn test_audit_errors() {
+    assert!(audit_method("tag.getnothing", "{}").is_none());
+
+    let res = audit_method("tag.getinfo", r#"{ "error": 6, "message": "Tag not found" }"#);
+    assert!(matches!(res, Some(Err(Error::Api(_)))));
+}
+
+#[test]
+fn test_audit_fixtures_parse() {
+    for dir in fs::read_dir("tests/fixtures").unwrap() {
+        let dir = dir.unwrap().path();
+        let method = dir.file_name().unwrap().to_string_lossy().into_owned();
+        for file in fs::read_dir(&dir).unwrap() {
+            let json = fs::read_to_string(file.unwrap().path()).unwrap();
+            match audit_method(&method, &json) {
+                Some(Ok(_)) => {}
+                Some(Err(err)) => panic!("{} fixture doesn't parse: {}", method, err),
+                None => panic!("{} is not a known method", method),
+            }
+        }
+    }
+}
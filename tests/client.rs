@@ -0,0 +1,116 @@
+extern crate lastfm_parse_rs as lastfm;
+
+mod common;
+use common::{BASE_URL, API_KEY};
+
+use std::borrow::Cow;
+
+use lastfm::{Client, Credentials};
+use lastfm::client::LASTFM_BASE_URL;
+use lastfm::error::Error;
+use lastfm::transport::InMemoryTransport;
+use lastfm::structs::api_error::ApiErrorKind;
+use lastfm::tag::{GetInfo, Params};
+
+
+#[test]
+fn test_client_call() {
+    let transport = InMemoryTransport::new();
+    transport.push_response(
+        200,
+        r#"{ "tag": { "name": "ethno", "total": 10, "reach": 20 } }"#,
+    );
+
+    let client = Client::new(&transport, Credentials::new(BASE_URL, API_KEY));
+    let mut buffer = Vec::new();
+    let data: GetInfo = client.call(Params::GetInfo { tag: "ethno", lang: None }, &mut buffer).unwrap();
+
+    assert_eq!(data.name, "ethno");
+    assert_eq!(data.total, 10);
+
+    let requests = transport.requests();
+    assert_eq!(requests.len(), 1);
+
+    let query = requests[0].url.query().unwrap();
+    assert!(query.contains("api_key=test_api_key"));
+    assert!(query.contains("method=tag.getinfo"));
+    assert!(query.contains("tag=ethno"));
+}
+
+#[test]
+fn test_client_call_api_error() {
+    let transport = InMemoryTransport::new();
+    transport.push_response(
+        400,
+        r#"{ "error": 6, "message": "Tag not found" }"#,
+    );
+
+    let client = Client::new(&transport, Credentials::new(BASE_URL, API_KEY));
+    let mut buffer = Vec::new();
+    let res: lastfm::Result<GetInfo> = client.call(Params::GetInfo { tag: "?", lang: None }, &mut buffer);
+
+    match res {
+        Err(Error::Api(err)) => assert_eq!(err.error, ApiErrorKind::InvalidParameters),
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn test_client_call_bad_status() {
+    let transport = InMemoryTransport::new();
+    transport.push_response(502, "<html>Bad Gateway</html>");
+
+    let client = Client::new(&transport, Credentials::new(BASE_URL, API_KEY));
+    let mut buffer = Vec::new();
+    let res: lastfm::Result<GetInfo> = client.call(Params::GetInfo { tag: "ethno", lang: None }, &mut buffer);
+
+    match res {
+        Err(Error::Status(502)) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn test_client_call_escaped_strings() {
+    let transport = InMemoryTransport::new();
+    transport.push_response(
+        200,
+        r#"{ "tag": { "name": "ethno", "total": 10, "reach": 20, "wiki": {
+            "summary": "Music of \"ethnic\" origin",
+            "content": "Plain content"
+        } } }"#,
+    );
+
+    let client = Client::new(&transport, Credentials::new(BASE_URL, API_KEY));
+    let mut buffer = Vec::new();
+    let data: GetInfo = client.call(Params::GetInfo { tag: "ethno", lang: None }, &mut buffer).unwrap();
+
+    let wiki = data.wiki.unwrap();
+    assert_eq!(wiki.summary, "Music of \"ethnic\" origin");
+    assert!(matches!(wiki.summary, Cow::Owned(_)));
+    assert!(matches!(wiki.content, Some(Cow::Borrowed("Plain content"))));
+}
+
+#[test]
+fn test_client_call_owned() {
+    let transport = InMemoryTransport::new();
+    transport.push_response(
+        200,
+        r#"{ "tag": { "name": "ethno", "total": 10, "reach": 20 } }"#,
+    );
+
+    let client = Client::new(&transport, Credentials::new(BASE_URL, API_KEY));
+    let data: GetInfo<'static> = client.call_owned(Params::GetInfo { tag: "ethno", lang: None }).unwrap();
+
+    let name = std::thread::spawn(move || data.name).join().unwrap();
+    assert_eq!(name, "ethno");
+}
+
+#[test]
+fn test_client_default_base_url_is_https() {
+    let credentials = Credentials::new(LASTFM_BASE_URL, API_KEY);
+    let url = GetInfo::builder("ethno").request(&credentials).get_url().unwrap();
+
+    assert_eq!(url.scheme(), "https");
+    assert_eq!(url.host_str(), Some("ws.audioscrobbler.com"));
+}
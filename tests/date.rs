@@ -0,0 +1,30 @@
+extern crate lastfm_parse_rs as lastfm;
+extern crate serde_json;
+
+use lastfm::structs::user::Date;
+
+
+#[test]
+fn test_date_shapes() {
+    let shapes = [
+        r##"{ "uts": "1513157820", "#text": "13 Dec 2017, 09:37" }"##,
+        r##"{ "unixtime": "1513157820", "#text": "13 Dec 2017, 09:37" }"##,
+        r##"{ "uts": "1513157820", "date": "13 Dec 2017, 09:37" }"##,
+    ];
+    for json in shapes.iter() {
+        let date: Date = serde_json::from_str(json).unwrap();
+        assert_eq!(date.uts, Some(1513157820));
+        assert_eq!(date.text, "13 Dec 2017, 09:37");
+    }
+
+    // user.getInfo repeats the timestamp in place of text
+    let date: Date = serde_json::from_str(r##"{ "unixtime": "1273175013", "#text": 1273175013 }"##).unwrap();
+    assert_eq!(date.uts, Some(1273175013));
+    assert_eq!(date.text, "1273175013");
+
+    let date: Date = serde_json::from_str(r##"{ "uts": "", "#text": "" }"##).unwrap();
+    assert_eq!(date.uts, None);
+
+    let json = serde_json::to_string(&date).unwrap();
+    assert_eq!(json, r##"{"uts":null,"#text":""}"##);
+}
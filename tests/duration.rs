@@ -0,0 +1,85 @@
+extern crate lastfm_parse_rs as lastfm;
+extern crate serde_json;
+
+mod common;
+use common::{API_KEY, BASE_URL};
+
+use std::time::Duration;
+
+use lastfm::{to_clean_json_value, Credentials};
+use lastfm::structs::{chart, entity, track};
+use lastfm::track::{ScrobbleTrack, Scrobble, UpdateNowPlaying};
+
+
+static CHART_TRACK: &str = r##"{
+    "name": "Os Lunatum", "mbid": "", "url": "https://www.last.fm/music/Iamthemorning/_/Os+Lunatum",
+    "duration": "{}", "playcount": "100", "listeners": "10",
+    "streamable": { "#text": "0", "fulltrack": "0" },
+    "artist": { "name": "Iamthemorning", "mbid": "", "url": "https://www.last.fm/music/Iamthemorning" },
+    "image": []
+}"##;
+
+static SIMILAR_TRACK: &str = r##"{
+    "name": "Touching II", "url": "https://www.last.fm/music/Iamthemorning/_/Touching+II",
+    "playcount": "12000", "match": 1.0, "duration": 231,
+    "artist": { "name": "Iamthemorning", "mbid": "", "url": "https://www.last.fm/music/Iamthemorning" },
+    "image": []
+}"##;
+
+fn chart_track(duration: &str) -> chart::Track<'static> {
+    let json = CHART_TRACK.replace("{}", duration);
+    serde_json::from_str::<chart::Track>(&json).unwrap().into_owned()
+}
+
+#[test]
+fn test_duration_units() {
+    assert_eq!(chart_track("262").duration, Some(Duration::from_secs(262)));
+
+    let similar: track::Similar = serde_json::from_str(SIMILAR_TRACK).unwrap();
+    assert_eq!(similar.duration, Some(Duration::from_secs(231)));
+    assert_eq!(entity::Track::from(similar).duration, Some(Duration::from_secs(231)));
+}
+
+#[test]
+fn test_duration_unknown() {
+    assert_eq!(chart_track("0").duration, None);
+    assert_eq!(chart_track("").duration, None);
+    assert_eq!(chart_track("FIXME").duration, None);
+
+    let json = SIMILAR_TRACK.replace("\"duration\": 231,", "");
+    assert_eq!(serde_json::from_str::<track::Similar>(&json).unwrap().duration, None);
+
+    let json = SIMILAR_TRACK.replace("231", "-1");
+    let err = serde_json::from_str::<track::Similar>(&json).unwrap_err();
+    assert!(err.to_string().contains("expected duration"));
+}
+
+#[test]
+fn test_duration_serialize() {
+    let track = chart_track("262");
+    let json = serde_json::to_value(&track).unwrap();
+    assert_eq!(json["duration"], "262");
+    assert_eq!(to_clean_json_value(&track).unwrap()["duration"], 262);
+
+    let json = serde_json::to_value(chart_track("0")).unwrap();
+    assert!(json["duration"].is_null());
+}
+
+#[test]
+fn test_duration_request_params() {
+    let credentials = Credentials::new(BASE_URL, API_KEY).authenticate("secret", "session");
+
+    let batch = vec![
+        ScrobbleTrack::new("Iamthemorning".into(), "Os Lunatum".into(), 1513157820)
+            .info(None, None, Some(Duration::from_millis(262500))),
+    ];
+    let url = Scrobble::request(&credentials, &batch).get_url().unwrap();
+    assert!(url.query().unwrap().contains("duration%5B0%5D=262&"));
+
+    let url = UpdateNowPlaying::builder("Iamthemorning", "Os Lunatum")
+        .duration(Duration::from_secs(262))
+        .request(&credentials)
+        .get_url()
+        .unwrap();
+    assert!(url.query().unwrap().contains("duration=262"));
+}
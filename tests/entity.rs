@@ -0,0 +1,98 @@
+extern crate lastfm_parse_rs as lastfm;
+
+mod common;
+use common::{RECENT_TRACKS, SEARCH};
+
+use lastfm::{from_json_str, HasName, HasMbid, HasImages, Mbid};
+use lastfm::structs::entity::{Album, Track, TrackRef, ArtistRef, ArtistIdent, TrackIdent};
+use lastfm::album::Search;
+use lastfm::artist::GetTopTracks;
+use lastfm::user::GetRecentTracks;
+
+
+static TOP_TRACKS: &str = r##"{ "toptracks": {
+    "track": [{
+        "name": "Touching II",
+        "playcount": "12000",
+        "listeners": "3000",
+        "mbid": "0a3e2a4b-2a9c-4c1d-9d4b-3f0b5c7e8a91",
+        "url": "https://www.last.fm/music/Iamthemorning/_/Touching+II",
+        "streamable": "0",
+        "artist": { "name": "Iamthemorning", "mbid": "", "url": "https://www.last.fm/music/Iamthemorning" },
+        "image": [],
+        "@attr": { "rank": "1" }
+    }],
+    "@attr": { "artist": "Iamthemorning", "page": "1", "perPage": "1", "totalPages": "1", "total": "1" }
+} }"##;
+
+fn names<T: HasName>(items: &[T]) -> Vec<&str> {
+    items.iter().map(HasName::name).collect()
+}
+
+#[test]
+fn test_entity_traits() {
+    let data: GetRecentTracks = from_json_str(RECENT_TRACKS).unwrap();
+    let tracks = data.track.unwrap();
+
+    assert_eq!(names(&tracks), vec!["Os Lunatum", "Winter Is \"Never\""]);
+    assert!(tracks[0].mbid().is_none());
+    assert_eq!(tracks[0].images().len(), 1);
+    assert_eq!(tracks[0].artist.name(), "Iamthemorning");
+}
+
+#[test]
+fn test_entity_track_conversions() {
+    let recent: GetRecentTracks = from_json_str(RECENT_TRACKS).unwrap();
+    let top: GetTopTracks = from_json_str(TOP_TRACKS).unwrap();
+
+    let mut tracks: Vec<Track> = recent.track.unwrap().into_iter().map(Track::from).collect();
+    tracks.extend(top.track.unwrap().into_iter().map(Track::from));
+    assert_eq!(names(&tracks), vec!["Os Lunatum", "Winter Is \"Never\"", "Touching II"]);
+
+    let album = tracks[0].album.as_ref().unwrap();
+    assert_eq!(album.name, "Lighthouse");
+    assert_eq!(album.artist.as_ref().unwrap().name, "Iamthemorning");
+    assert_eq!(tracks[0].streamable, Some(false));
+
+    // Blank album and url are left out
+    assert!(tracks[1].album.is_none());
+    assert!(tracks[1].artist.as_ref().unwrap().url.is_none());
+
+    assert_eq!(tracks[2].playcount, Some(12000));
+    assert_eq!(tracks[2].listeners, Some(3000));
+
+    let track: TrackRef = tracks.pop().unwrap().into();
+    let artist: ArtistRef = track.artist.unwrap();
+    assert_eq!(artist.name, "Iamthemorning");
+}
+
+#[test]
+fn test_entity_album_conversions() {
+    let data: Search = from_json_str(SEARCH).unwrap();
+    let album = Album::from(data.albummatches.unwrap().album.unwrap().remove(0)).into_owned();
+
+    assert_eq!(album.name(), "Lighthouse");
+    assert_eq!(album.artist.unwrap().name, "Iamthemorning");
+    assert!(album.image.is_empty());
+}
+
+#[test]
+fn test_entity_mbid() {
+    let top: GetTopTracks = from_json_str(TOP_TRACKS).unwrap();
+    let track: TrackRef = top.track.unwrap().remove(0).into();
+    assert_eq!(track.mbid().unwrap().as_str(), "0a3e2a4b-2a9c-4c1d-9d4b-3f0b5c7e8a91");
+
+    match track.ident() {
+        Some(TrackIdent::Mbid(mbid)) => assert_eq!(mbid.to_string(), "0a3e2a4b-2a9c-4c1d-9d4b-3f0b5c7e8a91"),
+        other => panic!("unexpected ident: {:?}", other),
+    }
+    match track.artist.as_ref().unwrap().ident() {
+        ArtistIdent::Name(name) => assert_eq!(name, "Iamthemorning"),
+        other => panic!("unexpected ident: {:?}", other),
+    }
+
+    let json = TOP_TRACKS.replace("0a3e2a4b-2a9c-4c1d-9d4b-3f0b5c7e8a91", "not-an-mbid");
+    let top: GetTopTracks = from_json_str(&json).unwrap();
+    assert!(top.track.unwrap()[0].mbid.is_none());
+    assert!(Mbid::new("not-an-mbid").is_err());
+}
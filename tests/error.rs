@@ -0,0 +1,80 @@
+extern crate lastfm_parse_rs as lastfm;
+
+use lastfm::{from_json_str, from_json_owned, Client, Credentials};
+use lastfm::error::{Error, DeserializeError};
+use lastfm::transport::InMemoryTransport;
+use lastfm::tag::{GetInfo, Params};
+
+
+fn deserialize_error<T: std::fmt::Debug>(res: lastfm::Result<T>) -> DeserializeError {
+    match res {
+        Err(Error::Deserialize(err)) => err,
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn test_error_path_and_snippet() {
+    let json = r#"{ "tag": { "name": "ethno", "total": "many", "reach": 20 } }"#;
+    let err = deserialize_error(from_json_str::<GetInfo>(json));
+
+    assert_eq!(err.path(), Some("tag.total"));
+    assert_eq!(err.expected(), Some("u32"));
+    assert!(err.snippet().unwrap().contains(r#""total": "many""#));
+    assert_eq!(err.method(), None);
+    assert!(!err.is_api_error());
+
+    let message = Error::Deserialize(err).to_string();
+    assert!(message.contains("tag.total: invalid type"));
+}
+
+#[test]
+fn test_error_snippet_bounds() {
+    let name = "é".repeat(100);
+    let json = format!(r#"{{ "tag": {{ "name": "{}", "total": true, "reach": 20 }} }}"#, name);
+    let err = deserialize_error(from_json_owned::<_, GetInfo>(json.as_bytes()));
+
+    let snippet = err.snippet().unwrap();
+    assert!(snippet.len() < 100);
+    assert!(snippet.contains("true"));
+    assert!(!snippet.contains('\u{fffd}'));
+}
+
+#[test]
+fn test_error_malformed_api_error() {
+    let json = r#"{ "error": "six", "message": "Tag not found" }"#;
+    let err = deserialize_error(from_json_str::<GetInfo>(json));
+
+    assert!(err.is_api_error());
+    assert_eq!(err.path(), Some("error"));
+}
+
+#[test]
+fn test_error_api_error_missing_message() {
+    let json = r#"{ "error": 6, "links": [] }"#;
+    let err = deserialize_error(from_json_str::<GetInfo>(json));
+
+    assert!(err.is_api_error());
+    assert_eq!(err.reason(), "missing field `message`");
+}
+
+#[test]
+fn test_error_api_error_detected_first() {
+    // Data type doesn't need to be tried before the error
+    let json = r#"{ "message": "Tag not found", "error": 6 }"#;
+    assert!(matches!(from_json_str::<GetInfo>(json), Err(Error::Api(_))));
+}
+
+#[test]
+fn test_error_method() {
+    let transport = InMemoryTransport::new();
+    transport.push_response(200, r#"{ "tag": { "name": "ethno" } }"#);
+
+    let client = Client::new(&transport, Credentials::new("http://localhost/2.0/", "key"));
+    let mut buffer = Vec::new();
+    let err = deserialize_error(client.call::<GetInfo, _>(Params::GetInfo { tag: "ethno", lang: None }, &mut buffer));
+
+    assert_eq!(err.method(), Some("tag.getinfo"));
+    assert_eq!(err.reason(), "missing field `total`");
+    assert!(err.to_string().starts_with("tag.getinfo: tag: missing field `total`"));
+}
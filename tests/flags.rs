@@ -0,0 +1,49 @@
+extern crate lastfm_parse_rs as lastfm;
+extern crate serde_json;
+
+use lastfm::{from_json_str, to_clean_json_value};
+use lastfm::structs::common::Streamable;
+use lastfm::structs::auth::GetSession;
+use lastfm::structs::user::GetInfo;
+
+
+static USER: &str = r##"{ "user": {
+    "name": "xenzh", "realname": "", "url": "https://www.last.fm/user/xenzh",
+    "country": "", "age": "0", "gender": "n", "subscriber": "FIXME",
+    "playcount": "100", "playlists": "0", "bootstrap": "0",
+    "registered": { "unixtime": "1273175013", "#text": 1273175013 },
+    "image": [], "type": "FIXME"
+} }"##;
+
+#[test]
+fn test_flags_strings_and_numbers() {
+    let streamable: Streamable = serde_json::from_str(r##"{ "#text": "1", "fulltrack": "0" }"##).unwrap();
+    assert!(streamable.streamable);
+    assert!(!streamable.fulltrack);
+
+    let session: GetSession = from_json_str(r#"{ "session": { "name": "xenzh", "key": "k", "subscriber": 0 } }"#)
+        .unwrap();
+    assert!(!session.subscriber);
+
+    let err = serde_json::from_str::<Streamable>(r##"{ "#text": "2", "fulltrack": "0" }"##).unwrap_err();
+    assert!(err.to_string().contains("expected flag"));
+}
+
+#[test]
+fn test_flags_placeholders() {
+    let data: GetInfo = from_json_str(USER).unwrap();
+    assert_eq!(data.subscriber, None);
+    assert!(!data.bootstrap);
+}
+
+#[test]
+fn test_flags_serialize() {
+    let streamable: Streamable = serde_json::from_str(r##"{ "#text": "1", "fulltrack": "0" }"##).unwrap();
+    let json = serde_json::to_string(&streamable).unwrap();
+    assert_eq!(json, r##"{"#text":"1","fulltrack":"0"}"##);
+    assert!(serde_json::from_str::<Streamable>(&json).unwrap().streamable);
+
+    let clean = to_clean_json_value(&streamable).unwrap();
+    assert_eq!(clean["#text"], true);
+    assert_eq!(clean["fulltrack"], false);
+}
@@ -0,0 +1,54 @@
+extern crate lastfm_parse_rs as lastfm;
+extern crate serde_json;
+extern crate url;
+
+use std::convert::TryFrom;
+
+use url::Url as StdUrl;
+
+use lastfm::structs::common::{Images, ImageSize, Url};
+
+
+static IMAGES: &str = r##"[
+    { "#text": "https://lastfm.freetls.fastly.net/i/u/34s/2a96cbd8b46e442fc41c2b86b821562f.png", "size": "small" },
+    { "#text": "https://lastfm.freetls.fastly.net/i/u/64s/8a3d1b5e.png", "size": "medium" },
+    { "#text": "https://lastfm.freetls.fastly.net/i/u/174s/8a3d1b5e.png", "size": "large" },
+    { "#text": "", "size": "extralarge" },
+    { "#text": "https://lastfm.freetls.fastly.net/i/u/8a3d1b5e.png", "size": "" }
+]"##;
+
+#[test]
+fn test_url_conversion() {
+    let url: Url = serde_json::from_str(r#""https://www.last.fm/music/Iamthemorning""#).unwrap();
+    assert_eq!(StdUrl::try_from(&url).unwrap().host_str(), Some("www.last.fm"));
+
+    let empty: Url = serde_json::from_str(r#""""#).unwrap();
+    assert!(empty.is_empty());
+    assert!(StdUrl::try_from(empty).is_err());
+}
+
+#[test]
+fn test_images_lookup() {
+    let images: Images = serde_json::from_str(IMAGES).unwrap();
+
+    assert_eq!(images.len(), 5);
+    assert_eq!(images.non_empty().count(), 2);
+
+    let largest = images.largest().unwrap();
+    assert_eq!(largest.size, ImageSize::Large);
+    assert_eq!(largest.url().unwrap().path(), "/i/u/174s/8a3d1b5e.png");
+
+    assert!(images.get(ImageSize::Medium).is_some());
+    assert!(images.get(ImageSize::Small).is_none());
+    assert!(images.get(ImageSize::ExtraLarge).is_none());
+    assert!(images.get(ImageSize::Default).is_none());
+}
+
+#[test]
+fn test_images_all_missing() {
+    let images: Images = serde_json::from_str(r##"[{ "#text": "", "size": "small" }]"##).unwrap();
+
+    assert!(images.largest().is_none());
+    assert!(images[0].url().is_none());
+    assert_eq!(serde_json::to_string(&images).unwrap(), r##"[{"#text":"","size":"small"}]"##);
+}
@@ -0,0 +1,107 @@
+extern crate lastfm_parse_rs as lastfm;
+
+use lastfm::{from_json_str_lenient, from_json_slice_lenient};
+use lastfm::error::Error;
+use lastfm::lenient::Fix;
+use lastfm::tag::GetInfo;
+use lastfm::user::GetRecentTracks;
+
+/// A lone track sent as an object instead of a list, with an empty `loved` flag
+static SINGLE_TRACK: &str = r##"{ "recenttracks": {
+    "track": {
+        "artist": { "name": "Iamthemorning", "mbid": "", "url": "https://www.last.fm/music/Iamthemorning", "image": [] },
+        "loved": "",
+        "name": "Os Lunatum",
+        "streamable": "0",
+        "mbid": "",
+        "album": { "#text": "Lighthouse", "mbid": "" },
+        "url": "https://www.last.fm/music/Iamthemorning/_/Os+Lunatum",
+        "image": { "#text": "", "size": "small" },
+        "date": { "uts": "1513157820", "#text": "13 Dec 2017, 09:37" }
+    },
+    "@attr": { "user": "xenzh", "page": "1", "perPage": "1", "totalPages": "1", "total": "1" }
+} }"##;
+
+#[test]
+fn test_lenient_well_formed() {
+    let json = r#"{ "tag": { "name": "ethno", "total": 10, "reach": 20 } }"#;
+    let res = from_json_str_lenient::<GetInfo>(json).unwrap();
+
+    assert_eq!(res.data.name, "ethno");
+    assert!(res.warnings.is_empty());
+}
+
+#[test]
+fn test_lenient_lists_and_leaves() {
+    let res = from_json_str_lenient::<GetRecentTracks>(SINGLE_TRACK).unwrap();
+
+    let tracks = res.data.track.unwrap();
+    assert_eq!(tracks.len(), 1);
+    assert!(!tracks[0].loved);
+    assert_eq!(tracks[0].image.len(), 1);
+
+    let warnings: Vec<_> = res.warnings.iter().map(|w| (w.path.as_str(), &w.fix)).collect();
+    assert_eq!(
+        warnings,
+        vec![
+            ("recenttracks.track", &Fix::WrappedInArray),
+            ("recenttracks.track[0].image", &Fix::WrappedInArray),
+            ("recenttracks.track[0].loved", &Fix::Replaced("0".into())),
+        ]
+    );
+}
+
+#[test]
+fn test_lenient_numbers_and_missing_fields() {
+    let json = br#"{ "tag": { "name": "ethno", "total": "10" } }"#;
+    let res = from_json_slice_lenient::<GetInfo>(json).unwrap();
+
+    assert_eq!(res.data.total, 10);
+    assert_eq!(res.data.reach, 0);
+
+    assert_eq!(res.warnings.len(), 2);
+    assert_eq!(res.warnings[0].path, "tag.total");
+    assert_eq!(res.warnings[0].fix, Fix::Replaced(10.into()));
+    assert_eq!(res.warnings[1].path, "tag.reach");
+    assert_eq!(res.warnings[1].fix, Fix::Inserted(0.into()));
+    assert!(res.warnings[1].to_string().contains("missing field `reach`"));
+}
+
+#[test]
+fn test_lenient_api_error() {
+    let res = from_json_str_lenient::<GetInfo>(r#"{ "error": 6, "message": "Tag not found" }"#);
+    assert!(matches!(res, Err(Error::Api(_))));
+}
+
+#[test]
+fn test_lenient_repeated_in_every_item() {
+    let track = r##"{
+        "artist": { "name": "Iamthemorning", "mbid": "", "url": "https://www.last.fm/music/Iamthemorning", "image": [] },
+        "loved": "",
+        "name": {name},
+        "streamable": "0",
+        "mbid": "",
+        "album": { "#text": "Lighthouse", "mbid": "" },
+        "url": "https://www.last.fm/music/Iamthemorning/_/Os+Lunatum",
+        "image": { "#text": "", "size": "small" },
+        "date": { "uts": "1513157820", "#text": "13 Dec 2017, 09:37" }
+    }"##;
+    let tracks: Vec<String> = (0..200).map(|i| track.replace("{name}", &(1800 + i).to_string())).collect();
+    let json = format!(
+        r##"{{ "recenttracks": {{ "track": [{}],
+              "@attr": {{ "user": "xenzh", "page": "1", "perPage": "200", "totalPages": "1", "total": "200" }} }} }}"##,
+        tracks.join(",")
+    );
+
+    let res = from_json_str_lenient::<GetRecentTracks>(&json).unwrap();
+
+    let tracks = res.data.track.unwrap();
+    assert_eq!(tracks.len(), 200);
+    assert!(tracks.iter().all(|track| !track.loved && track.image.len() == 1));
+    assert_eq!(tracks[199].name, "1999");
+
+    assert_eq!(res.warnings.len(), 3 * 200);
+    let warning = res.warnings.iter().find(|w| w.path == "recenttracks.track[199].name").unwrap();
+    assert_eq!(warning.fix, Fix::Replaced("1999".into()));
+    assert_eq!(warning.reason, "invalid type: integer `1999`, expected a string");
+}
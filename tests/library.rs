@@ -2,8 +2,48 @@
 
 extern crate lastfm_parse_rs as lastfm;
 
-mod common;
-use common::test_fn;
+mod net;
+use net::test_fn;
+
+use lastfm::from_json_str;
 
 use lastfm::library::GetArtists;
 test_fn!(test_library_getartists, GetArtists, ["xenzh", Some(3), None]);
+
+#[test]
+fn test_library_getartists_page_info() {
+    let raw_json = r##"
+{
+  "artists": {
+    "artist": [
+      {
+        "name": "iamthemorning",
+        "mbid": "",
+        "url": "https://www.last.fm/music/iamthemorning",
+        "playcount": "512",
+        "tagcount": "0",
+        "streamable": "0",
+        "image": []
+      }
+    ],
+    "@attr": {
+      "user": "xenzh",
+      "page": "2",
+      "perPage": "1",
+      "totalPages": "745",
+      "total": "745"
+    }
+  }
+}
+    "##;
+
+    let data: GetArtists = from_json_str(&raw_json).unwrap();
+    println!("\nDeserialized {}:\n{:?}", stringify!(GetArtists), data);
+
+    let page_info = data.page_info.unwrap();
+    assert_eq!(page_info.page, 2);
+    assert_eq!(page_info.per_page, 1);
+    assert_eq!(page_info.total_pages, 745);
+    assert!(!page_info.is_last());
+    assert_eq!(page_info.user.unwrap(), "xenzh");
+}
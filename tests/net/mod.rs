@@ -0,0 +1,48 @@
+extern crate url;
+extern crate serde_json;
+extern crate async_http_client;
+
+extern crate lastfm_parse_rs as lastfm;
+
+
+use std::convert::TryFrom;
+
+use self::url::Url;
+
+use self::async_http_client::prelude::*;
+use self::async_http_client::HttpRequest;
+
+use self::lastfm::{from_json_slice, Credentials};
+
+
+static LASTFM_BASE_URL: &str = "https://ws.audioscrobbler.com/2.0/";
+static LASTFM_API_KEY: &str = "INSERT_YOUR_API_KEY_HERE";
+
+// ----------------------------------------------------------------
+
+pub macro test_fn($name:ident, $lastfm_type:ident, [$($param_val:expr),*]) {
+    #[test]
+    fn $name() {
+        let credentials = Credentials::new(LASTFM_BASE_URL, LASTFM_API_KEY);
+        let rq = $lastfm_type::request(&credentials, $($param_val),*);
+        let url: Url = TryFrom::try_from(rq).unwrap();
+
+        println!("\nUrl: {}\n", url);
+
+        let req = HttpRequest::get(url).unwrap();
+        let addr = req.addr().unwrap();
+
+        let mut core = Core::new().unwrap(); 
+        let handle = core.handle();
+
+        let (res, _) = core.run(TcpStream::connect(&addr, &handle).and_then(|conn| {
+            req.send(conn)
+        })).unwrap();
+
+        let res = res.unwrap();
+        println!("\nRaw: {}\n", String::from_utf8_lossy(res.get_body()));
+
+        let data: $lastfm_type = from_json_slice(res.get_body()).unwrap();
+        println!("\nDeserialized {}:\n{:?}", stringify!($lastfm_type), data);
+    }
+}
@@ -0,0 +1,62 @@
+extern crate lastfm_parse_rs as lastfm;
+
+mod common;
+use common::RECENT_TRACKS;
+
+use std::borrow::Cow;
+
+use lastfm::{from_json_str, from_json_owned, IntoOwned};
+use lastfm::album::Search;
+use lastfm::user::GetRecentTracks;
+
+
+fn parse_recent_tracks() -> GetRecentTracks<'static> {
+    let body = RECENT_TRACKS.to_string();
+    let data: GetRecentTracks = from_json_str(&body).unwrap();
+    data.into_owned()
+}
+
+#[test]
+fn test_into_owned() {
+    let data = parse_recent_tracks();
+
+    let track = &data.track.unwrap()[0];
+    assert_eq!(track.name, "Os Lunatum");
+    assert!(matches!(track.name, Cow::Owned(_)));
+    assert_eq!(data.page_info.unwrap().user.unwrap(), "xenzh");
+}
+
+#[test]
+fn test_into_owned_items() {
+    let body = RECENT_TRACKS.to_string();
+    let data: GetRecentTracks = from_json_str(&body).unwrap();
+    let tracks: Vec<_> = data.track.unwrap().into_iter().map(IntoOwned::into_owned).collect();
+    drop(body);
+
+    assert_eq!(tracks[0].artist.name, "Iamthemorning");
+}
+
+#[test]
+fn test_from_json_owned() {
+    let body = RECENT_TRACKS.as_bytes().to_vec();
+    let data: GetRecentTracks<'static> = from_json_owned(&body).unwrap();
+    drop(body);
+    assert_eq!(data.track.unwrap().len(), 2);
+
+    let raw_json = String::from(r##"{ "results": {
+        "opensearch:Query": { "#text": "", "role": "request", "searchTerms": "lighthouse", "startPage": "1" },
+        "opensearch:totalResults": "0",
+        "opensearch:startIndex": "0",
+        "opensearch:itemsPerPage": "30",
+        "albummatches": { "album": [] }
+    } }"##);
+    let search: Search<'static> = from_json_owned(raw_json.as_str()).unwrap();
+    assert_eq!(search.query.search_terms.unwrap(), "lighthouse");
+}
+
+#[test]
+fn test_from_json_owned_api_error() {
+    let res: lastfm::Result<Search<'static>> =
+        from_json_owned(r#"{ "error": 6, "message": "Album not found" }"#);
+    assert!(matches!(res, Err(lastfm::error::Error::Api(_))));
+}
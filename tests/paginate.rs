@@ -0,0 +1,200 @@
+extern crate lastfm_parse_rs as lastfm;
+#[cfg(feature = "async")]
+extern crate futures_core;
+
+mod common;
+use common::{BASE_URL, API_KEY};
+
+use lastfm::{Client, Credentials, Paginated};
+use lastfm::error::Error;
+use lastfm::transport::InMemoryTransport;
+use lastfm::{library, user};
+
+
+fn artists_page(names: &[&str], page: u32, total_pages: u32) -> String {
+    let artists: Vec<String> = names
+        .iter()
+        .map(|name| {
+            format!(
+                r#"{{ "name": "{}", "mbid": "", "url": "https://www.last.fm/music/{}",
+                     "playcount": "1", "tagcount": "0", "streamable": "0", "image": [] }}"#,
+                name, name
+            )
+        })
+        .collect();
+
+    format!(
+        r#"{{ "artists": {{ "artist": [{}], "@attr": {{ "user": "xenzh", "page": "{}",
+              "perPage": "2", "totalPages": "{}", "total": "3" }} }} }}"#,
+        artists.join(","), page, total_pages
+    )
+}
+
+#[test]
+fn test_paginate_stops_at_total_pages() {
+    let transport = InMemoryTransport::new();
+    transport.push_response(200, &artists_page(&["rome", "ulver"], 1, 2));
+    transport.push_response(200, &artists_page(&["iamthemorning"], 2, 2));
+    transport.push_response(200, &artists_page(&["should not be fetched"], 3, 2));
+
+    let client = Client::new(&transport, Credentials::new(BASE_URL, API_KEY));
+    let names: Vec<String> = library::GetArtists::builder("xenzh")
+        .limit(2)
+        .paginate(&client, |artist| artist.name.into_owned())
+        .collect::<lastfm::Result<_>>()
+        .unwrap();
+
+    assert_eq!(names, vec!["rome", "ulver", "iamthemorning"]);
+
+    let requests = transport.requests();
+    assert_eq!(requests.len(), 2);
+    assert!(requests[0].url.query().unwrap().contains("page=1"));
+    assert!(requests[1].url.query().unwrap().contains("page=2"));
+}
+
+#[test]
+fn test_paginate_starts_at_requested_page() {
+    let transport = InMemoryTransport::new();
+    transport.push_response(200, &artists_page(&["iamthemorning"], 2, 2));
+
+    let client = Client::new(&transport, Credentials::new(BASE_URL, API_KEY));
+    let count = library::GetArtists::builder("xenzh")
+        .page(2)
+        .paginate(&client, |_| ())
+        .count();
+
+    assert_eq!(count, 1);
+    assert!(transport.requests()[0].url.query().unwrap().contains("page=2"));
+}
+
+#[test]
+fn test_paginate_stops_after_error() {
+    let transport = InMemoryTransport::new();
+    transport.push_response(200, &artists_page(&["rome", "ulver"], 1, 2));
+    transport.push_response(500, r#"{ "error": 8, "message": "Operation failed" }"#);
+
+    let client = Client::new(&transport, Credentials::new(BASE_URL, API_KEY));
+    let results: Vec<_> = library::GetArtists::builder("xenzh")
+        .paginate(&client, |artist| artist.name.into_owned())
+        .collect();
+
+    assert_eq!(results.len(), 3);
+    assert!(results[0].is_ok() && results[1].is_ok());
+    match results[2] {
+        Err(Error::Api(_)) => {}
+        ref other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn test_paginate_not_paginated() {
+    let transport = InMemoryTransport::new();
+    let client = Client::new(&transport, Credentials::new(BASE_URL, API_KEY));
+
+    let mut items = Paginated::<_, _, _, user::GetRecentTracks, _, _>::new(
+        &client,
+        user::Params::GetInfo { user: "xenzh" },
+        |track| track.name.into_owned(),
+    );
+
+    match items.next() {
+        Some(Err(Error::InvalidParameter { name: "page", .. })) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+    assert!(items.next().is_none());
+    assert!(transport.requests().is_empty());
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn test_paginate_async() {
+    use std::pin::Pin;
+    use std::task::{Context, Poll, Waker};
+    use futures_core::Stream;
+
+    let transport = InMemoryTransport::new();
+    transport.push_response(200, &artists_page(&["rome", "ulver"], 1, 2));
+    transport.push_response(200, &artists_page(&["iamthemorning"], 2, 2));
+
+    let client = Client::new(&transport, Credentials::new(BASE_URL, API_KEY));
+    let mut stream = library::GetArtists::builder("xenzh")
+        .paginate_async(&client, |artist| artist.name.into_owned());
+
+    // In-memory transport is always ready, so the stream never returns Pending
+    let mut cx = Context::from_waker(Waker::noop());
+    let mut names = Vec::new();
+    while let Poll::Ready(Some(name)) = Pin::new(&mut stream).poll_next(&mut cx) {
+        names.push(name.unwrap());
+    }
+
+    assert_eq!(names, vec!["rome", "ulver", "iamthemorning"]);
+    assert_eq!(transport.requests().len(), 2);
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn test_paginate_async_retries_api_error() {
+    use std::pin::Pin;
+    use std::task::{Context, Poll, Waker};
+    use futures_core::Stream;
+    use lastfm::RetryPolicy;
+
+    let transport = InMemoryTransport::new();
+    transport.push_response(200, r#"{ "error": 8, "message": "Operation failed" }"#);
+    transport.push_response(200, &artists_page(&["rome"], 1, 1));
+
+    let client = Client::new(&transport, Credentials::new(BASE_URL, API_KEY))
+        .with_retry_policy(RetryPolicy::new(3).with_jitter(false));
+    let mut stream = library::GetArtists::builder("xenzh")
+        .paginate_async(&client, |artist| artist.name.into_owned());
+
+    let mut cx = Context::from_waker(Waker::noop());
+    let mut names = Vec::new();
+    while let Poll::Ready(Some(name)) = Pin::new(&mut stream).poll_next(&mut cx) {
+        names.push(name.unwrap());
+    }
+
+    assert_eq!(names, vec!["rome"]);
+    assert_eq!(transport.requests().len(), 2);
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn test_paginate_async_rate_limit_backoff() {
+    use std::pin::Pin;
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Waker};
+    use std::time::Duration;
+    use futures_core::Stream;
+    use lastfm::RateLimiter;
+    use lastfm::rate_limit::MockClock;
+
+    let rate_limited = r#"{ "error": 29, "message": "Rate Limit Exceded" }"#;
+    let limiter = || Arc::new(RateLimiter::new(5, Duration::from_secs(1)).with_clock(Arc::new(MockClock::new())));
+
+    let transport = InMemoryTransport::new();
+    transport.push_response(429, rate_limited);
+    let sync_limiter = limiter();
+    let client = Client::new(&transport, Credentials::new(BASE_URL, API_KEY)).with_rate_limiter(sync_limiter.clone());
+    let results: Vec<_> = library::GetArtists::builder("xenzh")
+        .paginate(&client, |artist| artist.name.into_owned())
+        .collect();
+    assert_eq!(results.len(), 1);
+
+    let transport = InMemoryTransport::new();
+    transport.push_response(429, rate_limited);
+    let async_limiter = limiter();
+    let client = Client::new(&transport, Credentials::new(BASE_URL, API_KEY)).with_rate_limiter(async_limiter.clone());
+    let mut stream = library::GetArtists::builder("xenzh")
+        .paginate_async(&client, |artist| artist.name.into_owned());
+
+    let mut cx = Context::from_waker(Waker::noop());
+    match Pin::new(&mut stream).poll_next(&mut cx) {
+        Poll::Ready(Some(Err(Error::Api(_)))) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    // Each response is observed once, so both paths back off by the same amount
+    assert_eq!(sync_limiter.backoff(API_KEY), Duration::from_secs(1));
+    assert_eq!(async_limiter.backoff(API_KEY), sync_limiter.backoff(API_KEY));
+}
@@ -0,0 +1,118 @@
+extern crate lastfm_parse_rs as lastfm;
+
+mod common;
+use common::{BASE_URL, API_KEY};
+
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use lastfm::{Client, Credentials, RateLimiter};
+use lastfm::error::Error;
+use lastfm::rate_limit::{Clock, MockClock};
+use lastfm::transport::InMemoryTransport;
+use lastfm::tag::{GetInfo, Params};
+
+
+fn millis(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+fn limiter(requests: u32, clock: &Arc<MockClock>) -> RateLimiter {
+    RateLimiter::new(requests, Duration::from_secs(1)).with_clock(clock.clone())
+}
+
+#[test]
+fn test_rate_limit_burst_and_refill() {
+    let clock = Arc::new(MockClock::new());
+    let limiter = limiter(2, &clock);
+
+    assert_eq!(limiter.reserve(API_KEY), Duration::ZERO);
+    assert_eq!(limiter.reserve(API_KEY), Duration::ZERO);
+    assert_eq!(limiter.reserve(API_KEY), millis(500));
+    assert_eq!(limiter.reserve(API_KEY), millis(1000));
+
+    clock.advance(Duration::from_secs(2));
+    assert_eq!(limiter.reserve(API_KEY), Duration::ZERO);
+
+    // Buckets are kept per API key
+    assert_eq!(limiter.reserve("other_api_key"), Duration::ZERO);
+}
+
+#[test]
+fn test_rate_limit_acquire_sleeps() {
+    let clock = Arc::new(MockClock::new());
+    let limiter = limiter(1, &clock);
+
+    limiter.acquire(API_KEY);
+    limiter.acquire(API_KEY);
+    limiter.acquire(API_KEY);
+
+    assert_eq!(clock.sleeps(), vec![millis(1000), millis(1000)]);
+}
+
+#[test]
+fn test_rate_limit_shared_across_threads() {
+    let clock = Arc::new(MockClock::new());
+    let limiter = Arc::new(limiter(1, &clock));
+
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let limiter = limiter.clone();
+            thread::spawn(move || limiter.reserve(API_KEY))
+        })
+        .collect();
+
+    let mut waits: Vec<Duration> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+    waits.sort();
+    assert_eq!(waits, vec![millis(0), millis(1000), millis(2000), millis(3000)]);
+}
+
+#[test]
+fn test_rate_limit_backoff() {
+    let clock = Arc::new(MockClock::new());
+    let limiter = limiter(5, &clock).with_backoff(millis(1000), millis(3000));
+
+    limiter.back_off(API_KEY);
+    assert_eq!(limiter.backoff(API_KEY), millis(1000));
+    assert_eq!(limiter.reserve(API_KEY), millis(1200));
+
+    limiter.back_off(API_KEY);
+    limiter.back_off(API_KEY);
+    assert_eq!(limiter.backoff(API_KEY), millis(3000));
+
+    limiter.recover(API_KEY);
+    assert_eq!(limiter.backoff(API_KEY), Duration::ZERO);
+}
+
+#[test]
+fn test_rate_limit_client() {
+    let transport = InMemoryTransport::new();
+    transport.push_response(
+        429,
+        r#"{ "error": 29, "message": "Rate Limit Exceded" }"#,
+    );
+    transport.push_response(
+        200,
+        r#"{ "tag": { "name": "ethno", "total": 10, "reach": 20 } }"#,
+    );
+
+    let clock = Arc::new(MockClock::new());
+    let limiter = Arc::new(limiter(5, &clock));
+    let client = Client::new(&transport, Credentials::new(BASE_URL, API_KEY))
+        .with_rate_limiter(limiter.clone());
+
+    let mut buffer = Vec::new();
+    let res: lastfm::Result<GetInfo> = client.call(Params::GetInfo { tag: "ethno", lang: None }, &mut buffer);
+    match res {
+        Err(Error::Api(_)) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+    assert_eq!(limiter.backoff(API_KEY), Duration::from_secs(1));
+    let before = clock.now();
+
+    let data: GetInfo = client.call(Params::GetInfo { tag: "ethno", lang: None }, &mut buffer).unwrap();
+    assert_eq!(data.name, "ethno");
+    assert!(clock.now() - before >= Duration::from_secs(1));
+    assert_eq!(limiter.backoff(API_KEY), Duration::ZERO);
+}
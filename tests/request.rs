@@ -0,0 +1,159 @@
+extern crate lastfm_parse_rs as lastfm;
+
+mod common;
+use common::{BASE_URL, API_KEY};
+
+use lastfm::{Credentials, Request};
+use lastfm::error::Error;
+use lastfm::transport::HttpMethod;
+use lastfm::structs::{album, artist, auth, tag, track, user};
+use lastfm::structs::common::{Mbid, Timestamp};
+
+
+#[test]
+fn test_request_to_http_get() {
+    let credentials = Credentials::new(BASE_URL, API_KEY);
+    let rq = tag::GetInfo::request(&credentials, "ethno", None);
+    let http = rq.to_http().unwrap();
+
+    assert_eq!(http.method, HttpMethod::Get);
+    assert!(http.body.is_none());
+    assert_eq!(http.url, rq.get_url().unwrap());
+}
+
+#[test]
+fn test_request_to_http_post() {
+    let credentials = Credentials::new(BASE_URL, API_KEY).authenticate("secret", "session");
+    let rq = track::Love::request(&credentials, "cure", "creep");
+    let http = rq.to_http().unwrap();
+
+    assert_eq!(http.method, HttpMethod::Post);
+    assert_eq!(http.url.query(), None);
+    assert_eq!(http.header("content-type"), Some("application/x-www-form-urlencoded"));
+
+    let body = String::from_utf8(http.body.unwrap()).unwrap();
+    assert_eq!(body, rq.get_url().unwrap().query().unwrap());
+    assert!(body.contains("sk=session"));
+    assert!(body.contains("api_sig="));
+}
+
+#[test]
+fn test_request_to_http_auth_post() {
+    let credentials = Credentials::new(BASE_URL, API_KEY).with_secret("secret");
+    let rq = auth::GetMobileSession::request(&credentials, "user", "password");
+    let http = rq.to_http().unwrap();
+
+    assert_eq!(http.method, HttpMethod::Post);
+    assert!(!http.url.as_str().contains("password"));
+
+    let body = String::from_utf8(http.body.unwrap()).unwrap();
+    assert!(body.contains("password=password"));
+}
+
+#[test]
+fn test_request_builder() {
+    let credentials = Credentials::new(BASE_URL, API_KEY);
+    let built = artist::GetInfo::builder("iamthemorning")
+        .autocorrect(true)
+        .lang("de")
+        .username("xenzh")
+        .request(&credentials);
+    let positional = artist::GetInfo::request(
+        &credentials,
+        "iamthemorning", Some(true), Some("de"), Some("xenzh"),
+    );
+
+    let url = built.get_url().unwrap();
+    assert_eq!(url, positional.get_url().unwrap());
+    assert!(url.query().unwrap().contains("autocorrect=1"));
+}
+
+#[test]
+fn test_request_tag_lang() {
+    let credentials = Credentials::new(BASE_URL, API_KEY);
+    let url = tag::GetInfo::builder("ethno").lang("de").request(&credentials).get_url().unwrap();
+    assert!(url.query().unwrap().contains("tag=ethno&lang=de"));
+}
+
+#[test]
+fn test_request_idents() {
+    let credentials = Credentials::new(BASE_URL, API_KEY);
+
+    let url = track::GetInfo::builder(("iamthemorning", "os lunatum")).request(&credentials).get_url().unwrap();
+    let query = url.query().unwrap();
+    assert!(query.contains("artist=iamthemorning") && query.contains("track=os+lunatum"));
+    assert!(!query.contains("mbid="));
+
+    let mbid = Mbid::new("bfcc6d75-a6a5-4bc6-8282-47aec8531818").unwrap();
+    for url in &[
+        track::GetInfo::builder(&mbid).request(&credentials).get_url().unwrap(),
+        album::GetTopTags::builder(&mbid).request(&credentials).get_url().unwrap(),
+        artist::GetTopTracks::builder(&mbid).limit(5).request(&credentials).get_url().unwrap(),
+    ] {
+        let query = url.query().unwrap();
+        assert!(query.contains("mbid=bfcc6d75-a6a5-4bc6-8282-47aec8531818"));
+        assert!(!query.contains("artist=") && !query.contains("track=") && !query.contains("album="));
+    }
+}
+
+#[test]
+fn test_request_builder_defaults() {
+    let params = user::GetRecentTracks::builder("xenzh").limit(5).params();
+
+    match params {
+        user::Params::GetRecentTracks { user, limit, page, extended, from, to } => {
+            assert_eq!(user, "xenzh");
+            assert_eq!(limit, Some(5));
+            assert_eq!((page, extended, from, to), (None, None, None, None));
+        }
+        other => panic!("unexpected params: {:?}", other),
+    }
+}
+
+#[test]
+fn test_request_builder_converts_timestamps() {
+    let params = user::GetRecentTracks::builder("xenzh").from(1108296000).to(Timestamp(1108900800)).params();
+
+    match params {
+        user::Params::GetRecentTracks { from, to, .. } => {
+            assert_eq!(from, Some(Timestamp(1108296000)));
+            assert_eq!(to, Some(Timestamp(1108900800)));
+        }
+        other => panic!("unexpected params: {:?}", other),
+    }
+}
+
+#[test]
+fn test_request_authenticated_credentials() {
+    let credentials = Credentials::new(BASE_URL, API_KEY).authenticate("secret", "session");
+
+    // authenticated credentials are accepted by session-free methods as well
+    let url = tag::GetInfo::builder("ethno").request(&credentials).get_url().unwrap();
+    assert!(!url.query().unwrap().contains("sk="));
+
+    let url = track::Love::builder("cure", "creep").request(&credentials).get_url().unwrap();
+    assert!(url.query().unwrap().contains("sk=session"));
+}
+
+#[test]
+fn test_request_errors() {
+    let credentials = Credentials::new(BASE_URL, API_KEY);
+
+    // raw requests bypass compile-time session checks
+    let love = Request::new(BASE_URL, API_KEY, None, None, track::Params::Love { artist: "cure", track: "creep" });
+    match love.get_url() {
+        Err(Error::MissingSession) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    match auth::GetToken::request(&credentials).get_url() {
+        Err(Error::MissingSecret) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    let credentials = Credentials::new("not a url", API_KEY);
+    match tag::GetInfo::request(&credentials, "ethno", None).to_http() {
+        Err(Error::InvalidBaseUrl(_)) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
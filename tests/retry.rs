@@ -0,0 +1,161 @@
+extern crate lastfm_parse_rs as lastfm;
+
+mod common;
+use common::{BASE_URL, API_KEY};
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use lastfm::{Client, Credentials, RetryPolicy};
+use lastfm::error::Error;
+use lastfm::rate_limit::MockClock;
+use lastfm::structs::api_error::ApiErrorKind;
+use lastfm::transport::InMemoryTransport;
+use lastfm::tag::{GetInfo, Params};
+use lastfm::structs::{auth, tag, track};
+
+
+static TAG_INFO: &str = r#"{ "tag": { "name": "ethno", "total": 10, "reach": 20 } }"#;
+static OPERATION_FAILED: &str = r#"{ "error": 8, "message": "Operation failed" }"#;
+
+fn millis(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+fn policy(max_attempts: u32, clock: &Arc<MockClock>) -> RetryPolicy {
+    RetryPolicy::new(max_attempts)
+        .with_jitter(false)
+        .with_clock(clock.clone())
+}
+
+#[test]
+fn test_retry_classification() {
+    assert!(ApiErrorKind::OperationFailed.is_retryable());
+    assert!(ApiErrorKind::ServiceOffline.is_retryable());
+    assert!(ApiErrorKind::ServiceTemporaryUnavailable.is_retryable());
+    assert!(ApiErrorKind::RateLimitExceeded.is_retryable());
+
+    assert!(!ApiErrorKind::InvalidParameters.is_retryable());
+    assert!(!ApiErrorKind::InvalidSessionKey.is_retryable());
+
+    assert!(Error::Status(503).is_retryable());
+    assert!(!Error::Status(404).is_retryable());
+    assert!(!Error::MissingSecret.is_retryable());
+}
+
+#[test]
+fn test_retry_policy_attempts() {
+    let policy = RetryPolicy::new(3).with_method("track.getInfo", 5);
+    let err = Error::Status(503);
+    let get_tag = tag::Params::GetInfo { tag: "ethno", lang: None };
+    let get_track = track::Params::GetInfo { track: ("iamthemorning", "os lunatum").into(), username: None, autocorrect: None };
+
+    assert_eq!(policy.max_attempts(&get_tag), 3);
+    assert_eq!(policy.max_attempts(&get_track), 5);
+    assert!(policy.should_retry(&get_tag, 2, &err));
+    assert!(!policy.should_retry(&get_tag, 3, &err));
+    assert!(!policy.should_retry(&get_tag, 1, &Error::Status(400)));
+
+    // Signed methods are only retried when enabled explicitly
+    let love = track::Params::Love { artist: "cure", track: "creep" };
+    let get_session = auth::Params::GetSession { token: "token" };
+    assert!(!policy.should_retry(&love, 1, &err));
+    assert!(!policy.should_retry(&get_session, 1, &err));
+    let policy = policy.with_method("track.love", 2);
+    assert!(policy.should_retry(&love, 1, &err));
+}
+
+#[test]
+fn test_retry_policy_delay() {
+    let policy = RetryPolicy::new(10)
+        .with_backoff(millis(100), millis(1000))
+        .with_jitter(false);
+
+    assert_eq!(policy.delay(1), millis(100));
+    assert_eq!(policy.delay(2), millis(200));
+    assert_eq!(policy.delay(4), millis(800));
+    assert_eq!(policy.delay(5), millis(1000));
+    assert_eq!(policy.delay(100), millis(1000));
+
+    let policy = policy.with_jitter(true);
+    for _ in 0..100 {
+        let delay = policy.delay(3);
+        assert!(delay >= millis(200) && delay <= millis(400));
+    }
+}
+
+#[test]
+fn test_retry_client() {
+    let transport = InMemoryTransport::new();
+    transport.push_response(500, OPERATION_FAILED);
+    transport.push_response(500, OPERATION_FAILED);
+    transport.push_response(200, TAG_INFO);
+
+    let clock = Arc::new(MockClock::new());
+    let client = Client::new(&transport, Credentials::new(BASE_URL, API_KEY))
+        .with_retry_policy(policy(3, &clock));
+
+    let mut buffer = Vec::new();
+    let data: GetInfo = client.call(Params::GetInfo { tag: "ethno", lang: None }, &mut buffer).unwrap();
+
+    assert_eq!(data.name, "ethno");
+    assert_eq!(transport.requests().len(), 3);
+    assert_eq!(clock.sleeps(), vec![millis(500), millis(1000)]);
+}
+
+#[test]
+fn test_retry_client_gives_up() {
+    let transport = InMemoryTransport::new();
+    transport.push_response(500, OPERATION_FAILED);
+    transport.push_response(500, OPERATION_FAILED);
+    transport.push_response(200, TAG_INFO);
+
+    let clock = Arc::new(MockClock::new());
+    let client = Client::new(&transport, Credentials::new(BASE_URL, API_KEY))
+        .with_retry_policy(policy(2, &clock));
+
+    let mut buffer = Vec::new();
+    let res: lastfm::Result<GetInfo> = client.call(Params::GetInfo { tag: "ethno", lang: None }, &mut buffer);
+
+    match res {
+        Err(Error::Api(err)) => assert_eq!(err.error, ApiErrorKind::OperationFailed),
+        other => panic!("unexpected result: {:?}", other),
+    }
+    assert_eq!(transport.requests().len(), 2);
+}
+
+#[test]
+fn test_retry_client_permanent_error() {
+    let transport = InMemoryTransport::new();
+    transport.push_response(400, r#"{ "error": 6, "message": "Tag not found" }"#);
+    transport.push_response(200, TAG_INFO);
+
+    let clock = Arc::new(MockClock::new());
+    let client = Client::new(&transport, Credentials::new(BASE_URL, API_KEY))
+        .with_retry_policy(policy(3, &clock));
+
+    let mut buffer = Vec::new();
+    let res: lastfm::Result<GetInfo> = client.call(Params::GetInfo { tag: "?", lang: None }, &mut buffer);
+
+    assert!(res.is_err());
+    assert_eq!(transport.requests().len(), 1);
+    assert!(clock.sleeps().is_empty());
+}
+
+#[test]
+fn test_retry_client_api_error_with_success_status() {
+    let transport = InMemoryTransport::new();
+    transport.push_response(200, OPERATION_FAILED);
+    transport.push_response(200, TAG_INFO);
+
+    let clock = Arc::new(MockClock::new());
+    let client = Client::new(&transport, Credentials::new(BASE_URL, API_KEY))
+        .with_retry_policy(policy(3, &clock));
+
+    let mut buffer = Vec::new();
+    let data: GetInfo = client.call(Params::GetInfo { tag: "ethno", lang: None }, &mut buffer).unwrap();
+
+    assert_eq!(data.name, "ethno");
+    assert_eq!(transport.requests().len(), 2);
+    assert_eq!(clock.sleeps(), vec![millis(500)]);
+}
@@ -0,0 +1,97 @@
+extern crate lastfm_parse_rs as lastfm;
+extern crate serde_json;
+
+mod common;
+use common::{RECENT_TRACKS, SEARCH};
+
+use std::panic;
+
+use lastfm::{from_json_str, to_json_string, to_json_vec, to_clean_json_string, to_clean_json_value};
+use lastfm::error::ApiError;
+use lastfm::structs::common::with_clean_shape;
+use lastfm::album::Search;
+use lastfm::tag::GetInfo;
+use lastfm::track::Scrobble;
+use lastfm::user::GetRecentTracks;
+
+
+static SCROBBLE: &str = r##"{ "scrobbles": {
+    "@attr": { "accepted": 1, "ignored": 0 },
+    "scrobble": {
+        "artist": { "corrected": "0", "#text": "iamthemorning" },
+        "ignoredMessage": { "code": "0", "#text": "" },
+        "albumArtist": { "corrected": "0", "#text": "" },
+        "timestamp": "1513154253",
+        "album": { "corrected": "0" },
+        "track": { "corrected": "0", "#text": "touching ii" }
+    }
+} }"##;
+
+/// Checks that serialized object parses back and serializes the same way again
+macro_rules! roundtrip {
+    ($data_t:ident, $raw_json:expr) => {{
+        let data: $data_t = from_json_str($raw_json).unwrap();
+        let json = to_json_string(&data).unwrap();
+
+        let parsed: $data_t = from_json_str(&json).unwrap();
+        assert_eq!(to_json_string(&parsed).unwrap(), json);
+        json
+    }};
+}
+
+#[test]
+fn test_serialize_roundtrip() {
+    let json = roundtrip!(GetRecentTracks, RECENT_TRACKS);
+    assert!(json.starts_with(r#"{"recenttracks":{"track":[{"#));
+    assert!(json.contains(r#""loved":"1""#));
+    assert!(json.contains(r#""name":"Winter Is \"Never\"""#));
+
+    let json = roundtrip!(Search, SEARCH);
+    assert!(json.contains(r#""opensearch:totalResults":"1""#));
+
+    let json = roundtrip!(Scrobble, SCROBBLE);
+    assert!(json.contains(r#""code":"0""#));
+
+    roundtrip!(GetInfo, r#"{ "tag": { "name": "ethno", "total": 10, "reach": 20 } }"#);
+}
+
+#[test]
+fn test_serialize_vec() {
+    let data: GetInfo = from_json_str(r#"{ "tag": { "name": "ethno", "total": 10, "reach": 20 } }"#).unwrap();
+    let json = to_json_vec(&data).unwrap();
+    assert_eq!(&json[..], &br#"{"tag":{"name":"ethno","total":10,"reach":20,"wiki":null}}"#[..]);
+}
+
+#[test]
+fn test_serialize_clean() {
+    let data: GetRecentTracks = from_json_str(RECENT_TRACKS).unwrap();
+    let json = to_clean_json_value(&data).unwrap();
+
+    assert!(json.get("recenttracks").is_none());
+    assert_eq!(json["track"][0]["loved"], true);
+    assert_eq!(json["track"][0]["date"]["uts"], 1513157820);
+    assert_eq!(json["@attr"]["totalPages"], 1);
+
+    let track = &data.track.as_ref().unwrap()[0];
+    let json = to_clean_json_string(track).unwrap();
+    assert!(json.contains(r#""streamable":false"#));
+
+    // Clean shape doesn't leak into regular serialization
+    assert!(to_json_string(&data).unwrap().contains(r#""loved":"1""#));
+}
+
+#[test]
+fn test_serialize_clean_after_panic() {
+    let res = panic::catch_unwind(|| with_clean_shape(|| panic!("serializer failed")));
+    assert!(res.is_err());
+
+    let data: GetRecentTracks = from_json_str(RECENT_TRACKS).unwrap();
+    assert!(to_json_string(&data).unwrap().contains(r#""loved":"1""#));
+}
+
+#[test]
+fn test_serialize_api_error() {
+    let err: ApiError = serde_json::from_str(r#"{ "error": 29, "message": "Rate Limit Exceded" }"#).unwrap();
+    let json = serde_json::to_string(&err).unwrap();
+    assert_eq!(json, r#"{"error":29,"message":"Rate Limit Exceded"}"#);
+}
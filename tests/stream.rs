@@ -0,0 +1,183 @@
+extern crate lastfm_parse_rs as lastfm;
+
+use std::io::{self, Read};
+
+use lastfm::{stream_items, ItemStream};
+use lastfm::error::Error;
+use lastfm::structs::album;
+use lastfm::structs::user::{GetRecentTracks, GetWeeklyAlbumChart};
+
+
+fn track(name: &str, uts: &str) -> String {
+    format!(
+        r##"{{
+            "artist": {{ "name": "Iamthemorning", "url": "https://www.last.fm/music/Iamthemorning", "image": [] }},
+            "loved": "0",
+            "name": "{}",
+            "streamable": "0",
+            "album": {{ "#text": "Lighthouse", "mbid": "" }},
+            "url": "https://www.last.fm/music/Iamthemorning/_/Track",
+            "image": [{{ "#text": "", "size": "small" }}],
+            "date": {{ "uts": "{}", "#text": "13 Dec 2017, 09:37" }}
+        }}"##,
+        name,
+        uts
+    )
+}
+
+fn recent_tracks(tracks: &[String]) -> String {
+    format!(
+        r#"{{ "recenttracks": {{
+            "track": [{}],
+            "@attr": {{ "user": "xenzh", "page": "2", "perPage": "3", "totalPages": "5", "total": "14" }}
+        }} }}"#,
+        tracks.join(",")
+    )
+}
+
+/// Reader that hands out one byte at a time
+struct Trickle<'a>(&'a [u8]);
+
+impl<'a> Read for Trickle<'a> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self.0.split_first() {
+            Some((byte, rest)) if !buf.is_empty() => {
+                buf[0] = *byte;
+                self.0 = rest;
+                Ok(1)
+            }
+            _ => Ok(0),
+        }
+    }
+}
+
+#[test]
+fn test_stream_items() {
+    let json = recent_tracks(&[track("Os Lunatum", "1"), track("Libretto", "2"), track("Belighted", "3")]);
+    let mut stream: ItemStream<GetRecentTracks, _> = ItemStream::new(Trickle(json.as_bytes()));
+
+    let names: Vec<String> = stream.by_ref().map(|track| track.unwrap().name.into_owned()).collect();
+    assert_eq!(names, vec!["Os Lunatum", "Libretto", "Belighted"]);
+
+    assert_eq!(stream.list_path(), Some("recenttracks.track".to_owned()));
+    let page_info = stream.page_info().unwrap();
+    assert_eq!(page_info.page, 2);
+    assert_eq!(page_info.total, 14);
+    assert_eq!(page_info.user.as_ref().unwrap(), "xenzh");
+    assert!(stream.metadata().unwrap()["recenttracks"].get("track").is_none());
+}
+
+#[test]
+fn test_stream_items_bad_item() {
+    let json = recent_tracks(&[track("Os Lunatum", "1"), track("Libretto", "yesterday"), track("Belighted", "3")]);
+    let items: Vec<_> = stream_items::<GetRecentTracks>(json.as_bytes()).collect();
+
+    assert_eq!(items.len(), 3);
+    assert!(items[0].is_ok());
+    assert!(items[2].is_ok());
+    match items[1] {
+        Err(Error::Deserialize(ref err)) => {
+            assert_eq!(err.path(), Some("recenttracks.track[1].date.uts"));
+            assert!(err.snippet().unwrap().contains("yesterday"));
+        }
+        ref other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn test_stream_items_no_page_info() {
+    let json = r##"{ "weeklyalbumchart": {
+        "album": [
+            { "artist": { "#text": "Iamthemorning", "mbid": "" }, "mbid": "", "url": "https://www.last.fm/music/Iamthemorning/Lighthouse",
+              "name": "Lighthouse", "@attr": { "rank": "1" }, "playcount": "12" }
+        ]
+    } }"##;
+    let mut stream = stream_items::<GetWeeklyAlbumChart>(json.as_bytes());
+
+    assert_eq!(stream.by_ref().filter(Result::is_ok).count(), 1);
+    assert!(stream.page_info().is_none());
+    assert!(stream.metadata().is_some());
+}
+
+#[test]
+fn test_stream_items_api_error() {
+    let json = r#"{ "error": 6, "message": "User not found" }"#;
+    let mut stream = stream_items::<GetRecentTracks>(json.as_bytes());
+
+    assert!(matches!(stream.next(), Some(Err(Error::Api(_)))));
+    assert!(stream.next().is_none());
+}
+
+#[test]
+fn test_stream_items_truncated() {
+    let json = recent_tracks(&[track("Os Lunatum", "1"), track("Libretto", "2")]);
+    let mut stream = stream_items::<GetRecentTracks>(&json.as_bytes()[..json.len() / 2]);
+
+    assert!(stream.next().unwrap().is_ok());
+    assert!(matches!(stream.next(), Some(Err(Error::Deserialize(_)))));
+    assert!(stream.next().is_none());
+}
+
+#[test]
+fn test_stream_items_single_object() {
+    let json = format!(
+        r#"{{ "recenttracks": {{
+            "track": {},
+            "@attr": {{ "user": "xenzh", "page": "1", "perPage": "3", "totalPages": "1", "total": "1" }}
+        }} }}"#,
+        track("Os Lunatum", "1")
+    );
+    let mut stream = stream_items::<GetRecentTracks>(Trickle(json.as_bytes()));
+
+    let names: Vec<String> = stream.by_ref().map(|track| track.unwrap().name.into_owned()).collect();
+    assert_eq!(names, vec!["Os Lunatum"]);
+    assert_eq!(stream.list_path(), Some("recenttracks.track".to_owned()));
+    assert_eq!(stream.page_info().unwrap().total, 1);
+    assert!(stream.metadata().unwrap()["recenttracks"].get("track").is_none());
+
+    // Without arrays inside the item
+    let json = r##"{ "weeklyalbumchart": {
+        "album": { "artist": { "#text": "Iamthemorning", "mbid": "" }, "mbid": "",
+                   "url": "https://www.last.fm/music/Iamthemorning/Lighthouse",
+                   "name": "Lighthouse", "@attr": { "rank": "1" }, "playcount": "12" },
+        "@attr": { "user": "xenzh", "from": "1108296000", "to": "1108900800" }
+    } }"##;
+    let items: Vec<_> = stream_items::<GetWeeklyAlbumChart>(json.as_bytes()).collect();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].as_ref().unwrap().name, "Lighthouse");
+}
+
+#[test]
+fn test_stream_items_array_before_list() {
+    // Arrays that are not the list are kept as metadata, wherever they are
+    let json = format!(
+        r#"{{ "recenttracks": {{
+            "@attr": {{ "user": "xenzh", "page": "1", "perPage": "3", "totalPages": "1", "total": "2" }},
+            "links": ["https://www.last.fm/user/xenzh"],
+            "track": [{}]
+        }} }}"#,
+        [track("Os Lunatum", "1"), track("Libretto", "2")].join(",")
+    );
+    let mut stream = stream_items::<GetRecentTracks>(Trickle(json.as_bytes()));
+
+    let names: Vec<String> = stream.by_ref().map(|track| track.unwrap().name.into_owned()).collect();
+    assert_eq!(names, vec!["Os Lunatum", "Libretto"]);
+    assert_eq!(stream.page_info().unwrap().total, 2);
+    assert_eq!(stream.metadata().unwrap()["recenttracks"]["links"][0], "https://www.last.fm/user/xenzh");
+
+    // Opensearch list is nested one level deeper, next to the query
+    let json = r##"{ "results": {
+        "opensearch:Query": { "#text": "", "role": "request", "searchTerms": "lighthouse", "startPage": "1" },
+        "opensearch:totalResults": "1", "opensearch:startIndex": "0", "opensearch:itemsPerPage": "30",
+        "albummatches": { "album": [
+            { "name": "Lighthouse", "artist": "Iamthemorning", "url": "https://www.last.fm/music/Iamthemorning/Lighthouse",
+              "image": [{ "#text": "", "size": "small" }], "streamable": "0", "mbid": "" }
+        ] },
+        "@attr": { "for": "lighthouse" }
+    } }"##;
+    let mut stream = stream_items::<album::Search>(json.as_bytes());
+
+    let names: Vec<String> = stream.by_ref().map(|album| album.unwrap().name.into_owned()).collect();
+    assert_eq!(names, vec!["Lighthouse"]);
+    assert_eq!(stream.list_path(), Some("results.albummatches.album".to_owned()));
+}
@@ -2,11 +2,11 @@
 
 extern crate lastfm_parse_rs as lastfm;
 
-mod common;
-use common::test_fn;
+mod net;
+use net::test_fn;
 
 use lastfm::structs::tag::GetInfo;
-test_fn!(test_tag_getinfo, GetInfo, ["ethno"]);
+test_fn!(test_tag_getinfo, GetInfo, ["ethno", None]);
 
 //17.11.2017 -- always returns an empty list
 use lastfm::tag::GetSimilar;
@@ -0,0 +1,61 @@
+#![cfg(feature = "chrono")]
+
+extern crate lastfm_parse_rs as lastfm;
+extern crate serde_json;
+
+mod common;
+use common::{API_KEY, BASE_URL, RECENT_TRACKS};
+
+use lastfm::{from_json_str, Credentials, Timestamp, Timestamped, TimeRange};
+use lastfm::time::{from_unix, parse_text};
+use lastfm::track::ScrobbleTrack;
+use lastfm::structs::wiki::Wiki;
+use lastfm::user::{GetRecentTracks, GetWeeklyChartList, GetWeeklyTrackChart};
+
+
+static CHART_LIST: &str = r##"{ "weeklychartlist": {
+    "chart": [{ "#text": "", "from": "1108296000", "to": "1108900800" }],
+    "@attr": { "user": "xenzh" }
+} }"##;
+
+#[test]
+fn test_time_parse_text() {
+    let expected = from_unix(1513157820).unwrap();
+    assert_eq!(parse_text("13 Dec 2017, 09:37"), Some(expected));
+    assert_eq!(parse_text("1513157820"), Some(expected));
+    assert_eq!(parse_text("Wed, 13 Dec 2017 11:37:00 +0200"), Some(expected));
+    assert_eq!(parse_text("2017-12-13T09:37:00Z"), Some(expected));
+
+    assert_eq!(parse_text(""), None);
+    assert_eq!(parse_text("FIXME"), None);
+}
+
+#[test]
+fn test_time_response_dates() {
+    let data: GetRecentTracks = from_json_str(RECENT_TRACKS).unwrap();
+    let track = &data.track.unwrap()[0];
+    assert_eq!(track.date.as_ref().unwrap().utc(), from_unix(1513157820));
+
+    let data: GetWeeklyChartList = from_json_str(CHART_LIST).unwrap();
+    let (from, to) = data.chart.unwrap()[0].utc_range().unwrap();
+    assert_eq!((to - from).num_days(), 7);
+
+    let wiki: Wiki = serde_json::from_str(r#"{ "published": "13 Dec 2017, 09:37", "summary": "" }"#).unwrap();
+    assert_eq!(wiki.published_utc(), from_unix(1513157820));
+    assert_eq!(wiki.utc(), wiki.published_utc());
+}
+
+#[test]
+fn test_time_request_params() {
+    let credentials = Credentials::new(BASE_URL, API_KEY);
+    let from = from_unix(1108296000).unwrap();
+    let to = parse_text("20 Feb 2005, 12:00").unwrap();
+
+    let typed = GetWeeklyTrackChart::builder("xenzh").from(from).to(to).request(&credentials);
+    let raw = GetWeeklyTrackChart::builder("xenzh").from(Timestamp(1108296000)).to(Timestamp(1108900800)).request(&credentials);
+    assert_eq!(typed.get_url().unwrap(), raw.get_url().unwrap());
+
+    let scrobble = ScrobbleTrack::new("Iamthemorning".into(), "Os Lunatum".into(), from);
+    assert_eq!(scrobble.timestamp_utc, Timestamp(1108296000));
+    assert_eq!(scrobble.timestamp_utc.utc(), Some(from));
+}
@@ -2,8 +2,8 @@
 
 extern crate lastfm_parse_rs as lastfm;
 
-mod common;
-use common::test_fn;
+mod net;
+use net::test_fn;
 use lastfm::structs::user::{TaggingType, Period};
 
 
@@ -0,0 +1,87 @@
+extern crate lastfm_parse_rs as lastfm;
+
+mod common;
+use common::{API_KEY, BASE_URL};
+
+use lastfm::{Credentials, RequestParams, Timestamp};
+use lastfm::error::Error;
+use lastfm::structs::{artist, album, library, track, user};
+use lastfm::structs::common::Mbid;
+
+
+fn invalid_parameter<P: RequestParams>(params: P) -> &'static str {
+    match params.validate() {
+        Err(Error::InvalidParameter { name, .. }) => name,
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn test_validate_required() {
+    let params = artist::GetInfo::builder("").params();
+    assert_eq!(invalid_parameter(params), "artist");
+
+    let params = track::GetInfo::builder(("iamthemorning", " ")).params();
+    assert_eq!(invalid_parameter(params), "track");
+
+    let mbid = Mbid::new("bfcc6d75-a6a5-4bc6-8282-47aec8531818").unwrap();
+    let params = artist::GetInfo::builder(mbid).params();
+    assert!(params.validate().is_ok());
+}
+
+#[test]
+fn test_validate_mbid() {
+    assert!(Mbid::new("BFCC6D75-a6a5-4bc6-8282-47aec8531818").is_ok());
+
+    for mbid in &["", "bfcc6d75", "bfcc6d75-a6a5-4bc6-8282-47aec853181g", "bfcc6d75a6a54bc6828247aec8531818"] {
+        match Mbid::new(*mbid) {
+            Err(Error::InvalidParameter { name, .. }) => assert_eq!(name, "mbid"),
+            other => panic!("unexpected result for {:?}: {:?}", mbid, other),
+        }
+    }
+}
+
+#[test]
+fn test_validate_limit_page() {
+    let params = library::GetArtists::builder("xenzh").limit(5000).params();
+    assert_eq!(invalid_parameter(params), "limit");
+
+    let params = library::GetArtists::builder("xenzh").page(0).params();
+    assert_eq!(invalid_parameter(params), "page");
+
+    let params = user::GetRecentTracks::builder("xenzh").limit(500).params();
+    assert_eq!(invalid_parameter(params), "limit");
+
+    let params = library::GetArtists::builder("xenzh").limit(1000).page(1).params();
+    assert!(params.validate().is_ok());
+}
+
+#[test]
+fn test_validate_range() {
+    let params = user::GetRecentTracks::builder("xenzh").from(Timestamp(20)).to(Timestamp(10)).params();
+    assert_eq!(invalid_parameter(params), "from");
+
+    let params = user::GetRecentTracks::builder("xenzh").from(Timestamp(10)).to(Timestamp(20)).params();
+    assert!(params.validate().is_ok());
+}
+
+#[test]
+fn test_validate_tags() {
+    let params = album::Params::AddTags {
+        artist: "rome",
+        album: "the hyperion machine",
+        tags: "a,b,c,d,e,f,g,h,i,j,k",
+    };
+    assert_eq!(invalid_parameter(params), "tags");
+}
+
+#[test]
+fn test_validate_get_url() {
+    let credentials = Credentials::new(BASE_URL, API_KEY);
+    let rq = user::GetTopTags::builder("xenzh").limit(0).request(&credentials);
+
+    match rq.get_url() {
+        Err(Error::InvalidParameter { name: "limit", .. }) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
@@ -0,0 +1,75 @@
+extern crate lastfm_parse_rs as lastfm;
+extern crate serde_json;
+
+use std::fs;
+
+use lastfm::from_json_str;
+use lastfm::structs::wiki::{self, Anchor, Wiki};
+use lastfm::album;
+use lastfm::structs::artist::Bio;
+
+
+static BIO: &str = r##"{
+    "links": { "link": { "#text": "", "rel": "original", "href": "https://last.fm/music/Iamthemorning/+wiki" } },
+    "published": "10 Feb 2012, 15:41",
+    "summary": "Iamthemorning is a duo from <a href=\"https://www.last.fm/tag/saint+petersburg\" class=\"bbcode_tag\">St. Petersburg</a> &amp; friends. <a href=\"https://www.last.fm/music/Iamthemorning/+wiki\">Read more on Last.fm</a>",
+    "content": "Iamthemorning is a duo from <a href=\"https://www.last.fm/tag/saint+petersburg\" class=\"bbcode_tag\">St. Petersburg</a> &amp; friends.\n\nSee also <a href='https://www.last.fm/music/Gazpacho'>Gazpacho</a>. <a href=\"https://www.last.fm/music/Iamthemorning/+wiki\">Read more on Last.fm</a>. User-contributed text is available under the Creative Commons By-SA License; additional terms may apply."
+}"##;
+
+fn bio() -> Bio<'static> {
+    serde_json::from_str::<Wiki>(BIO).unwrap().into_owned()
+}
+
+#[test]
+fn test_wiki_raw_fields() {
+    let bio = bio();
+    assert_eq!(bio.published.as_ref().unwrap(), "10 Feb 2012, 15:41");
+    assert!(bio.summary.ends_with("Read more on Last.fm</a>"));
+    assert!(bio.content.as_ref().unwrap().contains("Creative Commons"));
+    assert_eq!(bio.links.as_ref().unwrap().link.as_ref().unwrap().rel, "original");
+}
+
+#[test]
+fn test_wiki_text() {
+    let bio = bio();
+    assert_eq!(bio.summary_text(), "Iamthemorning is a duo from St. Petersburg & friends.");
+    assert_eq!(
+        bio.content_text(),
+        "Iamthemorning is a duo from St. Petersburg & friends.\n\nSee also Gazpacho."
+    );
+    assert_eq!(wiki::strip_footer("No footer here  "), "No footer here");
+    assert_eq!(wiki::to_text("1 &lt; 2 &#8212; &#x263A; &unknown; &"), "1 < 2 \u{2014} \u{263a} &unknown; &");
+}
+
+#[test]
+fn test_wiki_markdown_and_anchors() {
+    let bio = bio();
+    assert_eq!(
+        bio.summary_markdown(),
+        "Iamthemorning is a duo from [St. Petersburg](https://www.last.fm/tag/saint+petersburg) & friends."
+    );
+    assert_eq!(bio.anchors(), vec![
+        Anchor { text: "St. Petersburg".into(), href: "https://www.last.fm/tag/saint+petersburg".into() },
+        Anchor { text: "Gazpacho".into(), href: "https://www.last.fm/music/Gazpacho".into() },
+    ]);
+
+    assert_eq!(
+        wiki::to_markdown(r#"Album *Lighthouse* [2015] by <a href="https://www.last.fm/music/I_am">i_am [live]</a>, see `notes`"#),
+        r"Album \*Lighthouse\* \[2015\] by [i\_am \[live\]](https://www.last.fm/music/I_am), see \`notes\`"
+    );
+    assert_eq!(wiki::to_text("*Lighthouse*"), "*Lighthouse*");
+
+    let html = r#"<a data-href="https://example.com/tracking" href="https://www.last.fm/music/Gazpacho">Gazpacho</a>"#;
+    assert_eq!(wiki::anchors(html)[0].href, "https://www.last.fm/music/Gazpacho");
+}
+
+#[test]
+fn test_wiki_album() {
+    let json = fs::read_to_string("tests/fixtures/album.getinfo/lighthouse.json").unwrap();
+    let data: album::GetInfo = from_json_str(&json).unwrap();
+    let wiki = data.wiki.unwrap();
+
+    assert_eq!(wiki.published.as_ref().unwrap(), "13 Dec 2017, 09:17");
+    assert_eq!(wiki.content_text(), "Lighthouse is the third studio album by Iamthemorning.");
+    assert!(wiki.links.is_none());
+}
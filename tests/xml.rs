@@ -0,0 +1,200 @@
+#![cfg(feature = "xml")]
+
+extern crate lastfm_parse_rs as lastfm;
+
+mod common;
+use common::{API_KEY, BASE_URL};
+
+use std::borrow::Cow;
+
+use lastfm::{from_xml_str, from_xml_slice, from_xml_owned, Client, Credentials, Format};
+use lastfm::error::Error;
+use lastfm::structs::api_error::ApiErrorKind;
+use lastfm::transport::InMemoryTransport;
+use lastfm::album::Search;
+use lastfm::tag::{GetInfo, Params};
+use lastfm::user::GetRecentTracks;
+
+
+static RECENT_TRACKS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<lfm status="ok">
+<recenttracks user="xenzh" page="1" perPage="2" totalPages="10" total="20">
+  <track nowplaying="true">
+    <artist>
+      <name>Iamthemorning</name>
+      <mbid></mbid>
+      <url>https://www.last.fm/music/Iamthemorning</url>
+      <image size="small">https://lastfm-img2.akamaized.net/i/u/34s/1.png</image>
+    </artist>
+    <loved>1</loved>
+    <name>Os Lunatum</name>
+    <streamable>0</streamable>
+    <mbid></mbid>
+    <album mbid="">Lighthouse</album>
+    <url>https://www.last.fm/music/Iamthemorning/_/Os+Lunatum</url>
+    <image size="small">https://lastfm-img2.akamaized.net/i/u/34s/2.png</image>
+    <image size="medium">https://lastfm-img2.akamaized.net/i/u/64s/2.png</image>
+  </track>
+  <track>
+    <artist>
+      <name>Schtimm</name>
+      <mbid></mbid>
+      <url>https://www.last.fm/music/Schtimm</url>
+    </artist>
+    <loved>0</loved>
+    <name>Sunotic Drive</name>
+    <streamable>0</streamable>
+    <mbid></mbid>
+    <album mbid=""></album>
+    <url>https://www.last.fm/music/Schtimm/_/Sunotic+Drive</url>
+    <image size="small"></image>
+    <date uts="1513157820">13 Dec 2017, 09:37</date>
+  </track>
+</recenttracks>
+</lfm>"#;
+
+static SEARCH: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<lfm status="ok">
+<results for="lighthouse" xmlns:opensearch="http://a9.com/-/spec/opensearch/1.1/">
+  <opensearch:Query role="request" searchTerms="lighthouse" startPage="1" />
+  <opensearch:totalResults>1</opensearch:totalResults>
+  <opensearch:startIndex>0</opensearch:startIndex>
+  <opensearch:itemsPerPage>30</opensearch:itemsPerPage>
+  <albummatches>
+    <album>
+      <name>Lighthouse</name>
+      <artist>Iamthemorning</artist>
+      <url>https://www.last.fm/music/Iamthemorning/Lighthouse</url>
+      <image size="small">https://lastfm-img2.akamaized.net/i/u/34s/3.png</image>
+      <streamable>0</streamable>
+      <mbid></mbid>
+    </album>
+  </albummatches>
+</results>
+</lfm>"#;
+
+static TAG_INFO: &str = r#"<lfm status="ok">
+<tag>
+  <name>ethno</name>
+  <total>10</total>
+  <reach>20</reach>
+  <wiki>
+    <summary>Music of &quot;ethnic&quot; origin</summary>
+    <content>Plain content</content>
+  </wiki>
+</tag>
+</lfm>"#;
+
+static FAILED: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<lfm status="failed">
+  <error code="6">Tag not found</error>
+</lfm>"#;
+
+#[test]
+fn test_xml_recent_tracks() {
+    let data: GetRecentTracks = from_xml_str(RECENT_TRACKS).unwrap();
+
+    let page_info = data.page_info.unwrap();
+    assert_eq!(page_info.total_pages, 10);
+    assert_eq!(page_info.user.unwrap(), "xenzh");
+
+    let tracks = data.track.unwrap();
+    assert_eq!(tracks.len(), 2);
+    assert!(tracks[0].now.as_ref().unwrap().nowplaying);
+    assert!(tracks[0].loved);
+    assert_eq!(tracks[0].image.len(), 2);
+    assert_eq!(tracks[0].artist.image.len(), 1);
+    assert_eq!(tracks[0].album.name, "Lighthouse");
+    assert!(tracks[1].artist.image.is_empty());
+    assert_eq!(tracks[1].date.as_ref().unwrap().uts, Some(1513157820));
+}
+
+#[test]
+fn test_xml_search() {
+    let data: Search = from_xml_slice(SEARCH.as_bytes()).unwrap();
+
+    assert_eq!(data.query.search_terms.unwrap(), "lighthouse");
+    assert_eq!(data.total_results, Some(1));
+    assert_eq!(data.iterms_per_page, Some(30));
+
+    let albums = data.albummatches.unwrap().album.unwrap();
+    assert_eq!(albums.len(), 1);
+    assert_eq!(albums[0].artist, "Iamthemorning");
+}
+
+#[test]
+fn test_xml_entities() {
+    let data: GetInfo = from_xml_str(TAG_INFO).unwrap();
+    assert_eq!(data.total, 10);
+
+    let wiki = data.wiki.unwrap();
+    assert_eq!(wiki.summary, "Music of \"ethnic\" origin");
+    assert!(matches!(wiki.summary, Cow::Owned(_)));
+    assert!(matches!(wiki.content, Some(Cow::Borrowed("Plain content"))));
+}
+
+#[test]
+fn test_xml_owned() {
+    let body = TAG_INFO.as_bytes().to_vec();
+    let data: GetInfo<'static> = from_xml_owned(&body).unwrap();
+    drop(body);
+    assert_eq!(data.name, "ethno");
+}
+
+#[test]
+fn test_xml_api_error() {
+    let res: lastfm::Result<GetInfo> = from_xml_str(FAILED);
+    match res {
+        Err(Error::Api(err)) => {
+            assert_eq!(err.error, ApiErrorKind::InvalidParameters);
+            assert_eq!(err.message, "Tag not found");
+        }
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    let res: lastfm::Result<GetInfo> = from_xml_str("<lfm status=\"ok\"><tag>");
+    assert!(matches!(res, Err(Error::Xml(_))));
+}
+
+#[test]
+fn test_xml_client() {
+    let transport = InMemoryTransport::new();
+    transport.push_response(200, TAG_INFO);
+    transport.push_response(400, FAILED);
+
+    let client = Client::new(&transport, Credentials::new(BASE_URL, API_KEY))
+        .with_format(Format::Xml);
+
+    let mut buffer = Vec::new();
+    let data: GetInfo = client.call(Params::GetInfo { tag: "ethno", lang: None }, &mut buffer).unwrap();
+    assert_eq!(data.reach, 20);
+
+    let query = transport.requests()[0].url.query().unwrap().to_owned();
+    assert!(!query.contains("format="));
+
+    let res: lastfm::Result<GetInfo<'static>> = client.call_owned(Params::GetInfo { tag: "?", lang: None });
+    match res {
+        Err(Error::Api(err)) => assert_eq!(err.error, ApiErrorKind::InvalidParameters),
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn test_xml_client_retries_failed_status() {
+    use std::sync::Arc;
+    use lastfm::RetryPolicy;
+    use lastfm::rate_limit::MockClock;
+
+    let transport = InMemoryTransport::new();
+    transport.push_response(200, "<lfm status='failed'><error code='8'>Operation failed</error></lfm>");
+    transport.push_response(200, TAG_INFO);
+
+    let policy = RetryPolicy::new(3).with_jitter(false).with_clock(Arc::new(MockClock::new()));
+    let client = Client::new(&transport, Credentials::new(BASE_URL, API_KEY))
+        .with_format(Format::Xml)
+        .with_retry_policy(policy);
+
+    let data: GetInfo<'static> = client.call_owned(Params::GetInfo { tag: "ethno", lang: None }).unwrap();
+    assert_eq!(data.reach, 20);
+    assert_eq!(transport.requests().len(), 2);
+}